@@ -1,6 +1,6 @@
 use std::{
     borrow::Borrow,
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     fmt::Debug,
     hash::Hash, ops::{Index, RangeFrom, RangeTo}
 };
@@ -364,6 +364,12 @@ pub struct M2<K1, K2, V>(pub HashMap<K1, HashMap<K2, V>>);
 pub struct MV<K, V>(pub HashMap<K, Vec<V>>);
 
 
+/// Key ordered MV, `bmv[k]` is an empty vec for unknown k
+#[derive(Default, Debug, Clone)]
+#[repr(transparent)]
+pub struct BMV<K, V>(pub BTreeMap<K, Vec<V>>);
+
+
 #[derive(Default, Debug, Clone)]
 #[repr(transparent)]
 pub struct MS<K, V>(pub HashMap<K, HashSet<V>>);
@@ -735,6 +741,90 @@ impl<K: Debug, V: Debug> Debug for MV<K, V> {
 }
 
 
+////////////////////////////////////////
+//// Implementation BMV
+
+impl<K, V> BMV<K, V> {
+    pub fn new() -> Self {
+        Self(BTreeMap::<K, Vec<V>>::new())
+    }
+}
+
+/// Look like `Vec<Vec<V>>` indexed by `0..len`
+impl<V> BMV<usize, V> {
+    /// max key + 1
+    pub fn len(&self) -> usize {
+        self.0.last_key_value().map_or(0, |(k, _)| k + 1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// vec of each key in `0..len`, empty for the absent
+    pub fn iter(&self) -> impl Iterator<Item = &Vec<V>> {
+        let mut it = self.0.iter().peekable();
+
+        (0..self.len()).map(move |i| match it.next_if(|(k, _)| **k == i) {
+            Some((_, vs)) => vs,
+            None => const { &Vec::new() },
+        })
+    }
+}
+
+impl<K: Ord, V> Index<K> for BMV<K, V> {
+    type Output = Vec<V>;
+
+    fn index(&self, k: K) -> &Self::Output {
+        self.0.get(&k).unwrap_or(const { &Vec::new() })
+    }
+}
+
+impl<K, V> EasyCollGet<K, Vec<V>> for BMV<K, V>
+where
+    K: Ord,
+    V: Clone,
+{
+    type Target = Vec<V>;
+
+    fn get<Q: Borrow<K>>(&self, k: &Q) -> Option<Self::Target> {
+        self.0.get(k.borrow()).cloned()
+    }
+}
+
+impl<K, V> EasyCollGet<K, Vec<V>> for &BMV<K, V>
+where
+    K: Ord,
+    V: Clone,
+{
+    type Target = Vec<V>;
+
+    fn get<Q: Borrow<K>>(&self, k: &Q) -> Option<Self::Target> {
+        EasyCollGet::get(*self, k)
+    }
+}
+
+impl<K, V> EasyCollInsert<K, Vec<V>> for BMV<K, V>
+where
+    K: Ord,
+{
+    type Target = Vec<V>;
+
+    fn insert(&mut self, k: K, v: Vec<V>) -> Option<Self::Target> {
+        self.0.insert(k, v)
+    }
+}
+
+impl<K, V> EasyCollAPush<K, V> for BMV<K, V>
+where
+    K: Ord,
+{
+    fn apush(&mut self, k: K, v: V) {
+        self.0.entry(k).or_default().push(v)
+    }
+}
+
+
 ////////////////////////////////////////
 //// Implementation MS
 
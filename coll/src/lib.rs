@@ -8,6 +8,7 @@
 pub mod fenwick_tree;
 pub mod frac_casc;
 pub mod segment_tree;
+pub mod sparse_table;
 pub mod easycoll;
 pub mod union_find;
 pub mod aux;
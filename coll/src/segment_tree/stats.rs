@@ -2,7 +2,7 @@ use std::{
     cmp::{Ordering::*, *},
     iter::Sum,
     marker::PhantomData,
-    ops::{Add, AddAssign, Range, RangeBounds, Sub},
+    ops::{Add, AddAssign, Mul, Range, RangeBounds, Sub},
 };
 
 use math::gcd_rem as gcd;
@@ -35,6 +35,16 @@ pub struct RangeSum<T>(PhantomData<T>);
 #[repr(transparent)]
 pub struct RangeMax<T>(T);
 
+/// (sum, len), range sum with range add by `UpdaterAdd`
+///
+/// Lazy addend is created by `RangeAddSum::addend` (len == 0),
+/// it's added `len` times onto a segment
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct RangeAddSum<T> {
+    pub sum: T,
+    len: usize,
+}
+
 /// (max value, max value count)
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct RangeMaxStats<T> {
@@ -352,6 +362,75 @@ impl<'a, T: Ord + Clone> AddAssign<&'a Self> for RangeMaxStats<T> {
     }
 }
 
+impl<T> RangeAddSum<T> {
+    pub fn addend(value: T) -> Self {
+        Self { sum: value, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<T> From<T> for RangeAddSum<T> {
+    fn from(value: T) -> Self {
+        Self { sum: value, len: 1 }
+    }
+}
+
+impl<T: Sum + Add<Output = T>> Sum for RangeAddSum<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(|acc, x| acc + x)
+            .unwrap_or(Self { sum: zero!(), len: 0 })
+    }
+}
+
+impl<T: Add<Output = T>> Add for RangeAddSum<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            sum: self.sum + rhs.sum,
+            len: self.len + rhs.len,
+        }
+    }
+}
+
+impl<'a, T: Clone + Add<Output = T>> Add for &'a RangeAddSum<T> {
+    type Output = RangeAddSum<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        RangeAddSum {
+            sum: self.sum.clone() + rhs.sum.clone(),
+            len: self.len + rhs.len,
+        }
+    }
+}
+
+impl<'a, T> AddAssign<&'a Self> for RangeAddSum<T>
+where
+    T: Clone + Add<Output = T> + Mul<Output = T> + TryFrom<usize>,
+{
+    /// apply addend (rhs) on the segment (self),
+    /// addend on addend (len == 0) is just compose
+    fn add_assign(&mut self, rhs: &'a Self) {
+        let times = if self.len == 0 { 1 } else { self.len };
+        let times = T::try_from(times).ok().expect("len overflow");
+
+        self.sum = self.sum.clone() + rhs.sum.clone() * times;
+    }
+}
+
+impl<T: PartialEq> PartialEq<T> for RangeAddSum<T> {
+    fn eq(&self, other: &T) -> bool {
+        self.sum.eq(other)
+    }
+}
+
 impl<T> From<T> for RangeMax<T> {
     fn from(value: T) -> Self {
         Self(value)
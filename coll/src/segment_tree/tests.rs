@@ -265,6 +265,34 @@ fn test_segment_tree_max_nth_updater() {
 }
 
 
+#[test]
+fn test_segment_tree_add_sum_updater() {
+    for mut arr in gen_arr!(N - i64) {
+        let mut st = SegmentTree::<RangeAddSum<i64>>::new(&arr);
+        let mut updater = st.create_updater();
+
+        for q in gen_query!(50, arr.len()) {
+            let addend = random_range!(-50..50) as i64;
+
+            for i in q.clone() {
+                arr[i] += addend;
+            }
+
+            updater.assoc(&mut st, q, RangeAddSum::addend(addend));
+
+            for q2 in gen_query!(50, arr.len()) {
+                let expect: i64 = arr[q2.clone()].iter().sum();
+
+                let res = updater.query(&mut st, q2.clone());
+
+                assert_eq!(res, expect, "res / expect");
+                assert_eq!(res.len(), q2.len());
+            }
+        }
+    }
+}
+
+
 #[test]
 fn test_segment_tree_max_stats() {
     for mut arr in gen_arr!(N) {
@@ -0,0 +1,109 @@
+//! Sparse table for idempotent range query (RMQ)
+//!
+
+use std::ops::{Add, Range, RangeBounds};
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Structures
+
+/// O(nlogn) build, O(1) query, static
+///
+/// Like `SegmentTree`, T combines by `&T + &T`, which should be idempotent
+/// (x + x = x), e.g. `RangeMax`, `RangeGCD`, since query overlaps two blocks.
+#[derive(Debug, Clone)]
+pub struct SparseTable<T> {
+    /// data[k][i]: [i, i + 2^k)
+    data: Vec<Vec<T>>,
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Implementations
+
+impl<T> SparseTable<T> {
+    pub fn len(&self) -> usize {
+        self.data[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> SparseTable<T>
+where
+    T: Clone,
+    for<'a> &'a T: Add<&'a T, Output = T>,
+{
+    pub fn new<U: Clone + Into<T>>(raw: &[U]) -> Self {
+        assert!(!raw.is_empty());
+
+        let n = raw.len();
+        let mut data: Vec<Vec<T>> =
+            vec![raw.iter().cloned().map(Into::into).collect()];
+
+        for k in 1..=n.ilog2() as usize {
+            let half = 1 << (k - 1);
+            let prev = &data[k - 1];
+
+            let row = (0..=n - (1 << k))
+                .map(|i| &prev[i] + &prev[i + half])
+                .collect();
+
+            data.push(row);
+        }
+
+        Self { data }
+    }
+
+    /// Panic if range is empty
+    pub fn query<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let Range { start, end } = std::slice::range(range, ..self.len());
+
+        assert!(start < end, "empty range {start}..{end}");
+
+        let k = (end - start).ilog2() as usize;
+
+        &self.data[k][start] + &self.data[k][end - (1 << k)]
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use crate::segment_tree::{
+        tests::{gen_arr, gen_query},
+        RangeGCD, RangeMax,
+    };
+    use math::gcd_rem as gcd;
+
+    use super::*;
+
+
+    #[test]
+    fn test_sparse_table_max_gcd() {
+        for arr in gen_arr!(200, 1..500, 1..1000, usize) {
+            let st_max = SparseTable::<RangeMax<usize>>::new(&arr);
+            let st_gcd = SparseTable::<RangeGCD<usize>>::new(&arr);
+
+            assert_eq!(st_max.len(), arr.len());
+
+            for q in gen_query!(arr.len()) {
+                let expect = arr[q.clone()].iter().max().cloned().unwrap();
+
+                assert_eq!(st_max.query(q.clone()), expect, "res / expect");
+
+                let expect =
+                    arr[q.clone()].iter().fold(0, |acc, &x| gcd!(acc, x));
+
+                assert_eq!(
+                    st_gcd.query(q.clone()),
+                    RangeGCD::from(expect),
+                    "res / expect"
+                );
+            }
+        }
+    }
+}
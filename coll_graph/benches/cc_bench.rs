@@ -2,8 +2,12 @@
 
 extern crate test;
 
-use common::random;
-use m6_coll_graph::{ test::GraphGenOptions, Graph, scc::scc_tarjan};
+use common::{random, rngs::StdRng, SeedableRng};
+use m6_coll_graph::{
+    test::{model::{gen_barabasi_albert, gen_grid}, GraphGenOptions},
+    Graph,
+    scc::scc_tarjan,
+};
 use test::{Bencher, black_box};
 
 const BATCH_NUM: usize = 0_100;
@@ -24,6 +28,10 @@ lazy_static::lazy_static! {
     pub static ref HIGH_DENSITY_GRAPHS: Vec<Graph> = {
         prepare_high_density_graphs()
     };
+
+    pub static ref TOPOLOGY_GRAPHS: Vec<Graph> = {
+        prepare_topology_graphs()
+    };
 }
 
 
@@ -55,6 +63,20 @@ fn prepare_high_density_graphs() -> Vec<Graph> {
 }
 
 
+/// grid and scale-free graphs
+fn prepare_topology_graphs() -> Vec<Graph> {
+    let rng = &mut StdRng::seed_from_u64(0);
+    let mut res = vec![];
+
+    for _ in 0..BATCH_NUM / 2 {
+        res.push(gen_grid(V_RANGE / 2, V_RANGE, false, 1..2, rng));
+        res.push(gen_barabasi_albert(V_RANGE * 5, 2, 1..2, rng));
+    }
+
+    res
+}
+
+
 #[bench]
 fn bench_undircc_lowdensity_msu(b: &mut Bencher) {
     let gs = &*LOW_DENSITY_GRAPHS;
@@ -98,3 +120,25 @@ fn bench_undircc_highdensity_tarjan(b: &mut Bencher) {
         }
     })
 }
+
+#[bench]
+fn bench_undircc_topology_msu(b: &mut Bencher) {
+    let gs = &*TOPOLOGY_GRAPHS;
+
+    b.iter(|| {
+        for g in gs.into_iter() {
+            black_box(g.components());
+        }
+    })
+}
+
+#[bench]
+fn bench_undircc_topology_tarjan(b: &mut Bencher) {
+    let gs = &*TOPOLOGY_GRAPHS;
+
+    b.iter(|| {
+        for g in gs.into_iter() {
+            black_box(scc_tarjan(g));
+        }
+    })
+}
@@ -1,17 +1,44 @@
 //! Biconnected Components (undirected graph)
 
-use std::collections::HashSet;
+use std::{
+    cmp::min,
+    collections::{HashMap, HashSet},
+};
 
 use coll::{
     aux::{VerifyError, VerifyResult},
-    get,
+    get, set,
 };
 use common::{ Itertools, ordered_insert };
 
-use crate::Graph;
+use crate::{Graph, Vertex, Weight};
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Structures
+
+/// Block-cut tree (forest), block i is blocks[i]
+#[derive(Debug, Clone)]
+pub struct BlockCutTree<V: Vertex> {
+    pub tree: Graph<BCNode<V>>,
+    /// the same as `bcc_tarjan`
+    pub blocks: Vec<Vec<(V, V)>>,
+}
+
+
+/// Vertex of block-cut tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BCNode<V> {
+    /// index of block
+    Block(usize),
+    Cut(V),
+}
+
 
+////////////////////////////////////////////////////////////////////////////////
+//// Implementations
 
-impl Graph {
+impl<V: Vertex, W: Weight> Graph<V, W> {
     /// Verify bccs for undirected graph
     ///
     /// 1. 边集不交且为合集
@@ -22,7 +49,7 @@ impl Graph {
     ///
     pub fn verify_undir_bccs(
         &self,
-        bccs: &[Vec<(usize, usize)>],
+        bccs: &[Vec<(V, V)>],
     ) -> VerifyResult {
         debug_assert!(!self.is_dir);
 
@@ -31,14 +58,14 @@ impl Graph {
         let all_edges = self
             .edges()
             .map(|(u, v, _)| if u < v { (u, v) } else { (v, u) })
-            .collect::<HashSet<(usize, usize)>>();
+            .collect::<HashSet<(V, V)>>();
 
         let mut acc = HashSet::new();
 
         for bcc in bccs {
             let bcc_set = HashSet::from_iter(bcc.clone());
 
-            let intersection: Vec<(usize, usize)> =
+            let intersection: Vec<(V, V)> =
                 acc.intersection(&bcc_set).cloned().collect();
 
             if !intersection.is_empty() {
@@ -67,7 +94,7 @@ impl Graph {
         for bcc in bccs {
             let mut cuts = vec![];
 
-            let g_bcc = Graph::from_undirected_iter(
+            let g_bcc = Graph::<V, isize>::from_undirected_iter(
                 bcc.into_iter().cloned().map(|(u, v)| (u, v, 1)),
             );
 
@@ -89,20 +116,20 @@ impl Graph {
         /* 3. ensure intersection vertexs are articulation points */
 
         let raw_components = self.components().len();
-        let mut excluded = vec![false; self.e.len()];
+        let mut excluded = HashSet::new();
 
         for bcc in bccs {
             if bcc.len() == 1 {
-                excluded[bcc[0].0] = true;
-                excluded[bcc[0].1] = true;
+                excluded.insert(bcc[0].0);
+                excluded.insert(bcc[0].1);
             }
         }
 
         for (i, bcc) in bccs.iter().enumerate() {
-            let bcc_edges_set: HashSet<(usize, usize)> =
+            let bcc_edges_set: HashSet<(V, V)> =
                 bcc.iter().cloned().collect();
 
-            let rem_edges_set: HashSet<(usize, usize)> = self
+            let rem_edges_set: HashSet<(V, V)> = self
                 .edges()
                 .map(|(u, v, _)| (u, v))
                 .filter(|(u, v)| {
@@ -111,17 +138,17 @@ impl Graph {
                 })
                 .collect();
 
-            let rem_vertexs_set: HashSet<usize> = rem_edges_set
+            let rem_vertexs_set: HashSet<V> = rem_edges_set
                 .iter()
                 .cloned()
                 .flat_map(|(u, v)| [u, v])
                 .collect();
 
-            let bcc_vertexs_set: HashSet<usize> =
+            let bcc_vertexs_set: HashSet<V> =
                 g_bccs[i].vertexs().collect();
 
             for v in bcc_vertexs_set.intersection(&rem_vertexs_set).cloned() {
-                if !excluded[v] {
+                if !excluded.contains(&v) {
                     let g1 = Graph::from_undirected_iter(
                         self.edges()
                             .filter(|(u1, v1, _)| *u1 != v && *v1 != v),
@@ -133,7 +160,7 @@ impl Graph {
                         )));
                     }
 
-                    excluded[v] = true;
+                    excluded.insert(v);
                 }
             }
         }
@@ -141,7 +168,7 @@ impl Graph {
         Ok(())
     }
 
-    pub fn test_cut_vertex(&self, v: usize) -> bool {
+    pub fn test_cut_vertex(&self, v: V) -> bool {
         let g1_edges = self
             .edges()
             .filter(|(u1, v1, _)| *u1 != v && *v1 != v);
@@ -152,15 +179,208 @@ impl Graph {
             Graph::from_undirected_iter(g1_edges)
         };
 
-        self.components().len() < g1.components().len()
+        // neighbors become orphan and disappear from g1
+        let lost =
+            self.vertexs().filter(|&u| u != v).count() - g1.vertexs().count();
+
+        self.components().len() < g1.components().len() + lost
+    }
+
+    /// Edge (u, v) is bridge (undirected)
+    pub fn test_bridge(&self, (u, v): (V, V)) -> bool {
+        debug_assert!(!self.is_dir);
+
+        let g1 = Graph::from_undirected_iter(
+            self.edges().filter(|&(u1, v1, _)| {
+                (u1, v1) != (u, v) && (u1, v1) != (v, u)
+            }),
+        );
+
+        let lost = self.vertexs().count() - g1.vertexs().count();
+
+        self.components().len() < g1.components().len() + lost
+    }
+
+    /// Verify cut vertexs for undirected graph (by definition)
+    pub fn verify_articulation_points(&self, cuts: &[V]) -> VerifyResult {
+        debug_assert!(!self.is_dir);
+
+        let cuts_set: HashSet<V> = cuts.iter().cloned().collect();
+
+        if cuts_set.len() != cuts.len() {
+            return Err(VerifyError::Inv(format!("Duplicated cuts {cuts:?}")));
+        }
+
+        let vertexs: HashSet<V> = self.vertexs().collect();
+
+        if let Some(v) = cuts_set.difference(&vertexs).next() {
+            return Err(VerifyError::Inv(format!("Unknown vertex {v:?}")));
+        }
+
+        for v in vertexs {
+            if self.test_cut_vertex(v) != cuts_set.contains(&v) {
+                return Err(VerifyError::Fail(format!(
+                    "Wrong cut point {v:?} (expect {})",
+                    self.test_cut_vertex(v)
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verify cut edges for undirected graph (by definition)
+    pub fn verify_bridges(&self, bridges: &[(V, V)]) -> VerifyResult {
+        debug_assert!(!self.is_dir);
+
+        let bridges_set: HashSet<(V, V)> =
+            bridges.iter().map(|&(u, v)| norm_edge(u, v)).collect();
+
+        if bridges_set.len() != bridges.len() {
+            return Err(VerifyError::Inv(format!(
+                "Duplicated bridges {bridges:?}"
+            )));
+        }
+
+        let edges: HashSet<(V, V)> =
+            self.edges().map(|(u, v, _)| norm_edge(u, v)).collect();
+
+        if let Some(e) = bridges_set.difference(&edges).next() {
+            return Err(VerifyError::Inv(format!("Unknown edge {e:?}")));
+        }
+
+        for e in edges {
+            if self.test_bridge(e) != bridges_set.contains(&e) {
+                return Err(VerifyError::Fail(format!(
+                    "Wrong bridge {e:?} (expect {})",
+                    self.test_bridge(e)
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verify 2-edge-connected components for undirected graph
+    ///
+    /// 1. 点集不交且为合集
+    ///
+    /// 2. 分量内部的边不是桥, 分量之间的边是桥
+    ///
+    /// 3. 分量内部(导出子图)连通
+    ///
+    pub fn verify_two_edge_ccs(&self, comps: &[Vec<V>]) -> VerifyResult {
+        debug_assert!(!self.is_dir);
+
+        /* 1. vertexs cover and disjoint */
+
+        let mut comp_of = HashMap::new();
+
+        for (i, comp) in comps.iter().enumerate() {
+            for &v in comp {
+                if comp_of.insert(v, i).is_some() {
+                    return Err(VerifyError::Fail(format!(
+                        "Overlapped vertex {v:?}"
+                    )));
+                }
+            }
+        }
+
+        if comp_of.keys().cloned().sorted().ne(self.vertexs()) {
+            return Err(VerifyError::Fail(
+                "Unmatched complete vertexs".to_owned(),
+            ));
+        }
+
+        /* 2. bridges are exactly edges between comps */
+
+        for (u, v, _) in self.edges().filter(|(u, v, _)| u < v) {
+            let cross = get!(comp_of => u) != get!(comp_of => v);
+
+            if cross != self.test_bridge((u, v)) {
+                return Err(VerifyError::Fail(format!(
+                    "Edge {:?} is {}bridge but {} comps",
+                    (u, v),
+                    if cross { "not " } else { "" },
+                    if cross { "across" } else { "inside" },
+                )));
+            }
+        }
+
+        /* 3. each comp is connected */
+
+        for comp in comps.iter().filter(|comp| comp.len() > 1) {
+            let g_comp = Graph::from_undirected_iter(self.edges().filter(
+                |(u, v, _)| comp.contains(u) && comp.contains(v),
+            ));
+
+            if g_comp.vertexs().count() != comp.len()
+                || g_comp.components().len() != 1
+            {
+                return Err(VerifyError::Fail(format!(
+                    "Disconnected comp {comp:?}"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verify block-cut tree for undirected graph
+    ///
+    /// 1. blocks are bccs
+    ///
+    /// 2. 只有块与割点之间有边, 块与其中的割点相连
+    ///
+    /// 3. 是森林
+    ///
+    pub fn verify_block_cut_tree(&self, bct: &BlockCutTree<V>) -> VerifyResult {
+        debug_assert!(!self.is_dir);
+
+        let BlockCutTree { tree, blocks } = bct;
+
+        /* 1. blocks */
+
+        self.verify_undir_bccs(blocks)?;
+
+        /* 2. block - cut */
+
+        let mut expect = HashSet::new();
+
+        for (i, block) in blocks.iter().enumerate() {
+            for v in block.iter().flat_map(|&(u, v)| [u, v]).unique() {
+                if self.test_cut_vertex(v) {
+                    expect.insert((BCNode::Block(i), BCNode::Cut(v)));
+                    expect.insert((BCNode::Cut(v), BCNode::Block(i)));
+                }
+            }
+        }
+
+        let found: HashSet<(BCNode<V>, BCNode<V>)> =
+            tree.edges().map(|(x, y, _)| (x, y)).collect();
+
+        if found != expect {
+            return Err(VerifyError::Fail(format!(
+                "Unmatched tree edges:\n found: {found:?}\n expect: {expect:?}"
+            )));
+        }
+
+        /* 3. forest */
+
+        if tree.vertexs().count() != found.len() / 2 + tree.components().len()
+        {
+            return Err(VerifyError::Fail("Not a forest".to_owned()));
+        }
+
+        Ok(())
     }
 }
 
 
 /// for undirected graph
-pub fn normalize_undir_edges_comps(
-    a: Vec<Vec<(usize, usize)>>,
-) -> Vec<Vec<(usize, usize)>> {
+pub fn normalize_undir_edges_comps<V: Vertex>(
+    a: Vec<Vec<(V, V)>>,
+) -> Vec<Vec<(V, V)>> {
     assert!(a.iter().all(|x| !x.is_empty()));
 
     a.into_iter()
@@ -170,47 +390,52 @@ pub fn normalize_undir_edges_comps(
                 .map(|(u, v)| if u < v { (u, v) } else { (v, u) })
                 .sorted_unstable()
                 .dedup()
-                .collect::<Vec<(usize, usize)>>()
+                .collect::<Vec<(V, V)>>()
         })
         .sorted_unstable_by_key(|comp| comp[0])
         .collect()
 }
 
 
-pub fn bcc_tarjan(g: &Graph) -> Vec<Vec<(usize, usize)>> {
+pub fn bcc_tarjan<V: Vertex, W: Weight>(g: &Graph<V, W>) -> Vec<Vec<(V, V)>> {
     #[derive(Default, Clone, Copy)]
     struct DFSMeta {
         index: Option<usize>,
         lowpt: usize,
     }
 
-    fn dfs_bcc(
-        g: &Graph,
-        u: usize,
-        p: usize,
-        mut bccs: &mut Vec<Vec<(usize, usize)>>,
-        stack: &mut Vec<(usize, usize)>,
+    fn dfs_bcc<V: Vertex, W: Weight>(
+        g: &Graph<V, W>,
+        u: V,
+        p: Option<V>,
+        mut bccs: &mut Vec<Vec<(V, V)>>,
+        stack: &mut Vec<(V, V)>,
         index: &mut usize,
-        vertexs: &mut Vec<DFSMeta>,
+        vertexs: &mut HashMap<V, DFSMeta>,
     ) {
         *index += 1;
 
-        vertexs[u] = DFSMeta {
+        set!(vertexs => u => DFSMeta {
             index: Some(*index),
             lowpt: *index,
-        };
+        });
 
         for v in get!(g.e => u => vec![]) {
-            if vertexs[v].index.is_none() {
+            let meta_v = get!(vertexs => v => DFSMeta::default());
+
+            if meta_v.index.is_none() {
                 stack.push((u, v));
-                dfs_bcc(g, v, u, bccs, stack, index, vertexs);
+                dfs_bcc(g, v, Some(u), bccs, stack, index, vertexs);
 
-                if vertexs[v].lowpt < vertexs[u].lowpt {
-                    vertexs[u].lowpt = vertexs[v].lowpt;
+                let lowpt_v = get!(vertexs => v).lowpt;
+                let meta_u = vertexs.get_mut(&u).unwrap();
+
+                if lowpt_v < meta_u.lowpt {
+                    meta_u.lowpt = lowpt_v;
                 }
 
-                if vertexs[u].index.unwrap() == vertexs[u].lowpt {
-                    // u is cut point
+                if lowpt_v >= meta_u.index.unwrap() {
+                    // u is cut point (or root)
                     // get biconnected component
 
                     let mut bcc = vec![];
@@ -227,13 +452,17 @@ pub fn bcc_tarjan(g: &Graph) -> Vec<Vec<(usize, usize)>> {
                         }
                     }
 
-                    ordered_insert!(&mut bccs, bcc, |bcc: &Vec<(usize, usize)>| bcc[0]);
+                    ordered_insert!(&mut bccs, bcc, |bcc: &Vec<(V, V)>| bcc[0]);
                 }
-            } else if vertexs[v].index < vertexs[u].index && v != p {
-                stack.push((u, v));
+            } else {
+                let meta_u = vertexs.get_mut(&u).unwrap();
 
-                if vertexs[v].index.unwrap() < vertexs[u].lowpt {
-                    vertexs[u].lowpt = vertexs[v].index.unwrap();
+                if meta_v.index < meta_u.index && Some(v) != p {
+                    stack.push((u, v));
+
+                    if meta_v.index.unwrap() < meta_u.lowpt {
+                        meta_u.lowpt = meta_v.index.unwrap();
+                    }
                 }
             }
         }
@@ -242,23 +471,21 @@ pub fn bcc_tarjan(g: &Graph) -> Vec<Vec<(usize, usize)>> {
 
     let mut bccs = Vec::new();
 
-    if g.e.is_empty() {
+    if g.e.0.is_empty() {
         return bccs;
     }
 
     let mut stack = vec![];
     let mut index = 0;
 
-    let max_v = g.vertexs().max().unwrap();
-
-    let mut vertexs = vec![DFSMeta::default(); max_v + 1];
+    let mut vertexs = HashMap::new();
 
     for u in g.vertexs() {
-        if vertexs[u].index.is_none() {
+        if !vertexs.contains_key(&u) {
             dfs_bcc(
                 g,
                 u,
-                max_v + 1,
+                None,
                 &mut bccs,
                 &mut stack,
                 &mut index,
@@ -271,16 +498,279 @@ pub fn bcc_tarjan(g: &Graph) -> Vec<Vec<(usize, usize)>> {
 }
 
 
+/// Non-recursive version of `bcc_tarjan` (explicit stack), same output
+pub fn bcc_tarjan_nr<V: Vertex, W: Weight>(
+    g: &Graph<V, W>,
+) -> Vec<Vec<(V, V)>> {
+    #[derive(Clone, Copy)]
+    struct DFSMeta {
+        index: usize,
+        lowpt: usize,
+    }
+
+    let mut bccs = Vec::new();
+
+    if g.e.0.is_empty() {
+        return bccs;
+    }
+
+    let mut stack = vec![];
+    let mut index = 0;
+
+    let mut vertexs: HashMap<V, DFSMeta> = HashMap::new();
+
+    for s in g.vertexs() {
+        if vertexs.contains_key(&s) {
+            continue;
+        }
+
+        index += 1;
+        set!(vertexs => s => DFSMeta { index, lowpt: index });
+
+        // (u, parent, rest adjacent vertexs of u)
+        let mut frames =
+            vec![(s, None, get!(g.e => s => vec![]).into_iter())];
+
+        while let Some((u, p, tos)) = frames.last_mut() {
+            let (u, p) = (*u, *p);
+
+            if let Some(v) = tos.next() {
+                if let Some(&meta_v) = vertexs.get(&v) {
+                    let meta_u = vertexs.get_mut(&u).unwrap();
+
+                    if meta_v.index < meta_u.index && Some(v) != p {
+                        stack.push((u, v));
+
+                        if meta_v.index < meta_u.lowpt {
+                            meta_u.lowpt = meta_v.index;
+                        }
+                    }
+                } else {
+                    stack.push((u, v));
+
+                    index += 1;
+                    set!(vertexs => v => DFSMeta { index, lowpt: index });
+
+                    frames.push((
+                        v,
+                        Some(u),
+                        get!(g.e => v => vec![]).into_iter(),
+                    ));
+                }
+
+                continue;
+            }
+
+            frames.pop();
+
+            /* return to parent */
+
+            let Some(p) = p else {
+                continue;
+            };
+
+            let lowpt_u = get!(vertexs => u).lowpt;
+            let meta_p = vertexs.get_mut(&p).unwrap();
+
+            if lowpt_u < meta_p.lowpt {
+                meta_p.lowpt = lowpt_u;
+            }
+
+            if lowpt_u >= meta_p.index {
+                // p is cut point (or root)
+                // get biconnected component
+
+                let mut bcc = vec![];
+
+                loop {
+                    let (v1, v2) = stack.pop().unwrap();
+                    bcc.push(if v1 < v2 { (v1, v2) } else { (v2, v1) });
+
+                    if v1 == p {
+                        break;
+                    }
+                }
+
+                bcc.sort_unstable();
+                bcc.dedup();
+                bccs.push(bcc);
+            }
+        }
+    }
+
+    bccs.sort_unstable_by_key(|bcc| bcc[0]);
+
+    bccs
+}
+
+
+/// Cut vertexs (sorted) of undirected graph
+pub fn articulation_points<V: Vertex, W: Weight>(g: &Graph<V, W>) -> Vec<V> {
+    cut_dfs(g).0
+}
+
+
+/// Cut edges (u < v, sorted) of undirected graph
+pub fn bridges<V: Vertex, W: Weight>(g: &Graph<V, W>) -> Vec<(V, V)> {
+    cut_dfs(g).1
+}
+
+
+/// 2-edge-connected components of undirected graph, like `components`
+///
+/// that's components after removing all bridges.
+pub fn two_edge_ccs<V: Vertex, W: Weight>(g: &Graph<V, W>) -> Vec<Vec<V>> {
+    let bridges: HashSet<(V, V)> = bridges(g).into_iter().collect();
+
+    let mut comps = Graph::from_undirected_iter(
+        g.edges()
+            .filter(|&(u, v, _)| !bridges.contains(&norm_edge(u, v))),
+    )
+    .components();
+
+    /* vertexs whose edges are all bridges */
+
+    let covered: HashSet<V> = comps.iter().flatten().cloned().collect();
+
+    comps.extend(
+        g.vertexs().filter(|v| !covered.contains(v)).map(|v| vec![v]),
+    );
+    comps.sort_unstable_by_key(|comp| comp[0]);
+
+    comps
+}
+
+
+/// Block-cut tree of undirected graph,
+/// block without cut point disappears from the tree as orphan.
+pub fn block_cut_tree<V: Vertex, W: Weight>(
+    g: &Graph<V, W>,
+) -> BlockCutTree<V> {
+    let blocks = bcc_tarjan_nr(g);
+    let cuts: HashSet<V> = articulation_points(g).into_iter().collect();
+
+    let mut edges = vec![];
+
+    for (i, block) in blocks.iter().enumerate() {
+        for v in block.iter().flat_map(|&(u, v)| [u, v]).unique() {
+            if cuts.contains(&v) {
+                edges.push((BCNode::Block(i), BCNode::Cut(v), 1));
+            }
+        }
+    }
+
+    BlockCutTree {
+        tree: Graph::from_undirected_iter(edges),
+        blocks,
+    }
+}
+
+
+/// Lowlink DFS (non-recursive), return (cut vertexs, bridges)
+fn cut_dfs<V: Vertex, W: Weight>(
+    g: &Graph<V, W>,
+) -> (Vec<V>, Vec<(V, V)>) {
+    debug_assert!(!g.is_dir);
+
+    let mut cuts = HashSet::new();
+    let mut bridges = vec![];
+
+    // v => (index, lowpt)
+    let mut vertexs: HashMap<V, (usize, usize)> = HashMap::new();
+    let mut index = 0;
+
+    for s in g.vertexs() {
+        if vertexs.contains_key(&s) {
+            continue;
+        }
+
+        index += 1;
+        set!(vertexs => s => (index, index));
+
+        let mut root_children = 0;
+
+        // (u, parent, rest adjacent vertexs of u)
+        let mut frames =
+            vec![(s, None, get!(g.e => s => vec![]).into_iter())];
+
+        while let Some((u, p, tos)) = frames.last_mut() {
+            let (u, p) = (*u, *p);
+
+            if let Some(v) = tos.next() {
+                if Some(v) == p {
+                    continue;
+                }
+
+                if let Some(&(index_v, _)) = vertexs.get(&v) {
+                    let lowpt_u = &mut vertexs.get_mut(&u).unwrap().1;
+
+                    *lowpt_u = min(*lowpt_u, index_v);
+                } else {
+                    index += 1;
+                    set!(vertexs => v => (index, index));
+
+                    frames.push((
+                        v,
+                        Some(u),
+                        get!(g.e => v => vec![]).into_iter(),
+                    ));
+                }
+
+                continue;
+            }
+
+            frames.pop();
+
+            /* return to parent */
+
+            let Some(p) = p else {
+                continue;
+            };
+
+            let lowpt_u = get!(vertexs => u).1;
+            let (index_p, lowpt_p) = vertexs.get_mut(&p).unwrap();
+
+            *lowpt_p = min(*lowpt_p, lowpt_u);
+
+            if lowpt_u > *index_p {
+                bridges.push(norm_edge(p, u));
+            }
+
+            if lowpt_u >= *index_p {
+                if p == s {
+                    root_children += 1;
+                } else {
+                    cuts.insert(p);
+                }
+            }
+        }
+
+        if root_children > 1 {
+            cuts.insert(s);
+        }
+    }
+
+    bridges.sort_unstable();
+
+    (cuts.into_iter().sorted_unstable().collect(), bridges)
+}
+
+
+fn norm_edge<V: Vertex>(u: V, v: V) -> (V, V) {
+    if u < v { (u, v) } else { (v, u) }
+}
+
+
 
 
 #[cfg(test)]
 mod tests {
 
     use coll::aux::VerifyError::*;
-    use common::{random_range, Itertools};
+    use common::{random_range, thread_rng, Itertools};
 
-    use super::normalize_undir_edges_comps;
-    use crate::{bcc::bcc_tarjan, test::GraphGenOptions, Graph};
+    use super::*;
+    use crate::test::{model::gen_chain, GraphGenOptions};
 
 
     fn setup_data() -> Vec<Graph> {
@@ -349,16 +839,26 @@ mod tests {
             normalize_undir_edges_comps(res0),
         ));
 
+        /* triangle with a pendant edge visited at last */
+
+        let g1 = vec![(1, 2, 1), (2, 3, 1), (3, 1, 1), (3, 4, 1)];
+        let res1 = vec![vec![(1, 2), (1, 3), (2, 3)], vec![(3, 4)]];
+
+        samples.push((Graph::from_undirected_iter(g1), res1));
+
         for (g, expect) in samples {
             let bccs = bcc_tarjan(&g);
 
             assert_eq!(bccs, expect);
+            assert_eq!(bccs, bcc_tarjan_nr(&g));
             g.verify_undir_bccs(&bccs).unwrap();
         }
 
         for g in setup_data() {
             let bccs = bcc_tarjan(&g);
 
+            assert_eq!(bccs, bcc_tarjan_nr(&g));
+
             // println!("bccs len: {}", bccs.len());
 
             if let Err(err) = g.verify_undir_bccs(&bccs) {
@@ -379,4 +879,76 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_bcc_nr_chain() {
+        let n = 1_000_000;
+        let g = gen_chain(n, false, 1..2, &mut thread_rng());
+
+        let bccs = bcc_tarjan_nr(&g);
+
+        assert_eq!(bccs.len(), n - 1);
+        assert!(bccs.iter().enumerate().all(|(i, bcc)| {
+            bcc == &[(i + 1, i + 2)]
+        }));
+    }
+
+    #[test]
+    fn test_cut_bridge_block() {
+        let g0 = Graph::from_undirected_iter([
+            (1, 2, 1),
+            (1, 6, 1),
+            (1, 7, 1),
+            (2, 3, 1),
+            (2, 4, 1),
+            (2, 5, 1),
+            (3, 4, 1),
+            (5, 6, 1),
+            (7, 8, 1),
+            (7, 9, 1),
+            (8, 9, 1),
+            (10, 11, 1),
+        ]);
+
+        assert_eq!(articulation_points(&g0), vec![1, 2, 7]);
+        assert_eq!(bridges(&g0), vec![(1, 7), (10, 11)]);
+        assert_eq!(
+            two_edge_ccs(&g0),
+            vec![vec![1, 2, 3, 4, 5, 6], vec![7, 8, 9], vec![10], vec![11]]
+        );
+
+        let BlockCutTree { tree, blocks } = block_cut_tree(&g0);
+
+        assert_eq!(blocks.len(), 5);
+        assert_eq!(blocks[1], vec![(1, 7)]);
+        assert_eq!(
+            tree.edges()
+                .filter(|(x, y, _)| x < y)
+                .map(|(x, y, _)| (x, y))
+                .sorted()
+                .collect_vec(),
+            [(0, 1), (0, 2), (1, 1), (1, 7), (2, 2), (3, 7)]
+                .map(|(i, v)| (BCNode::Block(i), BCNode::Cut(v)))
+        );
+
+        for g in setup_data().into_iter().take(10).chain([g0]) {
+            let check = |res: VerifyResult| {
+                if let Err(err) = res {
+                    match err {
+                        Inv(inv) => panic!("Invalid {inv}"),
+                        Fail(fail) => panic!("Fail {fail}"),
+                    }
+                }
+            };
+
+            check(g.verify_articulation_points(&articulation_points(&g)));
+            check(g.verify_bridges(&bridges(&g)));
+            check(g.verify_two_edge_ccs(&two_edge_ccs(&g)));
+
+            let bct = block_cut_tree(&g);
+
+            assert_eq!(bct.blocks, bcc_tarjan(&g));
+            check(g.verify_block_cut_tree(&bct));
+        }
+    }
 }
@@ -0,0 +1,252 @@
+//! Compressed sparse row (CSR), compact adjacency for large static graph
+//!
+
+
+use common::Itertools;
+
+use crate::{Graph, GraphView, Vertex, Weight};
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Structures
+
+/// 压缩稀疏行: vs[i] 的出边是 adj[off[i]..off[i+1]], 权重存于平行数组 w
+///
+/// 同一点的出边按终点排序, 不做任何哈希
+#[derive(Clone)]
+pub struct CSR<V = usize, W = isize> {
+    is_dir: bool,
+    /// sorted vertexs
+    vs: Vec<V>,
+    /// len = |V| + 1
+    off: Vec<usize>,
+    /// index (in vs) of edge end
+    adj: Vec<usize>,
+    w: Vec<W>,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Implementations
+
+impl<V: Vertex, W: Weight> CSR<V, W> {
+    /// Duplicated edge keeps the last weight (the same as `Graph`)
+    pub fn from_directed_iter<T: IntoIterator<Item = (V, V, W)>>(
+        iter: T,
+    ) -> Self {
+        Self::build(true, iter)
+    }
+
+    pub fn from_undirected_iter<T: IntoIterator<Item = (V, V, W)>>(
+        iter: T,
+    ) -> Self {
+        Self::build(
+            false,
+            iter.into_iter().flat_map(|(u, v, w)| [(u, v, w), (v, u, w)]),
+        )
+    }
+
+    pub fn from_graph(g: &Graph<V, W>) -> Self {
+        Self::build(g.is_dir, g.edges())
+    }
+
+    fn build<T: IntoIterator<Item = (V, V, W)>>(
+        is_dir: bool,
+        iter: T,
+    ) -> Self {
+        let mut edges: Vec<(V, V, W)> = iter.into_iter().collect();
+
+        // stable sort keeps the insertion order of duplicated edges
+        edges.sort_by_key(|(u, v, _)| (*u, *v));
+
+        let mut uniq_edges: Vec<(V, V, W)> = Vec::with_capacity(edges.len());
+
+        for (u, v, w) in edges {
+            match uniq_edges.last_mut() {
+                Some(last) if (last.0, last.1) == (u, v) => last.2 = w,
+                _ => uniq_edges.push((u, v, w)),
+            }
+        }
+
+        let vs: Vec<V> = uniq_edges
+            .iter()
+            .flat_map(|(u, v, _)| [*u, *v])
+            .sorted_unstable()
+            .dedup()
+            .collect();
+
+        let mut off = vec![0; vs.len() + 1];
+        let mut adj = Vec::with_capacity(uniq_edges.len());
+        let mut w = Vec::with_capacity(uniq_edges.len());
+
+        for (u, v, w_) in uniq_edges {
+            off[vs.binary_search(&u).unwrap() + 1] += 1;
+            adj.push(vs.binary_search(&v).unwrap());
+            w.push(w_);
+        }
+
+        for i in 0..vs.len() {
+            off[i + 1] += off[i];
+        }
+
+        Self { is_dir, vs, off, adj, w }
+    }
+
+    pub fn to_graph(&self) -> Graph<V, W> {
+        let edges = GraphView::edges(self);
+
+        if self.is_dir {
+            Graph::from_directed_iter(edges)
+        } else {
+            // both directions have been contained
+            let mut g = Graph::from_directed_iter(edges);
+            g.is_dir = false;
+            g
+        }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.vs.len()
+    }
+
+    /// Count of arcs (undirected edge counts twice)
+    pub fn edge_count(&self) -> usize {
+        self.adj.len()
+    }
+
+    /// O(log|V|)
+    pub fn index(&self, v: V) -> Option<usize> {
+        self.vs.binary_search(&v).ok()
+    }
+
+    pub fn vertex(&self, i: usize) -> V {
+        self.vs[i]
+    }
+
+    /// O(log|V|)
+    pub fn weight(&self, u: V, v: V) -> Option<W> {
+        let i = self.index(u)?;
+        let j = self.index(v)?;
+        let lo = self.off[i];
+
+        self.adj[lo..self.off[i + 1]]
+            .binary_search(&j)
+            .ok()
+            .map(|k| self.w[lo + k])
+    }
+}
+
+
+impl<V: Vertex, W: Weight> From<&Graph<V, W>> for CSR<V, W> {
+    fn from(g: &Graph<V, W>) -> Self {
+        Self::from_graph(g)
+    }
+}
+
+
+impl<V: Vertex, W: Weight> GraphView<V, W> for CSR<V, W> {
+    fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+
+    fn vertexs(&self) -> impl Iterator<Item = V> {
+        self.vs.iter().cloned()
+    }
+
+    fn adj(&self, u: V) -> impl Iterator<Item = (V, W)> {
+        let range = match self.index(u) {
+            Some(i) => self.off[i]..self.off[i + 1],
+            None => 0..0,
+        };
+
+        range.map(move |k| (self.vs[self.adj[k]], self.w[k]))
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use common::Itertools;
+
+    use super::*;
+    use crate::{
+        scc::{scc_path_based, scc_tarjan},
+        sp::{SPBellmanFord, SPDijkstra, SPFA},
+        test::{batch_graph, GraphGenOptions},
+        toposort::toposort_tarjan,
+    };
+
+
+    #[test]
+    fn test_csr_fixeddata() {
+        let csr = CSR::from_directed_iter([
+            (3, 1, 4),
+            (1, 2, 1),
+            (1, 3, 5),
+            (1, 2, 2), // override
+            (2, 7, 3),
+        ]);
+
+        assert_eq!(csr.vertex_count(), 4);
+        assert_eq!(csr.edge_count(), 4);
+        assert_eq!(csr.vertexs().collect_vec(), vec![1, 2, 3, 7]);
+        assert_eq!(csr.adj(1).collect_vec(), vec![(2, 2), (3, 5)]);
+        assert_eq!(csr.adj(7).count(), 0);
+        assert_eq!(csr.adj(100).count(), 0);
+        assert_eq!(csr.weight(3, 1), Some(4));
+        assert_eq!(csr.weight(1, 7), None);
+
+        let g = csr.to_graph();
+
+        assert_eq!(
+            g.edges().sorted().collect_vec(),
+            GraphView::edges(&csr).collect_vec()
+        );
+    }
+
+    #[test]
+    fn test_csr_randomdata() {
+        for g in batch_graph(30, 30, 1..50, &GraphGenOptions::undir_conn()) {
+            let csr = CSR::from(&g);
+
+            assert_eq!(
+                g.edges().sorted().dedup().collect_vec(),
+                GraphView::edges(&csr).collect_vec()
+            );
+
+            for src in g.vertexs() {
+                let sp_dijkstra_g = SPDijkstra::new(&g, src);
+                let sp_dijkstra = SPDijkstra::new(&csr, src);
+                let sp_fa = SPFA::new(&csr, src).unwrap();
+                let sp_bellmanford = SPBellmanFord::new(&csr, src).unwrap();
+
+                for dst in g.vertexs() {
+                    let (w, path) = sp_dijkstra.query(dst);
+
+                    g.verify_path(src, dst, &path).unwrap();
+
+                    assert_eq!(w, sp_dijkstra_g.query(dst).0);
+                    assert_eq!(w, sp_fa.query(dst).0);
+                    assert_eq!(w, sp_bellmanford.query(dst).0);
+                }
+            }
+        }
+
+        for g in batch_graph(30, 30, 1..2, &GraphGenOptions::dir_conn()) {
+            let csr = CSR::from(&g);
+
+            assert_eq!(
+                csr.to_graph().edges().collect_vec(),
+                g.edges().sorted().dedup().collect_vec()
+            );
+            assert_eq!(scc_tarjan(&csr), scc_tarjan(&g));
+            assert_eq!(scc_path_based(&csr), scc_tarjan(&g));
+            assert_eq!(
+                toposort_tarjan(&csr).is_ok(),
+                toposort_tarjan(&g).is_ok()
+            );
+        }
+    }
+}
@@ -0,0 +1,366 @@
+//! Cycles of directed graph: enumerate elementary cycles (Johnson)
+//! and minimum mean-weight cycle (Karp)
+//!
+//! Cycle is output as v0, v1, ..., vk (not repeat v0),
+//! like `Path::from_cycle`.
+
+use std::collections::{HashMap, HashSet};
+
+use common::Itertools;
+
+use crate::{scc::scc_tarjan, Graph, GraphView, Vertex, Weight};
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Functions
+
+/// Johnson's circuit algorithm, O((n+e)(c+1) + n(n+e)) for c cycles
+///
+/// The subgraph {v >= s} and its SCC are rebuilt for every start vertex s,
+/// which gives the extra O(n(n+e)).
+///
+/// Each cycle starts from its minimum vertex, self loop is [v].
+pub fn cycles_johnson<V: Vertex, W: Weight, G: GraphView<V, W>>(
+    g: &G,
+) -> Vec<Vec<V>> {
+    debug_assert!(g.is_dir());
+
+    fn circuit<V: Vertex>(
+        v: V,
+        s: V,
+        adj: &HashMap<V, Vec<V>>,
+        blocked: &mut HashSet<V>,
+        b: &mut HashMap<V, HashSet<V>>,
+        stack: &mut Vec<V>,
+        res: &mut Vec<Vec<V>>,
+    ) -> bool {
+        let mut found = false;
+
+        stack.push(v);
+        blocked.insert(v);
+
+        for &w in adj[&v].iter() {
+            if w == s {
+                res.push(stack.clone());
+                found = true;
+            } else if !blocked.contains(&w)
+                && circuit(w, s, adj, blocked, b, stack, res)
+            {
+                found = true;
+            }
+        }
+
+        if found {
+            unblock(v, blocked, b);
+        } else {
+            for &w in adj[&v].iter() {
+                b.entry(w).or_default().insert(v);
+            }
+        }
+
+        stack.pop();
+
+        found
+    }
+
+    fn unblock<V: Vertex>(
+        u: V,
+        blocked: &mut HashSet<V>,
+        b: &mut HashMap<V, HashSet<V>>,
+    ) {
+        let mut stack = vec![u];
+
+        while let Some(u) = stack.pop() {
+            if blocked.remove(&u) {
+                stack.extend(b.remove(&u).into_iter().flatten());
+            }
+        }
+    }
+
+    let vs = g.vertexs().collect_vec();
+    let mut res = vec![];
+
+    for &s in vs.iter() {
+        /* scc containing s in subgraph induced by {v >= s} */
+
+        let sub = Graph::from_directed_iter(
+            g.edges().filter(|&(u, v, _)| u >= s && v >= s),
+        );
+
+        let comps = scc_tarjan(&sub);
+
+        let Some(comp) = comps.into_iter().find(|c| c.contains(&s)) else {
+            continue;
+        };
+
+        let comp: HashSet<V> = comp.into_iter().collect();
+
+        let adj = comp
+            .iter()
+            .map(|&u| {
+                let tos =
+                    g.adj(u).map(|(v, _)| v).filter(|v| comp.contains(v));
+
+                (u, tos.collect_vec())
+            })
+            .collect();
+
+        circuit(
+            s,
+            s,
+            &adj,
+            &mut HashSet::new(),
+            &mut HashMap::new(),
+            &mut vec![],
+            &mut res,
+        );
+    }
+
+    res
+}
+
+
+/// Karp's minimum mean cycle, O(n*e)
+///
+/// Return (cycle weight, cycle), mean = weight / cycle.len(),
+/// e.g. arbitrage exists iff mean of -ln(rate) is negative.
+pub fn min_mean_cycle_karp<V, W, G>(g: &G) -> Option<(W, Vec<V>)>
+where
+    V: Vertex,
+    W: Weight + std::ops::Sub<Output = W>,
+    G: GraphView<V, W>,
+{
+    let vs = g.vertexs().collect_vec();
+    let n = vs.len();
+
+    if n == 0 {
+        return None;
+    }
+
+    let idx: HashMap<V, usize> =
+        vs.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+
+    let edges = g
+        .edges()
+        .map(|(u, v, w)| (idx[&u], idx[&v], w))
+        .collect_vec();
+
+    /* d[k][v]: min weight of walk with exactly k edges ending at v
+       (start from anywhere) */
+
+    let mut d = vec![vec![None; n]; n + 1];
+    let mut pre = vec![vec![usize::MAX; n]; n + 1];
+
+    d[0] = vec![Some(W::zero()); n];
+
+    for k in 1..=n {
+        for &(u, v, w) in edges.iter() {
+            let Some(d_u) = d[k - 1][u] else {
+                continue;
+            };
+
+            if d[k][v].is_none_or(|d_v| d_u + w < d_v) {
+                d[k][v] = Some(d_u + w);
+                pre[k][v] = u;
+            }
+        }
+    }
+
+    /* min_v max_k (d[n][v] - d[k][v]) / (n - k) */
+
+    // (numerator, denominator)
+    let less = |a: (W, usize), b: (W, usize)| {
+        scale(a.0, b.1) < scale(b.0, a.1)
+    };
+
+    let mut best: Option<((W, usize), usize)> = None;
+
+    for (v, &d_n) in d[n].iter().enumerate() {
+        let Some(d_n) = d_n else {
+            continue;
+        };
+
+        let worst = (0..n)
+            .filter_map(|k| d[k][v].map(|d_k| (d_n - d_k, n - k)))
+            .reduce(|acc, x| if less(acc, x) { x } else { acc })
+            .unwrap();
+
+        if best.is_none_or(|(b, _)| less(worst, b)) {
+            best = Some((worst, v));
+        }
+    }
+
+    let (_, v) = best?;
+
+    /* any cycle on the critical walk is a minimum mean cycle */
+
+    let mut walk = vec![v];
+    let mut seen = HashMap::from([(v, 0)]);
+    let mut x = v;
+
+    for k in (1..=n).rev() {
+        x = pre[k][x];
+
+        if let Some(&i) = seen.get(&x) {
+            let mut cycle = walk[i..].iter().map(|&i| vs[i]).collect_vec();
+            cycle.reverse();
+
+            let w = cycle
+                .iter()
+                .cloned()
+                .circular_tuple_windows()
+                .map(|(a, b)| {
+                    g.adj(a).find(|&(c, _)| c == b).unwrap().1
+                })
+                .reduce(|acc, w| acc + w)
+                .unwrap();
+
+            return Some((w, cycle));
+        }
+
+        seen.insert(x, walk.len());
+        walk.push(x);
+    }
+
+    unreachable!("walk of n edges must contain a cycle")
+}
+
+
+/// w * k by doubling, needs only `Add`
+fn scale<W: Weight>(w: W, mut k: usize) -> W {
+    let mut res = W::zero();
+    let mut base = w;
+
+    while k > 0 {
+        if k & 1 == 1 {
+            res = res + base;
+        }
+
+        base = base + base;
+        k >>= 1;
+    }
+
+    res
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use common::{thread_rng, Itertools};
+
+    use super::*;
+    use crate::{
+        test::{model::gen_gnp, path::Path},
+        OrdF64,
+    };
+
+
+    fn brute_force_cycles(g: &Graph) -> HashSet<Vec<usize>> {
+        let vs = g.vertexs().collect_vec();
+        let mut res = HashSet::new();
+
+        for k in 1..=vs.len() {
+            for p in vs.iter().cloned().permutations(k) {
+                if p[0] == *p.iter().min().unwrap()
+                    && p.iter()
+                        .circular_tuple_windows()
+                        .all(|(&u, &v)| g.contains_edge((u, v)))
+                {
+                    res.insert(p);
+                }
+            }
+        }
+
+        res
+    }
+
+    #[test]
+    fn test_cycles_fixeddata() {
+        let g = Graph::from_directed_iter(
+            [(1, 2), (2, 1), (2, 3), (3, 1), (3, 3), (3, 4), (4, 2)]
+                .map(|(u, v)| (u, v, 1)),
+        );
+
+        let cycles = cycles_johnson(&g).into_iter().sorted().collect_vec();
+
+        assert_eq!(
+            cycles,
+            vec![
+                vec![1, 2],
+                vec![1, 2, 3],
+                vec![2, 3, 4],
+                vec![3],
+            ]
+        );
+
+        /* arbitrage: usd -> eur -> gbp -> usd gains */
+
+        let rates = [
+            ("usd", "eur", 0.9),
+            ("eur", "gbp", 0.9),
+            ("gbp", "usd", 1.3),
+            ("eur", "usd", 1.1),
+            ("gbp", "eur", 1.1),
+        ];
+
+        let g = Graph::from_directed_iter(
+            rates.map(|(u, v, r): (&str, &str, f64)| {
+                (u, v, OrdF64(-r.ln()))
+            }),
+        );
+
+        let (w, cycle) = min_mean_cycle_karp(&g).unwrap();
+
+        assert!(w.0 < 0.0);
+        assert_eq!(cycle.len(), 3);
+        assert_eq!(
+            cycle.into_iter().sorted().collect_vec(),
+            vec!["eur", "gbp", "usd"]
+        );
+
+        let g = Graph::from_directed_iter([(1, 2, 1), (2, 3, 1)]);
+
+        assert!(cycles_johnson(&g).is_empty());
+        assert_eq!(min_mean_cycle_karp(&g), None);
+    }
+
+    #[test]
+    fn test_cycles_randomdata() {
+        let mut rng = thread_rng();
+
+        for _ in 0..100 {
+            let g = gen_gnp(6, 0.4, true, -10..10, &mut rng);
+            let expect = brute_force_cycles(&g);
+
+            let cycles = cycles_johnson(&g);
+
+            assert_eq!(cycles.len(), expect.len());
+            assert_eq!(cycles.iter().cloned().collect::<HashSet<_>>(), expect);
+
+            /* Karp against all cycles */
+
+            let mean = |c: &[usize]| {
+                (Path::from_cycle(&g, c).weight(), c.len() as isize)
+            };
+
+            let expect = cycles
+                .iter()
+                .map(|c| mean(c))
+                .min_by(|a, b| (a.0 * b.1).cmp(&(b.0 * a.1)));
+
+            let res = min_mean_cycle_karp(&g);
+
+            match (res, expect) {
+                (None, None) => (),
+                (Some((w, cycle)), Some((ew, elen))) => {
+                    assert_eq!(Path::from_cycle(&g, &cycle).weight(), w);
+                    assert_eq!(w * elen, ew * cycle.len() as isize);
+                }
+                (res, expect) => panic!("{res:?} / {expect:?}"),
+            }
+        }
+    }
+}
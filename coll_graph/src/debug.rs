@@ -0,0 +1,258 @@
+//! Graphviz DOT export and `Debug` for `Graph`
+
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Debug, Display},
+    io::{self, Write},
+};
+
+use common::Itertools;
+
+use crate::{Graph, Vertex, Weight};
+use graphviz::{Highlight, RenderOptions};
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Structures
+
+pub mod graphviz {
+    /// dir: render as digraph (`->`) or graph (`--`),
+    /// undirected rendering merges the pair of arcs into one edge
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct RenderOptions {
+        pub dir: bool,
+        pub weight_edge: bool,
+    }
+
+
+    /// Something to be emphasized on the rendered graph,
+    /// the i-th highlight uses the i-th color of palette
+    #[derive(Debug, Clone)]
+    pub enum Highlight<V> {
+        /// v0, v1, ..., vn
+        Path(Vec<V>),
+        /// v0, v1, ..., vn (, v0)
+        Cycle(Vec<V>),
+        /// e.g. MST edges
+        Edges(Vec<(V, V)>),
+        /// e.g. SCC / BCC, each group fills a distinct color
+        Groups(Vec<Vec<V>>),
+    }
+
+
+    pub(super) const PALETTE: [&str; 8] = [
+        "red",
+        "blue",
+        "forestgreen",
+        "darkorange",
+        "purple",
+        "gold",
+        "deeppink",
+        "cyan3",
+    ];
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Implementations
+
+impl<V: Vertex, W: Weight> Debug for Graph<V, W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Graph")
+            .field("is_dir", &self.is_dir)
+            .field("edges", &self.edges().collect_vec())
+            .finish()
+    }
+}
+
+
+impl<V, W> Graph<V, W>
+where
+    V: Vertex + Display,
+    W: Weight + Display,
+{
+    pub fn render<O: Write>(
+        &self,
+        opt: &RenderOptions,
+        w: &mut O,
+    ) -> io::Result<()> {
+        self.render_highlight(opt, &[], w)
+    }
+
+    pub fn render_highlight<O: Write>(
+        &self,
+        opt: &RenderOptions,
+        hls: &[Highlight<V>],
+        w: &mut O,
+    ) -> io::Result<()> {
+        let norm = |u: V, v: V| {
+            if opt.dir || u <= v { (u, v) } else { (v, u) }
+        };
+
+        /* collect highlighted vertexs and edges */
+
+        let mut vcolor = HashMap::new();
+        let mut ecolor = HashMap::new();
+
+        for (i, hl) in hls.iter().enumerate() {
+            let color = graphviz::PALETTE[i % graphviz::PALETTE.len()];
+
+            let edges = match hl {
+                Highlight::Path(path) => {
+                    path.iter().cloned().tuple_windows().collect_vec()
+                }
+                Highlight::Cycle(cycle) => {
+                    let mut cycle = cycle.clone();
+
+                    if cycle.len() > 1 && cycle.first() != cycle.last() {
+                        cycle.push(cycle[0]);
+                    }
+
+                    cycle.into_iter().tuple_windows().collect_vec()
+                }
+                Highlight::Edges(edges) => edges.clone(),
+                Highlight::Groups(groups) => {
+                    for (j, group) in groups.iter().enumerate() {
+                        let color = graphviz::PALETTE
+                            [(i + j) % graphviz::PALETTE.len()];
+
+                        for v in group.iter() {
+                            vcolor.insert(*v, color);
+                        }
+                    }
+
+                    vec![]
+                }
+            };
+
+            for (u, v) in edges {
+                ecolor.insert(norm(u, v), color);
+            }
+        }
+
+        /* write */
+
+        let (kind, arrow) = if opt.dir {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+
+        writeln!(w, "{kind} {{")?;
+
+        for v in self.vertexs() {
+            write!(w, "    {}", quote(v))?;
+
+            if let Some(color) = vcolor.get(&v) {
+                write!(w, " [style=filled, fillcolor={color}]")?;
+            }
+
+            writeln!(w, ";")?;
+        }
+
+        let mut visited = HashSet::new();
+
+        for (u, v, w_) in self.edges() {
+            if !visited.insert(norm(u, v)) {
+                continue;
+            }
+
+            let mut attrs = vec![];
+
+            if opt.weight_edge {
+                attrs.push(format!("label={}", quote(w_)));
+            }
+
+            if let Some(color) = ecolor.get(&norm(u, v)) {
+                attrs.push(format!("color={color}"));
+                attrs.push("penwidth=3".to_owned());
+            }
+
+            write!(w, "    {} {arrow} {}", quote(u), quote(v))?;
+
+            if !attrs.is_empty() {
+                write!(w, " [{}]", attrs.join(", "))?;
+            }
+
+            writeln!(w, ";")?;
+        }
+
+        writeln!(w, "}}")
+    }
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Functions
+
+fn quote<T: Display>(x: T) -> String {
+    format!("\"{}\"", x.to_string().replace('"', "\\\""))
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mst::mst_kruskal, scc::scc_tarjan};
+
+
+    #[test]
+    fn test_render_fixeddata() {
+        let g = Graph::from_undirected_iter([(1, 2, 3), (2, 3, 1), (1, 3, 1)]);
+        let opt = RenderOptions {
+            dir: false,
+            weight_edge: true,
+        };
+
+        let mut buf = vec![];
+        g.render(&opt, &mut buf).unwrap();
+        let dot = String::from_utf8(buf).unwrap();
+
+        assert!(dot.starts_with("graph {"));
+        assert_eq!(dot.matches(" -- ").count(), 3);
+        assert!(dot.contains("\"1\" -- \"2\" [label=\"3\"];"));
+
+        let mst = mst_kruskal(&g);
+        let mut buf = vec![];
+        g.render_highlight(
+            &opt,
+            &[Highlight::Edges(mst)],
+            &mut buf,
+        )
+        .unwrap();
+        let dot = String::from_utf8(buf).unwrap();
+
+        assert_eq!(dot.matches("color=red").count(), 2);
+
+        let g = Graph::from_directed_iter([
+            ("a", "b", 1),
+            ("b", "a", 1),
+            ("b", "c", 1),
+        ]);
+        let opt = RenderOptions {
+            dir: true,
+            weight_edge: false,
+        };
+        let mut buf = vec![];
+        g.render_highlight(
+            &opt,
+            &[
+                Highlight::Cycle(vec!["a", "b"]),
+                Highlight::Groups(scc_tarjan(&g)),
+            ],
+            &mut buf,
+        )
+        .unwrap();
+        let dot = String::from_utf8(buf).unwrap();
+
+        assert!(dot.starts_with("digraph {"));
+        assert_eq!(dot.matches(" -> ").count(), 3);
+        assert_eq!(dot.matches("color=red, penwidth=3").count(), 2);
+        assert_eq!(dot.matches("fillcolor").count(), 3);
+        assert_eq!(format!("{g:?}").matches("(\"b\", \"a\", 1)").count(), 1);
+    }
+}
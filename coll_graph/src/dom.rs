@@ -0,0 +1,511 @@
+//! Dominator tree of flow graph (e.g. control-flow graph)
+//!
+//! d dominates v: every path from root to v passes through d.
+
+use std::collections::{HashMap, HashSet};
+
+use coll::{apush, easycoll::MV, get, set};
+
+use crate::{Graph, GraphView, Vertex, Weight};
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Constants
+
+const NIL: usize = usize::MAX;
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Structures
+
+/// Immediate dominator of every vertex reachable from root (except root)
+#[derive(Debug, Clone)]
+pub struct DomTree<V = usize> {
+    pub root: V,
+    pub idom: HashMap<V, V>,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Implementations
+
+impl<V: Vertex> PartialEq for DomTree<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root && self.idom == other.idom
+    }
+}
+
+impl<V: Vertex> Eq for DomTree<V> {}
+
+
+impl<V: Vertex> DomTree<V> {
+    pub fn contains(&self, v: V) -> bool {
+        v == self.root || self.idom.contains_key(&v)
+    }
+
+    /// Check if d dominates v (v dominates itself)
+    pub fn dominates(&self, d: V, mut v: V) -> bool {
+        if !self.contains(v) {
+            return false;
+        }
+
+        loop {
+            if v == d {
+                return true;
+            }
+
+            if v == self.root {
+                return false;
+            }
+
+            v = get!(self.idom => v);
+        }
+    }
+
+    /// Dominator tree idom(v) -> v, weighted by zero
+    pub fn to_graph<W: Weight>(&self) -> Graph<V, W> {
+        Graph::from_directed_iter(
+            self.idom.iter().map(|(&v, &d)| (d, v, W::zero())),
+        )
+    }
+
+    /// Dominance frontiers (Cytron et al. by join points),
+    /// g should be the same graph used to build the tree
+    ///
+    /// DF(d) = { v | d dominates a pred of v, d doesn't strictly dominate v }
+    pub fn frontiers<W: Weight, G: GraphView<V, W>>(
+        &self,
+        g: &G,
+    ) -> HashMap<V, Vec<V>> {
+        let mut preds = MV::new();
+
+        for (u, v, _) in g.edges() {
+            if self.contains(u) && self.contains(v) {
+                apush!(preds => v => u);
+            }
+        }
+
+        let mut df: HashMap<V, Vec<V>> = HashMap::new();
+
+        for (v, ps) in preds.0.iter() {
+            // only join point (or root on cycle) has frontier
+            if ps.len() < 2 && *v != self.root {
+                continue;
+            }
+
+            let stop = self.idom.get(v).cloned();
+
+            for &p in ps.iter() {
+                let mut runner = p;
+
+                while Some(runner) != stop {
+                    let ent = df.entry(runner).or_default();
+
+                    if !ent.contains(v) {
+                        ent.push(*v);
+                    }
+
+                    if runner == self.root {
+                        break;
+                    }
+
+                    runner = get!(self.idom => runner);
+                }
+            }
+        }
+
+        for vs in df.values_mut() {
+            vs.sort_unstable();
+        }
+
+        df
+    }
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Functions
+
+/// Lengauer-Tarjan (simple version, path compression), O(m*log(n))
+pub fn dom_lengauer_tarjan<V: Vertex, W: Weight, G: GraphView<V, W>>(
+    g: &G,
+    root: V,
+) -> DomTree<V> {
+    /* dfs numbering */
+
+    let mut dfn = HashMap::new();
+    let mut vertex = vec![];
+    let mut parent = vec![];
+    let mut stack = vec![(root, NIL)];
+
+    while let Some((u, p)) = stack.pop() {
+        if dfn.contains_key(&u) {
+            continue;
+        }
+
+        set!(dfn => u => vertex.len());
+        vertex.push(u);
+        parent.push(p);
+
+        let i = vertex.len() - 1;
+
+        for (v, _) in g.adj(u).collect::<Vec<_>>().into_iter().rev() {
+            if !dfn.contains_key(&v) {
+                stack.push((v, i));
+            }
+        }
+    }
+
+    let n = vertex.len();
+    let mut pred = vec![vec![]; n];
+
+    for (i, &u) in vertex.iter().enumerate() {
+        for (v, _) in g.adj(u) {
+            pred[get!(dfn => v)].push(i);
+        }
+    }
+
+    /* semi-dominators, from bottom to top */
+
+    let mut semi = (0..n).collect::<Vec<usize>>();
+    let mut label = (0..n).collect::<Vec<usize>>();
+    let mut ancestor = vec![NIL; n];
+    let mut idom = vec![NIL; n];
+    let mut bucket = vec![vec![]; n];
+
+    fn eval(
+        v: usize,
+        ancestor: &mut [usize],
+        label: &mut [usize],
+        semi: &[usize],
+    ) -> usize {
+        if ancestor[v] == NIL {
+            return v;
+        }
+
+        // compress
+        let mut stack = vec![];
+        let mut x = v;
+
+        while ancestor[ancestor[x]] != NIL {
+            stack.push(x);
+            x = ancestor[x];
+        }
+
+        while let Some(x) = stack.pop() {
+            let a = ancestor[x];
+
+            if semi[label[a]] < semi[label[x]] {
+                label[x] = label[a];
+            }
+
+            ancestor[x] = ancestor[a];
+        }
+
+        label[v]
+    }
+
+    for w in (1..n).rev() {
+        for &v in pred[w].iter() {
+            let u = eval(v, &mut ancestor, &mut label, &semi);
+
+            if semi[u] < semi[w] {
+                semi[w] = semi[u];
+            }
+        }
+
+        bucket[semi[w]].push(w);
+
+        let p = parent[w];
+        ancestor[w] = p;
+
+        for v in std::mem::take(&mut bucket[p]) {
+            let u = eval(v, &mut ancestor, &mut label, &semi);
+
+            idom[v] = if semi[u] < semi[v] { u } else { p };
+        }
+    }
+
+    /* implicit idom */
+
+    for w in 1..n {
+        if idom[w] != semi[w] {
+            idom[w] = idom[idom[w]];
+        }
+    }
+
+    DomTree {
+        root,
+        idom: (1..n).map(|w| (vertex[w], vertex[idom[w]])).collect(),
+    }
+}
+
+
+/// Cooper-Harvey-Kennedy iterative algorithm,
+/// O(n^2) worst but simple and fast in practice
+pub fn dom_chk<V: Vertex, W: Weight, G: GraphView<V, W>>(
+    g: &G,
+    root: V,
+) -> DomTree<V> {
+    /* reverse postorder */
+
+    let mut visited = HashSet::from([root]);
+    let mut postorder = vec![];
+    let mut stack =
+        vec![(root, g.adj(root).map(|(v, _)| v).collect::<Vec<V>>())];
+
+    while let Some((u, children)) = stack.last_mut() {
+        if let Some(v) = children.pop() {
+            if visited.insert(v) {
+                let next = g.adj(v).map(|(x, _)| x).collect();
+                stack.push((v, next));
+            }
+        } else {
+            postorder.push(*u);
+            stack.pop();
+        }
+    }
+
+    let po: HashMap<V, usize> =
+        postorder.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+
+    let mut preds = MV::new();
+
+    for &u in postorder.iter() {
+        for (v, _) in g.adj(u) {
+            apush!(preds => v => u);
+        }
+    }
+
+    let mut idom = HashMap::from([(root, root)]);
+
+    let intersect = |idom: &HashMap<V, V>, mut x: V, mut y: V| {
+        while x != y {
+            while get!(po => x) < get!(po => y) {
+                x = get!(idom => x);
+            }
+
+            while get!(po => y) < get!(po => x) {
+                y = get!(idom => y);
+            }
+        }
+
+        x
+    };
+
+    let mut changed = true;
+
+    while changed {
+        changed = false;
+
+        for &v in postorder.iter().rev().skip(1) {
+            let mut new_idom = None;
+
+            for p in get!(preds => v => vec![]) {
+                if !idom.contains_key(&p) {
+                    continue;
+                }
+
+                new_idom = Some(match new_idom {
+                    None => p,
+                    Some(d) => intersect(&idom, p, d),
+                });
+            }
+
+            let new_idom = new_idom.unwrap();
+
+            if idom.get(&v) != Some(&new_idom) {
+                idom.insert(v, new_idom);
+                changed = true;
+            }
+        }
+    }
+
+    idom.remove(&root);
+
+    DomTree { root, idom }
+}
+
+
+/// Post dominators: dominators on the transpose with exit as root
+pub fn post_dom<V: Vertex, W: Weight>(g: &Graph<V, W>, exit: V) -> DomTree<V> {
+    dom_lengauer_tarjan(&g.t(), exit)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use common::Itertools;
+
+    use super::*;
+    use crate::test::GraphGenOptions;
+
+
+    /// dom(v) by definition: v is unreachable without d
+    fn brute_force<V: Vertex>(g: &Graph<V, isize>, root: V) -> DomTree<V> {
+        let reach = |removed: Option<V>| {
+            let mut visited = HashSet::new();
+            let mut stack = vec![root];
+
+            if Some(root) == removed {
+                return visited;
+            }
+
+            visited.insert(root);
+
+            while let Some(u) = stack.pop() {
+                for v in get!(g.e => u => vec![]) {
+                    if Some(v) != removed && visited.insert(v) {
+                        stack.push(v);
+                    }
+                }
+            }
+
+            visited
+        };
+
+        let all = reach(None);
+        let mut doms: HashMap<V, HashSet<V>> =
+            all.iter().map(|&v| (v, HashSet::from([v]))).collect();
+
+        for &d in all.iter() {
+            let left = reach(Some(d));
+
+            for &v in all.iter() {
+                if !left.contains(&v) {
+                    doms.get_mut(&v).unwrap().insert(d);
+                }
+            }
+        }
+
+        // idom(v) is the strict dominator dominated by all others
+        let idom = all
+            .iter()
+            .filter(|&&v| v != root)
+            .map(|&v| {
+                let strict = &doms[&v] - &HashSet::from([v]);
+                let d = strict
+                    .iter()
+                    .cloned()
+                    .find(|d| doms[d] == strict)
+                    .unwrap();
+
+                (v, d)
+            })
+            .collect();
+
+        DomTree { root, idom }
+    }
+
+    #[test]
+    fn test_dom_fixeddata() {
+        // Lengauer & Tarjan 1979, Figure 1
+        let g = Graph::from_directed_iter(
+            [
+                ('R', 'A'),
+                ('R', 'B'),
+                ('R', 'C'),
+                ('A', 'D'),
+                ('B', 'A'),
+                ('B', 'D'),
+                ('B', 'E'),
+                ('C', 'F'),
+                ('C', 'G'),
+                ('D', 'L'),
+                ('E', 'H'),
+                ('F', 'I'),
+                ('G', 'I'),
+                ('G', 'J'),
+                ('H', 'E'),
+                ('H', 'K'),
+                ('I', 'K'),
+                ('J', 'I'),
+                ('K', 'I'),
+                ('K', 'R'),
+                ('L', 'H'),
+            ]
+            .map(|(u, v)| (u, v, 1isize)),
+        );
+
+        let expect = DomTree {
+            root: 'R',
+            idom: "ARBRCRDRERFCGCHRIRJGKRLD"
+                .chars()
+                .tuples()
+                .collect(),
+        };
+
+        assert_eq!(dom_lengauer_tarjan(&g, 'R'), expect);
+        assert_eq!(dom_chk(&g, 'R'), expect);
+        assert!(expect.dominates('C', 'J'));
+        assert!(!expect.dominates('B', 'E'));
+        assert_eq!(expect.to_graph::<isize>().edges().count(), 12);
+
+        /* diamond */
+
+        let g = Graph::from_directed_iter([
+            (1, 2, 1),
+            (1, 3, 1),
+            (2, 4, 1),
+            (3, 4, 1),
+            (4, 5, 1),
+        ]);
+
+        let dom = dom_lengauer_tarjan(&g, 1);
+        let df = dom.frontiers(&g);
+
+        assert_eq!(df[&2], vec![4]);
+        assert_eq!(df[&3], vec![4]);
+        assert!(!df.contains_key(&1) && !df.contains_key(&4));
+
+        let pdom = post_dom(&g, 5);
+
+        assert_eq!(pdom.idom[&1], 4);
+        assert_eq!(pdom.idom[&2], 4);
+        assert_eq!(pdom.idom[&4], 5);
+        assert_eq!(pdom.frontiers(&g.t())[&2], vec![1]);
+    }
+
+    #[test]
+    fn test_dom_randomdata() {
+        let opt = GraphGenOptions {
+            is_dir: true,
+            allow_cycle: true,
+            non_negative_cycle: false,
+            weak_conn: false,
+        };
+
+        for _ in 0..50 {
+            let g = Graph::generate(&opt, 30, 1, 1..2);
+            let root = g.anypoint();
+
+            let expect = brute_force(&g, root);
+
+            assert_eq!(dom_lengauer_tarjan(&g, root), expect);
+            assert_eq!(dom_chk(&g, root), expect);
+
+            /* frontiers by definition */
+
+            let df = expect.frontiers(&g);
+
+            for d in g.vertexs().filter(|&v| expect.contains(v)) {
+                let df_d = g
+                    .edges()
+                    .filter(|&(u, v, _)| {
+                        expect.contains(u)
+                            && expect.dominates(d, u)
+                            && (d == v || !expect.dominates(d, v))
+                    })
+                    .map(|(_, v, _)| v)
+                    .unique()
+                    .sorted()
+                    .collect_vec();
+
+                assert_eq!(df.get(&d).cloned().unwrap_or_default(), df_d);
+            }
+        }
+    }
+}
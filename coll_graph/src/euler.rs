@@ -0,0 +1,303 @@
+//! Eulerian path / circuit (Hierholzer), every edge is passed exactly once
+//!
+
+use std::collections::HashMap;
+
+use common::Itertools;
+
+use crate::{Graph, Vertex, Weight};
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Structures
+
+/// Why there is no Eulerian path / circuit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EulerError<V> {
+    /// There is no edge
+    Empty,
+    /// Edges lie in more than one (weakly) connected components
+    Disconnected(Vec<Vec<V>>),
+    /// Unbalanced vertexs (sorted):
+    ///
+    /// directed: (v, out-degree - in-degree), path allows one +1 and one -1;
+    ///
+    /// undirected: (v, degree) of odd degree, path allows two of them.
+    Unbalanced(Vec<(V, isize)>),
+    /// The specified start can't start the path / circuit
+    BadStart(V),
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Functions
+
+/// Eulerian path v0, v1, ..., vm (m is edge number),
+/// start from src if it's specified
+pub fn euler_path<V: Vertex, W: Weight>(
+    g: &Graph<V, W>,
+    src: Option<V>,
+) -> Result<Vec<V>, EulerError<V>> {
+    euler(g, src, false)
+}
+
+
+/// Eulerian circuit v0, v1, ..., vm (= v0),
+/// start from src if it's specified
+pub fn euler_circuit<V: Vertex, W: Weight>(
+    g: &Graph<V, W>,
+    src: Option<V>,
+) -> Result<Vec<V>, EulerError<V>> {
+    euler(g, src, true)
+}
+
+
+fn euler<V: Vertex, W: Weight>(
+    g: &Graph<V, W>,
+    src: Option<V>,
+    circuit: bool,
+) -> Result<Vec<V>, EulerError<V>> {
+    /* edge list, each undirected edge occurs once */
+
+    let edges = if g.is_dir {
+        g.edges().map(|(u, v, _)| (u, v)).collect_vec()
+    } else {
+        g.edges()
+            .filter(|(u, v, _)| u <= v)
+            .map(|(u, v, _)| (u, v))
+            .unique()
+            .collect_vec()
+    };
+
+    if edges.is_empty() {
+        return Err(EulerError::Empty);
+    }
+
+    /* connectivity */
+
+    let comps = Graph::from_undirected_iter(
+        edges.iter().map(|&(u, v)| (u, v, 1)),
+    )
+    .components();
+
+    if comps.len() > 1 {
+        return Err(EulerError::Disconnected(comps));
+    }
+
+    /* degree */
+
+    let mut adj: HashMap<V, Vec<(V, usize)>> = HashMap::new();
+    let mut diff: HashMap<V, isize> = HashMap::new();
+
+    for (i, &(u, v)) in edges.iter().enumerate() {
+        adj.entry(u).or_default().push((v, i));
+
+        if g.is_dir {
+            adj.entry(v).or_default();
+
+            *diff.entry(u).or_default() += 1;
+            *diff.entry(v).or_default() -= 1;
+        } else {
+            adj.entry(v).or_default().push((u, i));
+
+            *diff.entry(u).or_default() += 1;
+            *diff.entry(v).or_default() += 1;
+        }
+    }
+
+    let unbalanced = diff
+        .into_iter()
+        .filter(|&(_, d)| if g.is_dir { d != 0 } else { d % 2 != 0 })
+        .sorted()
+        .collect_vec();
+
+    let starts = if unbalanced.is_empty() {
+        comps[0].clone()
+    } else if circuit {
+        return Err(EulerError::Unbalanced(unbalanced));
+    } else if g.is_dir {
+        let ok = unbalanced.len() == 2
+            && unbalanced.iter().map(|&(_, d)| d).sorted().eq([-1, 1]);
+
+        if !ok {
+            return Err(EulerError::Unbalanced(unbalanced));
+        }
+
+        unbalanced.iter().filter(|(_, d)| *d > 0).map(|(v, _)| *v).collect()
+    } else {
+        if unbalanced.len() != 2 {
+            return Err(EulerError::Unbalanced(unbalanced));
+        }
+
+        unbalanced.iter().map(|(v, _)| *v).collect()
+    };
+
+    let src = match src {
+        Some(src) if starts.contains(&src) => src,
+        Some(src) => return Err(EulerError::BadStart(src)),
+        None => starts[0],
+    };
+
+    /* Hierholzer */
+
+    let mut used = vec![false; edges.len()];
+    let mut cur: HashMap<V, usize> = HashMap::new();
+    let mut stack = vec![src];
+    let mut path = Vec::with_capacity(edges.len() + 1);
+
+    while let Some(&u) = stack.last() {
+        let tos = &adj[&u];
+        let i = cur.entry(u).or_default();
+
+        while *i < tos.len() && used[tos[*i].1] {
+            *i += 1;
+        }
+
+        if let Some(&(v, e)) = tos.get(*i) {
+            used[e] = true;
+            stack.push(v);
+        } else {
+            path.push(u);
+            stack.pop();
+        }
+    }
+
+    path.reverse();
+
+    debug_assert_eq!(path.len(), edges.len() + 1);
+
+    Ok(path)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use common::{thread_rng, Itertools};
+
+    use super::*;
+    use crate::test::{model::gen_gnp, path::Path};
+
+
+    /// every edge is used exactly once
+    fn assert_euler<V: Vertex>(g: &Graph<V, isize>, path: &[V]) {
+        let norm = |u: V, v: V| {
+            if g.is_dir || u <= v { (u, v) } else { (v, u) }
+        };
+
+        let edges: HashSet<(V, V)> =
+            g.edges().map(|(u, v, _)| norm(u, v)).collect();
+
+        let p = Path::new(g, path);
+        let used = p.iter().map(|(u, v, _)| norm(u, v)).collect_vec();
+
+        assert_eq!(used.len(), edges.len(), "{path:?}");
+        assert_eq!(used.into_iter().collect::<HashSet<_>>(), edges);
+        assert_eq!(
+            p.weight(),
+            edges.iter().map(|&(u, v)| g.w[&(u, v)]).sum::<isize>()
+        );
+    }
+
+    #[test]
+    fn test_euler_fixeddata() {
+        use EulerError::*;
+
+        /* undirected: house with a cross */
+
+        let g = Graph::from_undirected_iter(
+            [(1, 2), (1, 3), (2, 3), (2, 4), (3, 4), (2, 5), (3, 5), (4, 5)]
+                .map(|(u, v)| (u, v, u + v)),
+        );
+
+        let path = euler_path(&g, None).unwrap();
+        assert!(path[0] == 4 || path[0] == 5);
+        assert_euler(&g, &path);
+
+        let path = euler_path(&g, Some(5)).unwrap();
+        assert_eq!((path[0], path[8]), (5, 4));
+
+        assert_eq!(euler_path(&g, Some(1)), Err(BadStart(1)));
+        assert_eq!(
+            euler_circuit(&g, None),
+            Err(Unbalanced(vec![(4, 3), (5, 3)]))
+        );
+
+        /* directed */
+
+        let g = Graph::from_directed_iter(
+            [(1, 2), (2, 3), (3, 1), (3, 4), (4, 5), (5, 3)]
+                .map(|(u, v)| (u, v, 1)),
+        );
+
+        let cycle = euler_circuit(&g, Some(4)).unwrap();
+        assert_eq!(cycle.first(), cycle.last());
+        assert_euler(&g, &cycle);
+
+        let g = Graph::from_directed_iter(
+            [(1, 2), (2, 3), (3, 1), (1, 4)].map(|(u, v)| (u, v, 1)),
+        );
+
+        assert_euler(&g, &euler_path(&g, None).unwrap());
+        assert_eq!(
+            euler_circuit(&g, None),
+            Err(Unbalanced(vec![(1, 1), (4, -1)]))
+        );
+
+        let g = Graph::from_directed_iter(
+            [(1, 2), (1, 3), (1, 4)].map(|(u, v)| (u, v, 1)),
+        );
+
+        assert_eq!(
+            euler_path(&g, None),
+            Err(Unbalanced(vec![(1, 3), (2, -1), (3, -1), (4, -1)]))
+        );
+
+        let g = Graph::from_directed_iter(
+            [(1, 2), (2, 1), (3, 4), (4, 3)].map(|(u, v)| (u, v, 1)),
+        );
+
+        assert_eq!(
+            euler_circuit(&g, None),
+            Err(Disconnected(vec![vec![1, 2], vec![3, 4]]))
+        );
+        assert_eq!(euler_path(&Graph::<usize>::new(), None), Err(Empty));
+    }
+
+    #[test]
+    fn test_euler_randomdata() {
+        let mut rng = thread_rng();
+
+        for is_dir in [false, true] {
+            for _ in 0..200 {
+                let g = gen_gnp(8, 0.5, is_dir, 1..10, &mut rng);
+
+                let res = euler_path(&g, None);
+
+                match res {
+                    Ok(path) => assert_euler(&g, &path),
+                    Err(EulerError::Empty) => {
+                        assert_eq!(g.edges().count(), 0)
+                    }
+                    Err(EulerError::Disconnected(comps)) => {
+                        assert!(comps.len() > 1)
+                    }
+                    Err(EulerError::Unbalanced(vs)) => {
+                        let ds = vs.iter().map(|&(_, d)| d).sorted();
+
+                        assert!(vs.len() > 2 || is_dir && !ds.eq([-1, 1]));
+                    }
+                    Err(EulerError::BadStart(_)) => unreachable!(),
+                }
+
+                if let Ok(cycle) = euler_circuit(&g, None) {
+                    assert_eq!(cycle.first(), cycle.last());
+                    assert_euler(&g, &cycle);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,926 @@
+//! Network flow (最大流 / 最小割)
+//!
+//! 把 `Graph::w` 当作边的容量 (capacity)，无向图的每条边视为两条反向的有向边
+//!
+
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use coll::{apush, get, getopt, set};
+use coll_heap::dary;
+use common::Itertools;
+
+use crate::{sp::johnson_potential, Graph};
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Structures
+
+/// Max-flow result
+#[allow(unused)]
+pub struct MaxFlow<'a> {
+    g: &'a Graph,
+    src: usize,
+    sink: usize,
+    /// max flow value
+    pub value: isize,
+    /// flow of each edge of `g` (zero flow edge omitted)
+    pub flow: HashMap<(usize, usize), isize>,
+}
+
+
+/// Min-cost max-flow result
+#[allow(unused)]
+pub struct MinCostFlow<'a> {
+    g: &'a Graph,
+    cost_g: &'a Graph,
+    src: usize,
+    sink: usize,
+    /// max flow value
+    pub value: isize,
+    /// min cost of the max flow
+    pub cost: isize,
+    /// flow of each edge of `g` (zero flow edge omitted)
+    pub flow: HashMap<(usize, usize), isize>,
+}
+
+
+/// Residual network (残量网络)
+///
+/// 2k: k-th edge's forward arc, 2k+1: its backward arc
+struct Network {
+    /// vertex -> arc ids
+    adj: Vec<Vec<usize>>,
+    /// arc -> (u, v)
+    arcs: Vec<(usize, usize)>,
+    /// residual capacity
+    cap: Vec<isize>,
+    /// cost of unit flow, c(v, u) = -c(u, v)
+    cost: Vec<isize>,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Implementations
+
+impl<'a> MaxFlow<'a> {
+    pub fn dinic(g: &'a Graph, src: usize, sink: usize) -> Self {
+        Self::new(g, src, sink, max_flow_dinic)
+    }
+
+    pub fn edmonds_karp(g: &'a Graph, src: usize, sink: usize) -> Self {
+        Self::new(g, src, sink, max_flow_edmonds_karp)
+    }
+
+    pub fn push_relabel(g: &'a Graph, src: usize, sink: usize) -> Self {
+        Self::new(g, src, sink, max_flow_push_relabel)
+    }
+
+    fn new(
+        g: &'a Graph,
+        src: usize,
+        sink: usize,
+        f: fn(&Graph, usize, usize) -> (isize, HashMap<(usize, usize), isize>),
+    ) -> Self {
+        let (value, flow) = f(g, src, sink);
+
+        Self {
+            g,
+            src,
+            sink,
+            value,
+            flow,
+        }
+    }
+
+    /// Min-cut vertex partition (S, T), src ∈ S, sink ∈ T, both sorted
+    ///
+    /// S is the reachable set from src on the residual network
+    pub fn min_cut(&self) -> (Vec<usize>, Vec<usize>) {
+        let g = self.g;
+
+        let residual = |u: usize, v: usize| {
+            getopt!(g.w => (u, v)).unwrap_or(0)
+                - getopt!(self.flow => (u, v)).unwrap_or(0)
+                + getopt!(self.flow => (v, u)).unwrap_or(0)
+        };
+
+        // 反向弧 (v, u) 上有流量同样可达
+        let mut flow_rev = vec![];
+
+        for (u, v) in self.flow.keys().cloned() {
+            apush!(flow_rev => v => u);
+        }
+
+        let mut s_side = HashSet::new();
+        let mut queue = VecDeque::from([self.src]);
+
+        s_side.insert(self.src);
+
+        while let Some(u) = queue.pop_front() {
+            for v in get!(g.e => u => vec![])
+                .into_iter()
+                .chain(get!(flow_rev => u => vec![]))
+            {
+                if !s_side.contains(&v) && residual(u, v) > 0 {
+                    s_side.insert(v);
+                    queue.push_back(v);
+                }
+            }
+        }
+
+        g.vertexs().partition(|v| s_side.contains(v))
+    }
+}
+
+
+impl<'a> MinCostFlow<'a> {
+    /// `g` as capacity and `cost_g` (with same edges) as cost
+    ///
+    /// Err(negative cycle of cost_g)
+    pub fn new(
+        g: &'a Graph,
+        cost_g: &'a Graph,
+        src: usize,
+        sink: usize,
+    ) -> Result<Self, Vec<usize>> {
+        let (value, cost, flow) = min_cost_flow_ssp(g, cost_g, src, sink)?;
+
+        Ok(Self {
+            g,
+            cost_g,
+            src,
+            sink,
+            value,
+            cost,
+            flow,
+        })
+    }
+}
+
+
+impl Network {
+    fn new(g: &Graph) -> Self {
+        let n = g.vertexs().max().map(|x| x + 1).unwrap_or(0);
+
+        let mut adj = vec![vec![]; n];
+        let mut arcs = vec![];
+        let mut cap = vec![];
+        let cost = vec![];
+
+        // g.e 可能有重复边
+        for (u, v, w) in g.edges().unique() {
+            debug_assert!(w >= 0, "negative capacity {w} for {u}->{v}");
+
+            adj[u].push(arcs.len());
+            arcs.push((u, v));
+            cap.push(w);
+
+            adj[v].push(arcs.len());
+            arcs.push((v, u));
+            cap.push(0);
+        }
+
+        Self { adj, arcs, cap, cost }
+    }
+
+    fn with_cost(g: &Graph, cost_g: &Graph) -> Self {
+        let mut net = Self::new(g);
+
+        for i in (0..net.arcs.len()).step_by(2) {
+            let c = get!(cost_g.w => net.arcs[i]);
+
+            net.cost.push(c);
+            net.cost.push(-c);
+        }
+
+        net
+    }
+
+    fn push(&mut self, arc: usize, delta: isize) {
+        self.cap[arc] -= delta;
+        self.cap[arc ^ 1] += delta;
+    }
+
+    /// Collect (max flow value, edge flow)
+    fn collect(
+        self,
+        src: usize,
+    ) -> (isize, HashMap<(usize, usize), isize>) {
+        let mut flow = HashMap::new();
+
+        for i in (0..self.arcs.len()).step_by(2) {
+            // flow on the forward arc is just the residual of the backward arc
+            let f = self.cap[i ^ 1];
+
+            if f > 0 {
+                let (u, v) = self.arcs[i];
+
+                set!(flow => (u, v) => get!(flow => (u, v) => 0) + f);
+            }
+        }
+
+        /* cancel flow on antiparallel edges (u, v) and (v, u) */
+
+        let antiparallel = flow
+            .keys()
+            .filter(|(u, v)| u < v && flow.contains_key(&(*v, *u)))
+            .cloned()
+            .collect::<Vec<(usize, usize)>>();
+
+        for (u, v) in antiparallel {
+            let f_uv = get!(flow => (u, v));
+            let f_vu = get!(flow => (v, u));
+            let common = f_uv.min(f_vu);
+
+            set!(flow => (u, v) => f_uv - common);
+            set!(flow => (v, u) => f_vu - common);
+        }
+
+        flow.retain(|_, f| *f > 0);
+
+        let value = flow
+            .iter()
+            .map(|(&(u, v), &f)| {
+                if u == src {
+                    f
+                } else if v == src {
+                    -f
+                } else {
+                    0
+                }
+            })
+            .sum();
+
+        (value, flow)
+    }
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Functions
+
+/// 分层图 + 多路增广 (当前弧优化)
+///
+/// O(v^2e), O(e√v) for unit capacity network
+pub fn max_flow_dinic(
+    g: &Graph,
+    src: usize,
+    sink: usize,
+) -> (isize, HashMap<(usize, usize), isize>) {
+    debug_assert_ne!(src, sink);
+
+    fn dfs(
+        net: &mut Network,
+        u: usize,
+        sink: usize,
+        limit: isize,
+        level: &Vec<Option<usize>>,
+        cur: &mut Vec<usize>,
+    ) -> isize {
+        if u == sink {
+            return limit;
+        }
+
+        let mut pushed = 0;
+
+        while cur[u] < net.adj[u].len() {
+            let arc = net.adj[u][cur[u]];
+            let (_, v) = net.arcs[arc];
+
+            if net.cap[arc] > 0 && level[v] == level[u].map(|l| l + 1) {
+                let d = dfs(
+                    net,
+                    v,
+                    sink,
+                    (limit - pushed).min(net.cap[arc]),
+                    level,
+                    cur,
+                );
+
+                if d > 0 {
+                    net.push(arc, d);
+                    pushed += d;
+
+                    if pushed == limit {
+                        return pushed;
+                    }
+                }
+            }
+
+            cur[u] += 1;
+        }
+
+        pushed
+    }
+
+    let mut net = Network::new(g);
+    let n = net.adj.len();
+
+    if src >= n || sink >= n {
+        return (0, HashMap::new());
+    }
+
+    loop {
+        /* build level graph */
+
+        let mut level = vec![None; n];
+        let mut queue = VecDeque::from([src]);
+
+        level[src] = Some(0);
+
+        while let Some(u) = queue.pop_front() {
+            for arc in net.adj[u].iter().cloned() {
+                let (_, v) = net.arcs[arc];
+
+                if net.cap[arc] > 0 && level[v].is_none() {
+                    level[v] = level[u].map(|l| l + 1);
+                    queue.push_back(v);
+                }
+            }
+        }
+
+        if level[sink].is_none() {
+            break;
+        }
+
+        /* blocking flow */
+
+        let mut cur = vec![0; n];
+
+        while dfs(&mut net, src, sink, isize::MAX, &level, &mut cur) > 0 {}
+    }
+
+    net.collect(src)
+}
+
+
+/// BFS 找最短增广路 (Ford-Fulkerson)
+///
+/// O(ve^2)
+pub fn max_flow_edmonds_karp(
+    g: &Graph,
+    src: usize,
+    sink: usize,
+) -> (isize, HashMap<(usize, usize), isize>) {
+    debug_assert_ne!(src, sink);
+
+    let mut net = Network::new(g);
+    let n = net.adj.len();
+
+    if src >= n || sink >= n {
+        return (0, HashMap::new());
+    }
+
+    loop {
+        // vertex -> arc into it
+        let mut pre: Vec<Option<usize>> = vec![None; n];
+        let mut queue = VecDeque::from([src]);
+
+        'bfs: while let Some(u) = queue.pop_front() {
+            for arc in net.adj[u].iter().cloned() {
+                let (_, v) = net.arcs[arc];
+
+                if net.cap[arc] > 0 && v != src && pre[v].is_none() {
+                    pre[v] = Some(arc);
+
+                    if v == sink {
+                        break 'bfs;
+                    }
+
+                    queue.push_back(v);
+                }
+            }
+        }
+
+        if pre[sink].is_none() {
+            break;
+        }
+
+        /* find bottleneck */
+
+        let mut bottleneck = isize::MAX;
+        let mut v = sink;
+
+        while let Some(arc) = pre[v] {
+            bottleneck = bottleneck.min(net.cap[arc]);
+            v = net.arcs[arc].0;
+        }
+
+        /* augment */
+
+        let mut v = sink;
+
+        while let Some(arc) = pre[v] {
+            net.push(arc, bottleneck);
+            v = net.arcs[arc].0;
+        }
+    }
+
+    net.collect(src)
+}
+
+
+/// 预流推进 (Goldberg-Tarjan), FIFO 选点 + 当前弧优化
+///
+/// O(v^3)
+pub fn max_flow_push_relabel(
+    g: &Graph,
+    src: usize,
+    sink: usize,
+) -> (isize, HashMap<(usize, usize), isize>) {
+    debug_assert_ne!(src, sink);
+
+    let mut net = Network::new(g);
+    let n = net.adj.len();
+
+    if src >= n || sink >= n {
+        return (0, HashMap::new());
+    }
+
+    let mut height = vec![0; n];
+    let mut excess = vec![0isize; n];
+    let mut cur = vec![0; n];
+    let mut active = VecDeque::new();
+
+    height[src] = n;
+
+    /* saturate all arcs out of src */
+
+    for arc in net.adj[src].clone() {
+        let c = net.cap[arc];
+
+        if c > 0 {
+            let (_, v) = net.arcs[arc];
+
+            net.push(arc, c);
+            excess[src] -= c;
+            excess[v] += c;
+
+            if v != sink && excess[v] == c {
+                active.push_back(v);
+            }
+        }
+    }
+
+    while let Some(u) = active.pop_front() {
+        /* discharge */
+
+        while excess[u] > 0 {
+            if cur[u] == net.adj[u].len() {
+                // relabel
+                height[u] = net.adj[u]
+                    .iter()
+                    .filter(|&&arc| net.cap[arc] > 0)
+                    .map(|&arc| height[net.arcs[arc].1] + 1)
+                    .min()
+                    .unwrap();
+                cur[u] = 0;
+
+                continue;
+            }
+
+            let arc = net.adj[u][cur[u]];
+            let (_, v) = net.arcs[arc];
+
+            if net.cap[arc] > 0 && height[u] == height[v] + 1 {
+                let d = excess[u].min(net.cap[arc]);
+
+                net.push(arc, d);
+                excess[u] -= d;
+                excess[v] += d;
+
+                if v != src && v != sink && excess[v] == d {
+                    active.push_back(v);
+                }
+            } else {
+                cur[u] += 1;
+            }
+        }
+    }
+
+    net.collect(src)
+}
+
+
+
+/// Successive Shortest Path (SSP), 每次沿费用最短路增广
+///
+/// 初始势能用 Johnson 重赋权 (允许负费用边), 之后每轮用 Dijkstra 的最短距离更新势能,
+/// 使得残量网络上的约化费用 c(u, v) + h(u) - h(v) 保持非负
+///
+/// O(f * elogv), f is max flow value
+///
+/// Ok((max flow value, min cost, edge flow)), Err(negative cycle of cost_g)
+pub fn min_cost_flow_ssp(
+    g: &Graph,
+    cost_g: &Graph,
+    src: usize,
+    sink: usize,
+) -> Result<(isize, isize, HashMap<(usize, usize), isize>), Vec<usize>> {
+    debug_assert!(g.is_dir);
+    debug_assert_ne!(src, sink);
+
+    let mut net = Network::with_cost(g, cost_g);
+    let n = net.adj.len();
+
+    if src >= n || sink >= n {
+        return Ok((0, 0, HashMap::new()));
+    }
+
+    /* init potential */
+
+    let mut h = vec![0; n];
+
+    if cost_g.w.values().any(|c| *c < 0) {
+        let (_g2, h0) = johnson_potential(cost_g).map_err(|(_g2, cycle)| cycle)?;
+
+        for (v, hv) in h0 {
+            if v < n {
+                h[v] = hv;
+            }
+        }
+    }
+
+    loop {
+        /* Dijkstra on reduced cost */
+
+        let mut dis: Vec<Option<isize>> = vec![None; n];
+        // vertex -> arc into it
+        let mut pre: Vec<Option<usize>> = vec![None; n];
+        let mut heap = dary::DaryHeap::<3, _, _>::new();
+
+        heap.insert(src, 0);
+
+        while let Some((u, dis_u)) = heap.pop_item() {
+            dis[u] = Some(dis_u);
+
+            for arc in net.adj[u].iter().cloned() {
+                let (_, v) = net.arcs[arc];
+
+                if net.cap[arc] == 0 || dis[v].is_some() {
+                    continue;
+                }
+
+                let d = dis_u + net.cost[arc] + h[u] - h[v];
+
+                debug_assert!(d >= dis_u, "negative reduced cost");
+
+                if heap.get(&v).map_or(true, |dis_v| d < *dis_v) {
+                    heap.insert(v, d);
+                    pre[v] = Some(arc);
+                }
+            }
+        }
+
+        if dis[sink].is_none() {
+            break;
+        }
+
+        /* update potential */
+
+        let max_dis = dis.iter().filter_map(|x| *x).max().unwrap();
+
+        for v in 0..n {
+            // 不可达点的入弧都已满流，加上最大距离保证其出弧约化费用非负
+            h[v] += dis[v].unwrap_or(max_dis);
+        }
+
+        /* find bottleneck */
+
+        let mut bottleneck = isize::MAX;
+        let mut v = sink;
+
+        while let Some(arc) = pre[v] {
+            bottleneck = bottleneck.min(net.cap[arc]);
+            v = net.arcs[arc].0;
+        }
+
+        /* augment */
+
+        let mut v = sink;
+
+        while let Some(arc) = pre[v] {
+            net.push(arc, bottleneck);
+            v = net.arcs[arc].0;
+        }
+    }
+
+    let (value, flow) = net.collect(src);
+
+    let cost = flow
+        .iter()
+        .map(|(e, f)| f * get!(cost_g.w => e))
+        .sum();
+
+    Ok((value, cost, flow))
+}
+
+
+/// Hungarian algorithm (Kuhn-Munkres) for assignment problem
+///
+/// cost: n x m (n <= m) dense matrix, each row (job) assigned to a distinct
+/// column (worker) with minimal total cost
+///
+/// O(n^2m)
+///
+/// (min total cost, column assigned to each row)
+pub fn assignment_hungarian(cost: &[Vec<isize>]) -> (isize, Vec<usize>) {
+    let n = cost.len();
+
+    if n == 0 {
+        return (0, vec![]);
+    }
+
+    let m = cost[0].len();
+
+    assert!(n <= m, "rows {n} > columns {m}");
+
+    // 1-based, row 0/column 0 is virtual
+    let mut u = vec![0isize; n + 1];
+    let mut v = vec![0isize; m + 1];
+    // column -> row matched
+    let mut p = vec![0usize; m + 1];
+    let mut way = vec![0usize; m + 1];
+
+    for i in 1..=n {
+        p[0] = i;
+
+        let mut j0 = 0;
+        let mut minv = vec![isize::MAX; m + 1];
+        let mut used = vec![false; m + 1];
+
+        loop {
+            used[j0] = true;
+
+            let i0 = p[j0];
+            let mut delta = isize::MAX;
+            let mut j1 = 0;
+
+            for j in 1..=m {
+                if used[j] {
+                    continue;
+                }
+
+                let cur = cost[i0 - 1][j - 1] - u[i0] - v[j];
+
+                if cur < minv[j] {
+                    minv[j] = cur;
+                    way[j] = j0;
+                }
+
+                if minv[j] < delta {
+                    delta = minv[j];
+                    j1 = j;
+                }
+            }
+
+            for j in 0..=m {
+                if used[j] {
+                    u[p[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    minv[j] -= delta;
+                }
+            }
+
+            j0 = j1;
+
+            if p[j0] == 0 {
+                break;
+            }
+        }
+
+        /* augment along the alternating path */
+
+        loop {
+            let j1 = way[j0];
+            p[j0] = p[j1];
+            j0 = j1;
+
+            if j0 == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut assign = vec![0; n];
+
+    for j in 1..=m {
+        if p[j] > 0 {
+            assign[p[j] - 1] = j - 1;
+        }
+    }
+
+    let tot = assign
+        .iter()
+        .enumerate()
+        .map(|(i, j)| cost[i][*j])
+        .sum();
+
+    (tot, assign)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use common::random_range;
+
+    use super::*;
+    use crate::test::{batch_graph, GraphGenOptions};
+
+    #[test]
+    fn test_max_flow_fixeddata() {
+        // CLRS 26.1
+        let g = Graph::from_directed_iter([
+            (1, 2, 16),
+            (1, 3, 13),
+            (3, 2, 4),
+            (2, 4, 12),
+            (4, 3, 9),
+            (3, 5, 14),
+            (5, 4, 7),
+            (4, 6, 20),
+            (5, 6, 4),
+        ]);
+
+        for mf in [
+            MaxFlow::dinic(&g, 1, 6),
+            MaxFlow::edmonds_karp(&g, 1, 6),
+            MaxFlow::push_relabel(&g, 1, 6),
+        ] {
+            assert_eq!(mf.value, 23);
+
+            let (s, t) = mf.min_cut();
+
+            assert_eq!(s, vec![1, 2, 3, 5]);
+            assert_eq!(t, vec![4, 6]);
+            assert_eq!(g.verify_max_flow(1, 6, mf.value, &mf.flow, &s), Ok(()));
+        }
+
+        /* unreachable sink */
+
+        let g = Graph::from_directed_iter([(1, 2, 3), (3, 2, 5)]);
+        let mf = MaxFlow::dinic(&g, 1, 3);
+
+        assert_eq!(mf.value, 0);
+        assert_eq!(mf.min_cut(), (vec![1, 2], vec![3]));
+    }
+
+    #[test]
+    fn test_max_flow_randomdata() {
+        for opt in [GraphGenOptions::dir_conn(), GraphGenOptions::undir_conn()]
+        {
+            for g in batch_graph(30, 40, 1..50, &opt) {
+                let vertexs = g.vertexs().collect::<Vec<usize>>();
+                let src = vertexs[0];
+                let sink = *vertexs.last().unwrap();
+
+                let mf_dinic = MaxFlow::dinic(&g, src, sink);
+                let mf_ek = MaxFlow::edmonds_karp(&g, src, sink);
+                let mf_pr = MaxFlow::push_relabel(&g, src, sink);
+
+                assert_eq!(mf_dinic.value, mf_ek.value);
+                assert_eq!(mf_dinic.value, mf_pr.value);
+
+                for mf in [mf_dinic, mf_ek, mf_pr] {
+                    let (s, _t) = mf.min_cut();
+
+                    assert_eq!(
+                        g.verify_max_flow(src, sink, mf.value, &mf.flow, &s),
+                        Ok(())
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_min_cost_flow_fixeddata() {
+        let edges = [
+            (1, 2, 4, 1),
+            (1, 3, 2, 2),
+            (2, 3, 2, 1),
+            (2, 4, 3, 3),
+            (3, 4, 5, 1),
+        ];
+
+        let g = Graph::from_directed_iter(edges.map(|(u, v, c, _)| (u, v, c)));
+        let cost_g =
+            Graph::from_directed_iter(edges.map(|(u, v, _, w)| (u, v, w)));
+
+        let mcf = MinCostFlow::new(&g, &cost_g, 1, 4).unwrap();
+
+        assert_eq!(mcf.value, 6);
+        assert_eq!(mcf.cost, 20);
+        assert_eq!(
+            g.verify_min_cost_flow(&cost_g, 1, 4, mcf.value, mcf.cost, &mcf.flow),
+            Ok(())
+        );
+
+        /* negative cost edge */
+
+        let cost_g = Graph::from_directed_iter(
+            edges.map(|(u, v, _, w)| (u, v, if u == 2 && v == 3 { -2 } else { w })),
+        );
+
+        let mcf = MinCostFlow::new(&g, &cost_g, 1, 4).unwrap();
+
+        assert_eq!(mcf.value, 6);
+        assert_eq!(mcf.cost, 14);
+        assert_eq!(
+            g.verify_min_cost_flow(&cost_g, 1, 4, mcf.value, mcf.cost, &mcf.flow),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_min_cost_flow_randomdata() {
+        for g in batch_graph(30, 30, 1..30, &GraphGenOptions::dir_conn()) {
+            let cost_g = Graph::from_directed_iter(
+                g.edges().unique().map(|(u, v, _)| (u, v, random_range!(0..20))),
+            );
+
+            let vertexs = g.vertexs().collect::<Vec<usize>>();
+            let src = vertexs[0];
+            let sink = *vertexs.last().unwrap();
+
+            let mcf = MinCostFlow::new(&g, &cost_g, src, sink).unwrap();
+
+            assert_eq!(mcf.value, MaxFlow::dinic(&g, src, sink).value);
+            assert_eq!(
+                g.verify_min_cost_flow(
+                    &cost_g, src, sink, mcf.value, mcf.cost, &mcf.flow
+                ),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn test_assignment_hungarian() {
+        let cost = vec![vec![4, 1, 3], vec![2, 0, 5], vec![3, 2, 2]];
+
+        assert_eq!(assignment_hungarian(&cost), (5, vec![1, 0, 2]));
+
+        for _ in 0..100 {
+            let n = random_range!(1..7);
+            let m = random_range!(n..9);
+
+            let cost = (0..n)
+                .map(|_| (0..m).map(|_| random_range!(-20..50)).collect())
+                .collect::<Vec<Vec<isize>>>();
+
+            let (tot, assign) = assignment_hungarian(&cost);
+
+            assert_eq!(assign.iter().unique().count(), n);
+            assert_eq!(
+                tot,
+                assign.iter().enumerate().map(|(i, j)| cost[i][*j]).sum()
+            );
+
+            /* brute force */
+
+            let min = (0..m)
+                .permutations(n)
+                .map(|p| p.into_iter().enumerate().map(|(i, j)| cost[i][j]).sum())
+                .min()
+                .unwrap();
+
+            assert_eq!(tot, min);
+
+            /* compare with min cost flow: 0 -> rows -> columns -> sink */
+
+            let offset = cost.iter().flatten().min().unwrap().abs() + 1;
+            let sink = n + m + 1;
+
+            let mut edges = vec![];
+            let mut costs = vec![];
+
+            for i in 1..=n {
+                edges.push((0, i, 1));
+                costs.push((0, i, 0));
+
+                for j in 1..=m {
+                    edges.push((i, n + j, 1));
+                    costs.push((i, n + j, cost[i - 1][j - 1] + offset));
+                }
+            }
+
+            for j in 1..=m {
+                edges.push((n + j, sink, 1));
+                costs.push((n + j, sink, 0));
+            }
+
+            let g = Graph::from_directed_iter(edges);
+            let cost_g = Graph::from_directed_iter(costs);
+            let mcf = MinCostFlow::new(&g, &cost_g, 0, sink).unwrap();
+
+            assert_eq!(mcf.value, n as isize);
+            assert_eq!(mcf.cost - offset * n as isize, tot);
+        }
+    }
+}
@@ -0,0 +1,273 @@
+//! Hamiltonian path / cycle (TSP) by bitmask DP (Held-Karp), for small graph
+//!
+//! O(2^n * n^2) time and O(2^n * n) space, it panics over 20 vertexs
+//! (the table is already hundreds of MB there).
+
+use common::Itertools;
+
+use crate::{Graph, Vertex, Weight};
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Functions
+
+/// Minimum weight Hamiltonian path (visit every vertex exactly once),
+/// src / dst is restricted if it's specified.
+pub fn hamilton_path<V: Vertex, W: Weight>(
+    g: &Graph<V, W>,
+    src: Option<V>,
+    dst: Option<V>,
+) -> Option<(W, Vec<V>)> {
+    let vs = g.vertexs().collect_vec();
+    let n = vs.len();
+
+    if n == 0 {
+        return None;
+    }
+
+    let idx = |v: Option<V>| v.map(|v| vs.binary_search(&v).ok());
+
+    let src = match idx(src) {
+        Some(None) => return None,
+        x => x.flatten(),
+    };
+    let dst = match idx(dst) {
+        Some(None) => return None,
+        x => x.flatten(),
+    };
+
+    let (dp, pre) = held_karp(g, &vs, |s| src.is_none_or(|src| s == src));
+    let full = (1 << n) - 1;
+
+    let (w, last) = (0..n)
+        .filter(|&v| dst.is_none_or(|dst| v == dst))
+        .filter_map(|v| dp[full][v].map(|w| (w, v)))
+        .min()?;
+
+    Some((w, restore(&vs, &pre, full, last)))
+}
+
+
+/// Minimum weight Hamiltonian cycle v0, v1, ..., v(n-1) (not repeat v0),
+/// v0 is the minimum vertex
+///
+/// Undirected cycle needs at least 3 vertexs.
+pub fn tsp_held_karp<V: Vertex, W: Weight>(
+    g: &Graph<V, W>,
+) -> Option<(W, Vec<V>)> {
+    let vs = g.vertexs().collect_vec();
+    let n = vs.len();
+
+    if n < 2 || !g.is_dir && n < 3 {
+        return None;
+    }
+
+    let (dp, pre) = held_karp(g, &vs, |s| s == 0);
+    let full = (1 << n) - 1;
+
+    let (w, last) = (1..n)
+        .filter(|&v| g.contains_edge((vs[v], vs[0])))
+        .filter_map(|v| {
+            dp[full][v].map(|w| (w + g.w[&(vs[v], vs[0])], v))
+        })
+        .min()?;
+
+    Some((w, restore(&vs, &pre, full, last)))
+}
+
+
+/// dp[mask][v]: minimum path weight visiting mask and ending at v
+fn held_karp<V: Vertex, W: Weight>(
+    g: &Graph<V, W>,
+    vs: &[V],
+    is_start: impl Fn(usize) -> bool,
+) -> (Vec<Vec<Option<W>>>, Vec<Vec<usize>>) {
+    let n = vs.len();
+
+    assert!(n <= 20, "too many vertexs for held-karp: {n} > 20");
+
+    let adj = vs
+        .iter()
+        .map(|&u| {
+            g.e.0
+                .get(&u)
+                .into_iter()
+                .flatten()
+                .map(|v| (vs.binary_search(v).unwrap(), g.w[&(u, *v)]))
+                .collect_vec()
+        })
+        .collect_vec();
+
+    let mut dp = vec![vec![None; n]; 1 << n];
+    let mut pre = vec![vec![usize::MAX; n]; 1 << n];
+
+    for s in (0..n).filter(|&s| is_start(s)) {
+        dp[1 << s][s] = Some(W::zero());
+    }
+
+    for mask in 1..1usize << n {
+        for u in 0..n {
+            let Some(w_u) = dp[mask][u] else {
+                continue;
+            };
+
+            for &(v, w_uv) in adj[u].iter() {
+                if mask & (1 << v) != 0 {
+                    continue;
+                }
+
+                let next = mask | (1 << v);
+                let w_v = w_u + w_uv;
+
+                if dp[next][v].is_none_or(|w| w_v < w) {
+                    dp[next][v] = Some(w_v);
+                    pre[next][v] = u;
+                }
+            }
+        }
+    }
+
+    (dp, pre)
+}
+
+
+fn restore<V: Vertex>(
+    vs: &[V],
+    pre: &[Vec<usize>],
+    mut mask: usize,
+    mut last: usize,
+) -> Vec<V> {
+    let mut path = vec![];
+
+    loop {
+        path.push(vs[last]);
+
+        let p = pre[mask][last];
+
+        if p == usize::MAX {
+            break;
+        }
+
+        mask ^= 1 << last;
+        last = p;
+    }
+
+    path.reverse();
+
+    path
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use common::{thread_rng, Itertools};
+
+    use super::*;
+    use crate::test::{model::gen_gnp, path::Path};
+
+
+    /// (min path, min cycle) by permutations
+    fn brute_force(g: &Graph) -> (Option<isize>, Option<isize>) {
+        let vs = g.vertexs().collect_vec();
+        let n = vs.len();
+
+        let weight = |p: &[usize]| {
+            p.iter()
+                .tuple_windows()
+                .map(|(u, v)| {
+                    g.contains_edge((*u, *v)).then(|| g.w[&(*u, *v)])
+                })
+                .sum::<Option<isize>>()
+        };
+
+        let min_path = vs
+            .iter()
+            .cloned()
+            .permutations(n)
+            .filter_map(|p| weight(&p))
+            .min();
+
+        let min_cycle = if n < 2 || !g.is_dir && n < 3 {
+            None
+        } else {
+            vs.iter()
+                .cloned()
+                .permutations(n)
+                .filter(|p| p[0] == vs[0])
+                .filter_map(|mut p| {
+                    p.push(p[0]);
+                    weight(&p)
+                })
+                .min()
+        };
+
+        (min_path, min_cycle)
+    }
+
+    #[test]
+    fn test_hamilton_fixeddata() {
+        // 4 cities
+        let g = Graph::from_undirected_iter([
+            (1, 2, 10),
+            (1, 3, 15),
+            (1, 4, 20),
+            (2, 3, 35),
+            (2, 4, 25),
+            (3, 4, 30),
+        ]);
+
+        let (w, cycle) = tsp_held_karp(&g).unwrap();
+
+        assert_eq!(w, 80);
+        assert_eq!(Path::from_cycle(&g, &cycle).weight(), 80);
+
+        let (w, path) = hamilton_path(&g, None, None).unwrap();
+
+        assert_eq!(w, 50);
+        assert_eq!(Path::new(&g, &path).weight(), 50);
+
+        let (w, path) = hamilton_path(&g, Some(2), Some(4)).unwrap();
+
+        assert_eq!(w, 55);
+        assert_eq!(path, vec![2, 1, 3, 4]);
+
+        /* star has no Hamiltonian path */
+
+        let g = Graph::from_undirected_iter([(1, 2, 1), (1, 3, 1), (1, 4, 1)]);
+
+        assert_eq!(hamilton_path(&g, None, None), None);
+        assert_eq!(tsp_held_karp(&g), None);
+    }
+
+    #[test]
+    fn test_hamilton_randomdata() {
+        let mut rng = thread_rng();
+
+        for is_dir in [false, true] {
+            for _ in 0..50 {
+                let g = gen_gnp(6, 0.6, is_dir, 1..20, &mut rng);
+                let (min_path, min_cycle) = brute_force(&g);
+
+                let res = hamilton_path(&g, None, None);
+
+                assert_eq!(res.as_ref().map(|x| x.0), min_path);
+
+                if let Some((w, path)) = res {
+                    assert_eq!(path.iter().unique().count(), path.len());
+                    assert_eq!(path.len(), g.vertexs().count());
+                    assert_eq!(Path::new(&g, &path).weight(), w);
+                }
+
+                let res = tsp_held_karp(&g);
+
+                assert_eq!(res.as_ref().map(|x| x.0), min_cycle);
+
+                if let Some((w, cycle)) = res {
+                    assert_eq!(cycle.len(), g.vertexs().count());
+                    assert_eq!(Path::from_cycle(&g, &cycle).weight(), w);
+                }
+            }
+        }
+    }
+}
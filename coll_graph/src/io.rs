@@ -0,0 +1,748 @@
+//! Graph file formats: weighted edge list CSV, DIMACS (shortest path / max flow)
+//! and METIS
+//!
+//! DIMACS and METIS number vertexs from 1, so they work on the default `Graph`
+//! without vertex 0.
+
+
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::Display,
+    io::{self, BufRead, BufReader, Read, Write},
+    str::FromStr,
+};
+
+use common::Itertools;
+
+use coll::get;
+
+use crate::{Graph, Vertex, Weight};
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Structures
+
+#[derive(Debug)]
+pub enum GraphIOError {
+    IO(io::Error),
+    CSV(csv::Error),
+    /// (line number from 1, reason)
+    Malformed(usize, String),
+    /// problem line (`p ..` / METIS header) is absent
+    MissingHeader,
+    /// count declared by header mismatches the body, (name, expect, found)
+    CountMismatch(&'static str, usize, usize),
+    /// DIMACS and METIS number vertexs from 1, vertex 0 can't be written
+    ZeroVertex,
+}
+
+
+pub type GraphIOResult<T> = Result<T, GraphIOError>;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Implementations
+
+impl Display for GraphIOError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IO(err) => write!(f, "io: {err}"),
+            Self::CSV(err) => write!(f, "csv: {err}"),
+            Self::Malformed(lineno, reason) => {
+                write!(f, "malformed line {lineno}: {reason}")
+            }
+            Self::MissingHeader => write!(f, "missing header"),
+            Self::CountMismatch(name, expect, found) => {
+                write!(f, "{name} count mismatch, expect {expect} found {found}")
+            }
+            Self::ZeroVertex => write!(f, "vertex 0 isn't allowed"),
+        }
+    }
+}
+
+impl Error for GraphIOError {}
+
+impl From<io::Error> for GraphIOError {
+    fn from(err: io::Error) -> Self {
+        Self::IO(err)
+    }
+}
+
+impl From<csv::Error> for GraphIOError {
+    fn from(err: csv::Error) -> Self {
+        Self::CSV(err)
+    }
+}
+
+
+/// Weighted edge list: header `u,v,w` and one arc per row
+///
+/// Undirected graph writes both directions, read it as directed then reset
+/// `is_dir`.
+impl<V, W> Graph<V, W>
+where
+    V: Vertex + Display,
+    W: Weight + Display,
+{
+    pub fn write_to_csv<O: Write>(&self, w: &mut O) -> GraphIOResult<()> {
+        let mut writer = csv::Writer::from_writer(w);
+
+        writer.write_record(["u", "v", "w"])?;
+
+        for (u, v, w) in self.edges().unique_by(|&(u, v, _)| (u, v)) {
+            writer.write_record([u.to_string(), v.to_string(), w.to_string()])?;
+        }
+
+        writer.flush()?;
+
+        Ok(())
+    }
+}
+
+
+impl Graph {
+    /// Default `Graph` version of [`read_csv`]
+    pub fn read_from_csv<R: Read>(r: &mut R) -> GraphIOResult<Self> {
+        read_csv(r)
+    }
+}
+
+
+/// DIMACS (9th implementation challenge) and METIS
+impl Graph {
+    /// ```text
+    /// c comment
+    /// p sp <n> <m>
+    /// a <u> <v> <w>
+    /// ```
+    ///
+    /// parallel arcs are merged into the one with minimum weight
+    pub fn read_dimacs_sp<R: Read>(r: &mut R) -> GraphIOResult<Self> {
+        let mut n = None;
+        let mut arcn = 0;
+        let mut edges = BTreeMap::new();
+
+        for (lineno, line) in lines(r) {
+            let line = line?;
+            let toks = line.split_whitespace().collect_vec();
+
+            match toks.as_slice() {
+                [] | ["c", ..] => (),
+                ["p", "sp", vn, m] => {
+                    if n.is_some() {
+                        return Err(GraphIOError::Malformed(
+                            lineno,
+                            "duplicated problem line".to_owned(),
+                        ));
+                    }
+
+                    n = Some((
+                        parse_field::<usize>(lineno, vn)?,
+                        parse_field::<usize>(lineno, m)?,
+                    ));
+                }
+                ["a", u, v, w] => {
+                    let Some((vn, _)) = n else {
+                        return Err(GraphIOError::MissingHeader);
+                    };
+
+                    let u = parse_vertex(lineno, u, vn)?;
+                    let v = parse_vertex(lineno, v, vn)?;
+                    let w: isize = parse_field(lineno, w)?;
+
+                    edges
+                        .entry((u, v))
+                        .and_modify(|cur: &mut isize| *cur = (*cur).min(w))
+                        .or_insert(w);
+                    arcn += 1;
+                }
+                _ => {
+                    return Err(GraphIOError::Malformed(lineno, line));
+                }
+            }
+        }
+
+        let Some((_vn, m)) = n else {
+            return Err(GraphIOError::MissingHeader);
+        };
+
+        if arcn != m {
+            return Err(GraphIOError::CountMismatch("arc", m, arcn));
+        }
+
+        Ok(Self::from_directed_iter(
+            edges.into_iter().map(|((u, v), w)| (u, v, w)),
+        ))
+    }
+
+    pub fn write_dimacs_sp<O: Write>(&self, w: &mut O) -> GraphIOResult<()> {
+        let edges = self.edges().unique().collect_vec();
+
+        writeln!(w, "p sp {} {}", self.max_vertex()?, edges.len())?;
+
+        for (u, v, w_) in edges {
+            writeln!(w, "a {u} {v} {w_}")?;
+        }
+
+        Ok(())
+    }
+
+    /// ```text
+    /// c comment
+    /// p max <n> <m>
+    /// n <src> s
+    /// n <sink> t
+    /// a <u> <v> <cap>
+    /// ```
+    ///
+    /// (g, src, sink), capacities of parallel arcs are summed up
+    pub fn read_dimacs_max_flow<R: Read>(
+        r: &mut R,
+    ) -> GraphIOResult<(Self, usize, usize)> {
+        let mut n = None;
+        let mut src = None;
+        let mut sink = None;
+        let mut arcn = 0;
+        let mut edges = BTreeMap::new();
+
+        for (lineno, line) in lines(r) {
+            let line = line?;
+            let toks = line.split_whitespace().collect_vec();
+
+            match toks.as_slice() {
+                [] | ["c", ..] => (),
+                ["p", "max", vn, m] => {
+                    if n.is_some() {
+                        return Err(GraphIOError::Malformed(
+                            lineno,
+                            "duplicated problem line".to_owned(),
+                        ));
+                    }
+
+                    n = Some((
+                        parse_field::<usize>(lineno, vn)?,
+                        parse_field::<usize>(lineno, m)?,
+                    ));
+                }
+                ["n", x, kind @ ("s" | "t")] => {
+                    let Some((vn, _)) = n else {
+                        return Err(GraphIOError::MissingHeader);
+                    };
+
+                    let x = parse_vertex(lineno, x, vn)?;
+                    let (slot, other) = if *kind == "s" {
+                        (&mut src, sink)
+                    } else {
+                        (&mut sink, src)
+                    };
+
+                    if other == Some(x) {
+                        return Err(GraphIOError::Malformed(
+                            lineno,
+                            format!("source and sink are both {x}"),
+                        ));
+                    }
+
+                    if slot.replace(x).is_some() {
+                        return Err(GraphIOError::Malformed(
+                            lineno,
+                            format!("duplicated {kind} node"),
+                        ));
+                    }
+                }
+                ["a", u, v, cap] => {
+                    let Some((vn, _)) = n else {
+                        return Err(GraphIOError::MissingHeader);
+                    };
+
+                    let cap: isize = parse_field(lineno, cap)?;
+
+                    if cap < 0 {
+                        return Err(GraphIOError::Malformed(
+                            lineno,
+                            format!("negative capacity {cap}"),
+                        ));
+                    }
+
+                    let u = parse_vertex(lineno, u, vn)?;
+                    let v = parse_vertex(lineno, v, vn)?;
+
+                    *edges.entry((u, v)).or_insert(0) += cap;
+                    arcn += 1;
+                }
+                _ => {
+                    return Err(GraphIOError::Malformed(lineno, line));
+                }
+            }
+        }
+
+        let Some((_vn, m)) = n else {
+            return Err(GraphIOError::MissingHeader);
+        };
+
+        if arcn != m {
+            return Err(GraphIOError::CountMismatch("arc", m, arcn));
+        }
+
+        let edges = edges.into_iter().map(|((u, v), cap)| (u, v, cap));
+
+        match (src, sink) {
+            (Some(src), Some(sink)) => {
+                Ok((Self::from_directed_iter(edges), src, sink))
+            }
+            (None, _) => Err(GraphIOError::CountMismatch("source", 1, 0)),
+            (_, None) => Err(GraphIOError::CountMismatch("sink", 1, 0)),
+        }
+    }
+
+    pub fn write_dimacs_max_flow<O: Write>(
+        &self,
+        src: usize,
+        sink: usize,
+        w: &mut O,
+    ) -> GraphIOResult<()> {
+        if src == 0 || sink == 0 {
+            return Err(GraphIOError::ZeroVertex);
+        }
+
+        let edges = self.edges().unique().collect_vec();
+
+        writeln!(w, "p max {} {}", self.max_vertex()?, edges.len())?;
+        writeln!(w, "n {src} s")?;
+        writeln!(w, "n {sink} t")?;
+
+        for (u, v, cap) in edges {
+            writeln!(w, "a {u} {v} {cap}")?;
+        }
+
+        Ok(())
+    }
+
+    /// Undirected graph
+    ///
+    /// ```text
+    /// % comment
+    /// <n> <m> [fmt [ncon]]
+    /// <adjacent vertexs of vertex i (with weight if fmt is xx1)>
+    /// ```
+    ///
+    /// fmt: (vertex size, vertex weight, edge weight) flags, default 000,
+    /// unweighted edge has weight 1, vertex size and weights are skipped.
+    pub fn read_metis<R: Read>(r: &mut R) -> GraphIOResult<Self> {
+        let mut header = None;
+        let mut edges = vec![];
+        // line number of each vertex
+        let mut vlines = vec![];
+        let mut u = 0;
+
+        for (lineno, line) in lines(r) {
+            let line = line?;
+
+            if line.trim_start().starts_with('%') {
+                continue;
+            }
+
+            let toks = line.split_whitespace().collect_vec();
+
+            let Some((n, _m, has_vsize, has_vw, has_ew, ncon)) = header else {
+                if toks.is_empty() {
+                    continue;
+                }
+
+                if toks.len() > 4 {
+                    return Err(GraphIOError::Malformed(lineno, line));
+                }
+
+                let n: usize = parse_field(lineno, toks[0])?;
+                let m: usize = if toks.len() > 1 {
+                    parse_field(lineno, toks[1])?
+                } else {
+                    return Err(GraphIOError::Malformed(lineno, line));
+                };
+                let fmt = toks.get(2).cloned().unwrap_or("0");
+
+                if fmt.len() > 3 || fmt.chars().any(|c| c != '0' && c != '1') {
+                    return Err(GraphIOError::Malformed(
+                        lineno,
+                        format!("invalid fmt {fmt}"),
+                    ));
+                }
+
+                let fmt = format!("{fmt:0>3}").into_bytes();
+                let ncon: usize = match toks.get(3) {
+                    Some(tok) => parse_field(lineno, tok)?,
+                    None => 1,
+                };
+
+                header = Some((
+                    n,
+                    m,
+                    fmt[0] == b'1',
+                    fmt[1] == b'1',
+                    fmt[2] == b'1',
+                    ncon,
+                ));
+
+                continue;
+            };
+
+            u += 1;
+
+            if u > n {
+                if toks.is_empty() {
+                    continue;
+                }
+
+                return Err(GraphIOError::CountMismatch("vertex", n, u));
+            }
+
+            vlines.push(lineno);
+
+            let mut toks = &toks[..];
+            let skip = has_vsize as usize + if has_vw { ncon } else { 0 };
+
+            if toks.len() < skip {
+                return Err(GraphIOError::Malformed(lineno, line));
+            }
+
+            toks = &toks[skip..];
+
+            let step = if has_ew { 2 } else { 1 };
+
+            if toks.len() % step != 0 {
+                return Err(GraphIOError::Malformed(
+                    lineno,
+                    "unpaired edge weight".to_owned(),
+                ));
+            }
+
+            for chunk in toks.chunks(step) {
+                let v = parse_vertex(lineno, chunk[0], n)?;
+                let w = if has_ew {
+                    parse_field(lineno, chunk[1])?
+                } else {
+                    1
+                };
+
+                edges.push((u, v, w));
+            }
+        }
+
+        let Some((n, m, ..)) = header else {
+            return Err(GraphIOError::MissingHeader);
+        };
+
+        if u < n {
+            return Err(GraphIOError::CountMismatch("vertex", n, u));
+        }
+
+        if edges.len() != 2 * m {
+            return Err(GraphIOError::CountMismatch("edge", m, edges.len() / 2));
+        }
+
+        let mut g = Self::from_directed_iter(edges);
+
+        for (u, v, w) in g.edges() {
+            if !g.contains_edge((v, u)) || get!(g.w => (v, u)) != w {
+                return Err(GraphIOError::Malformed(
+                    vlines[u - 1],
+                    format!("asymmetric edge {u}-{v}"),
+                ));
+            }
+        }
+
+        g.is_dir = false;
+
+        Ok(g)
+    }
+
+    pub fn write_metis<O: Write>(&self, w: &mut O) -> GraphIOResult<()> {
+        debug_assert!(!self.is_dir);
+
+        let n = self.max_vertex()?;
+        let edges = self.edges().unique().collect_vec();
+
+        writeln!(w, "{n} {} 001", edges.len() / 2)?;
+
+        let mut edges = edges.into_iter().peekable();
+
+        for u in 1..=n {
+            let mut line = vec![];
+
+            while let Some((_, v, w_)) = edges.next_if(|(x, ..)| *x == u) {
+                line.push(format!("{v} {w_}"));
+            }
+
+            writeln!(w, "{}", line.join(" "))?;
+        }
+
+        Ok(())
+    }
+
+    /// Vertexs should be in 1..=max
+    fn max_vertex(&self) -> GraphIOResult<usize> {
+        if self.vertexs().contains(&0) {
+            return Err(GraphIOError::ZeroVertex);
+        }
+
+        Ok(self.vertexs().max().unwrap_or(0))
+    }
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Functions
+
+/// Read weighted edge list CSV (see [`Graph::write_to_csv`]) as directed graph
+pub fn read_csv<V, W, R>(r: &mut R) -> GraphIOResult<Graph<V, W>>
+where
+    V: Vertex + FromStr,
+    W: Weight + FromStr,
+    R: Read,
+{
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(r);
+    let mut edges = vec![];
+
+    for (i, record) in reader.records().enumerate() {
+        let record = record?;
+        // header is line 1
+        let lineno = i + 2;
+
+        if record.len() != 3 {
+            return Err(GraphIOError::Malformed(
+                lineno,
+                format!("expect 3 fields, found {}", record.len()),
+            ));
+        }
+
+        edges.push((
+            parse_field(lineno, &record[0])?,
+            parse_field(lineno, &record[1])?,
+            parse_field(lineno, &record[2])?,
+        ));
+    }
+
+    Ok(Graph::from_directed_iter(edges))
+}
+
+
+fn lines<R: Read>(
+    r: &mut R,
+) -> impl Iterator<Item = (usize, io::Result<String>)> {
+    BufReader::new(r)
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+}
+
+
+fn parse_field<T: FromStr>(lineno: usize, s: &str) -> GraphIOResult<T> {
+    s.parse().map_err(|_| {
+        GraphIOError::Malformed(lineno, format!("invalid field {s:?}"))
+    })
+}
+
+
+/// 1..=n
+fn parse_vertex(lineno: usize, s: &str, n: usize) -> GraphIOResult<usize> {
+    let v = parse_field(lineno, s)?;
+
+    if v == 0 || v > n {
+        return Err(GraphIOError::Malformed(
+            lineno,
+            format!("vertex {v} out of 1..={n}"),
+        ));
+    }
+
+    Ok(v)
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::{batch_graph, GraphGenOptions};
+
+
+    fn edges_of(g: &Graph) -> Vec<(usize, usize, isize)> {
+        g.edges().unique().sorted().collect()
+    }
+
+    #[test]
+    fn test_io_roundtrip() {
+        for g in batch_graph(20, 30, -10..20, &GraphGenOptions::undir_conn())
+            .into_iter()
+            .chain(batch_graph(20, 30, 1..20, &GraphGenOptions::dir_conn()))
+        {
+            /* csv */
+
+            let mut buf = vec![];
+            g.write_to_csv(&mut buf).unwrap();
+            let g2 = Graph::read_from_csv(&mut &buf[..]).unwrap();
+            assert_eq!(edges_of(&g), edges_of(&g2));
+
+            /* dimacs sp */
+
+            let mut buf = vec![];
+            g.write_dimacs_sp(&mut buf).unwrap();
+            let g2 = Graph::read_dimacs_sp(&mut &buf[..]).unwrap();
+            assert_eq!(edges_of(&g), edges_of(&g2));
+
+            /* dimacs max flow */
+
+            if g.edges().all(|(_, _, w)| w >= 0) {
+                let src = g.anypoint();
+                let sink = g.vertexs().max().unwrap();
+
+                let mut buf = vec![];
+                g.write_dimacs_max_flow(src, sink, &mut buf).unwrap();
+                let (g2, src2, sink2) =
+                    Graph::read_dimacs_max_flow(&mut &buf[..]).unwrap();
+                assert_eq!(edges_of(&g), edges_of(&g2));
+                assert_eq!((src, sink), (src2, sink2));
+            }
+
+            /* metis */
+
+            if !g.is_dir {
+                let mut buf = vec![];
+                g.write_metis(&mut buf).unwrap();
+                let g2 = Graph::read_metis(&mut &buf[..]).unwrap();
+                assert_eq!(edges_of(&g), edges_of(&g2));
+                assert!(!g2.is_dir);
+            }
+        }
+    }
+
+    #[test]
+    fn test_io_zero_vertex() {
+        let g = Graph::from_undirected_iter([(0, 1, 2), (1, 2, 3)]);
+
+        assert!(matches!(
+            g.write_dimacs_sp(&mut vec![]),
+            Err(GraphIOError::ZeroVertex)
+        ));
+        assert!(matches!(
+            g.write_dimacs_max_flow(1, 2, &mut vec![]),
+            Err(GraphIOError::ZeroVertex)
+        ));
+        assert!(matches!(
+            g.write_metis(&mut vec![]),
+            Err(GraphIOError::ZeroVertex)
+        ));
+
+        /* csv keeps vertex 0 */
+
+        let mut buf = vec![];
+        g.write_to_csv(&mut buf).unwrap();
+        let g2 = Graph::read_from_csv(&mut &buf[..]).unwrap();
+        assert_eq!(edges_of(&g), edges_of(&g2));
+
+        /* vertex 0 is rejected on read */
+
+        assert!(matches!(
+            Graph::read_dimacs_sp(&mut "p sp 2 1\na 0 1 3\n".as_bytes()),
+            Err(GraphIOError::Malformed(2, _))
+        ));
+        assert!(matches!(
+            Graph::read_metis(&mut "2 1\n0\n1\n".as_bytes()),
+            Err(GraphIOError::Malformed(2, _))
+        ));
+    }
+
+    #[test]
+    fn test_io_fixeddata() {
+        let g = Graph::<&str, isize>::from_directed_iter([("a", "b", 1)]);
+        let mut buf = vec![];
+        g.write_to_csv(&mut buf).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "u,v,w\na,b,1\n");
+
+        let text = "c sample\np sp 3 2\na 1 2 5\n\na 2 3 -1\n";
+        let g = Graph::read_dimacs_sp(&mut text.as_bytes()).unwrap();
+        assert_eq!(edges_of(&g), vec![(1, 2, 5), (2, 3, -1)]);
+
+        /* parallel arcs */
+
+        let text = "p sp 3 4\na 1 2 5\na 2 3 1\na 1 2 -2\na 1 2 7\n";
+        let g = Graph::read_dimacs_sp(&mut text.as_bytes()).unwrap();
+        assert_eq!(edges_of(&g), vec![(1, 2, -2), (2, 3, 1)]);
+        assert_eq!(g.e[1], [2]);
+
+        let text = "p max 3 3\nn 1 s\nn 3 t\na 1 2 5\na 2 3 1\na 1 2 4\n";
+        let (g, src, sink) =
+            Graph::read_dimacs_max_flow(&mut text.as_bytes()).unwrap();
+        assert_eq!(edges_of(&g), vec![(1, 2, 9), (2, 3, 1)]);
+        assert_eq!(g.e[1], [2]);
+        assert_eq!((src, sink), (1, 3));
+
+        let text = "% sample\n3 2 1\n2 4\n1 4 3 7\n2 7\n";
+        let g = Graph::read_metis(&mut text.as_bytes()).unwrap();
+        assert_eq!(
+            edges_of(&g),
+            vec![(1, 2, 4), (2, 1, 4), (2, 3, 7), (3, 2, 7)]
+        );
+
+        // vertex weights are skipped
+        let text = "3 2 010\n9 2\n8 1 3\n7 2\n";
+        let g = Graph::read_metis(&mut text.as_bytes()).unwrap();
+        assert_eq!(edges_of(&g).len(), 4);
+
+        /* malformed */
+
+        assert!(matches!(
+            Graph::read_from_csv(&mut "u,v,w\n1,2\n".as_bytes()),
+            Err(GraphIOError::CSV(_))
+        ));
+        assert!(matches!(
+            Graph::read_from_csv(&mut "u,v,w\n1,x,2\n".as_bytes()),
+            Err(GraphIOError::Malformed(2, _))
+        ));
+
+        let g: Graph<char, i32> =
+            read_csv(&mut "u,v,w\n a , b ,3\n".as_bytes()).unwrap();
+        assert_eq!(get!(g.w => ('a', 'b')), 3);
+        assert!(matches!(
+            Graph::read_dimacs_sp(&mut "a 1 2 3\n".as_bytes()),
+            Err(GraphIOError::MissingHeader)
+        ));
+        assert!(matches!(
+            Graph::read_dimacs_sp(&mut "p sp 2 2\na 1 2 3\n".as_bytes()),
+            Err(GraphIOError::CountMismatch("arc", 2, 1))
+        ));
+        assert!(matches!(
+            Graph::read_dimacs_sp(&mut "p sp 2 1\na 1 3 3\n".as_bytes()),
+            Err(GraphIOError::Malformed(2, _))
+        ));
+        assert!(matches!(
+            Graph::read_dimacs_max_flow(
+                &mut "p max 2 1\nn 1 s\na 1 2 3\n".as_bytes()
+            ),
+            Err(GraphIOError::CountMismatch("sink", 1, 0))
+        ));
+        assert!(matches!(
+            Graph::read_dimacs_max_flow(
+                &mut "p max 2 1\nn 1 s\nn 1 t\na 1 2 3\n".as_bytes()
+            ),
+            Err(GraphIOError::Malformed(3, _))
+        ));
+        assert!(matches!(
+            Graph::read_metis(&mut "2 1\n2\n\n".as_bytes()),
+            Err(GraphIOError::CountMismatch("edge", 1, 0))
+        ));
+        assert!(matches!(
+            Graph::read_metis(&mut "2 1 1\n2 3\n1 4\n".as_bytes()),
+            Err(GraphIOError::Malformed(2, _))
+        ));
+        assert!(matches!(
+            Graph::read_metis(&mut "3 1\n2\n1\n".as_bytes()),
+            Err(GraphIOError::CountMismatch("vertex", 3, 2))
+        ));
+    }
+}
@@ -1,6 +1,7 @@
 #![feature(iter_from_coroutine)]
 #![feature(coroutines)]
 #![feature(type_alias_impl_trait)]
+#![feature(trait_alias)]
 
 
 pub mod mst;
@@ -9,14 +10,29 @@ pub mod toposort;
 pub mod tree;
 pub mod scc;
 pub mod bcc;
+pub mod flow;
+pub mod matching;
+pub mod csr;
+pub mod cycle;
+pub mod dom;
+pub mod euler;
+pub mod hamilton;
+pub mod sat;
 pub mod test;
-#[cfg(test)]
-mod debug;
+pub mod io;
+pub mod debug;
 
 
-use std::collections::{BTreeSet, HashMap, hash_map::Entry};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashMap, hash_map::Entry},
+    fmt::Debug,
+    hash::Hash,
+    ops::{Add, Sub},
+};
 
 use coll::{
+    easycoll::BMV,
     union_find::{UnionFind, SZ},
     apush, get, getopt, set,
 };
@@ -25,6 +41,78 @@ use common::{Itertools, ordered_insert};
 use crate::scc::scc_tarjan;
 
 
+////////////////////////////////////////////////////////////////////////////////
+//// Macros
+
+macro_rules! impl_weight_for_num {
+    ($($ty:ty),*) => {
+        $(
+            impl Weight for $ty {
+                fn zero() -> Self {
+                    0
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_graph_view_for_ref {
+    ($($ref:ty),*) => {
+        $(
+            impl<V: Vertex, W: Weight, G: GraphView<V, W>> GraphView<V, W> for $ref {
+                fn is_dir(&self) -> bool {
+                    (**self).is_dir()
+                }
+
+                fn vertexs(&self) -> impl Iterator<Item = V> {
+                    (**self).vertexs()
+                }
+
+                fn adj(&self, u: V) -> impl Iterator<Item = (V, W)> {
+                    (**self).adj(u)
+                }
+
+                fn edges(&self) -> impl Iterator<Item = (V, V, W)> {
+                    (**self).edges()
+                }
+            }
+        )*
+    };
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Traits
+
+/// Vertex id
+pub trait Vertex = Copy + Eq + Ord + Hash + Debug;
+
+
+/// Ordered additive monoid
+///
+/// Algorithms need reweight (Johnson) require `Sub` additionally
+pub trait Weight: Copy + Ord + Add<Output = Self> + Debug {
+    fn zero() -> Self;
+}
+
+
+/// Read-only adjacency access shared by [`Graph`] and [`csr::CSR`]
+pub trait GraphView<V: Vertex, W: Weight> {
+    fn is_dir(&self) -> bool;
+
+    /// Sorted vertexs
+    fn vertexs(&self) -> impl Iterator<Item = V>;
+
+    /// Out-edges (v, w) of u, empty for unknown vertex
+    fn adj(&self, u: V) -> impl Iterator<Item = (V, W)>;
+
+    fn edges(&self) -> impl Iterator<Item = (V, V, W)> {
+        self.vertexs()
+            .flat_map(move |u| self.adj(u).map(move |(v, w)| (u, v, w)))
+    }
+}
+
+
 
 ////////////////////////////////////////////////////////////////////////////////
 //// Structures
@@ -32,27 +120,126 @@ use crate::scc::scc_tarjan;
 /// Adjacent list formed simple (directed) connected graph
 ///
 /// Just ignore orphan vertex
-#[derive(Default, Clone)]
-pub struct Graph {
+#[derive(Clone)]
+pub struct Graph<V = usize, W = isize> {
     /// If it's directed
     pub is_dir: bool,
-    /// Out-edges
-    pub e: Vec<Vec<usize>>,
+    /// Out-edges ordered by vertex, `e[u]` is the adjacent vec of u (empty
+    /// for unknown vertex), `e[u]`, `e.len()` and `e.iter()` work as they
+    /// used to with `Vec<Vec<usize>>`
+    pub e: BMV<V, V>,
     /// In-edges
-    pub rev: Vec<Vec<usize>>,
-    pub w: HashMap<(usize, usize), isize>,
+    pub rev: BMV<V, V>,
+    pub w: HashMap<(V, V), W>,
+}
+
+
+/// Total ordered f64 (by `f64::total_cmp`) as weight
+#[derive(Debug, Default, Clone, Copy)]
+#[repr(transparent)]
+pub struct OrdF64(pub f64);
+
+
+/// Lexicographic ordered pair as weight
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LexPair<A, B>(pub A, pub B);
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Implementations
+
+impl_weight_for_num!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+
+impl Weight for OrdF64 {
+    fn zero() -> Self {
+        Self(0.0)
+    }
 }
 
+impl PartialEq for OrdF64 {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
 
+impl Eq for OrdF64 {}
 
-impl Graph {
+impl PartialOrd for OrdF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OrdF64 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Add for OrdF64 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Sub for OrdF64 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+
+
+impl<A: Weight, B: Weight> Weight for LexPair<A, B> {
+    fn zero() -> Self {
+        Self(A::zero(), B::zero())
+    }
+}
+
+impl<A: Add<Output = A>, B: Add<Output = B>> Add for LexPair<A, B> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl<A: Sub<Output = A>, B: Sub<Output = B>> Sub for LexPair<A, B> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0, self.1 - rhs.1)
+    }
+}
+
+
+impl<V, W> Default for Graph<V, W> {
+    fn default() -> Self {
+        Self {
+            is_dir: false,
+            e: BMV(BTreeMap::new()),
+            rev: BMV(BTreeMap::new()),
+            w: HashMap::new(),
+        }
+    }
+}
+
+
+impl<V: Vertex, W: Weight> Graph<V, W> {
     pub fn new() -> Self {
         Default::default()
     }
 
-    pub fn from_directed_iter<T: IntoIterator<Item = (usize, usize, isize)>>(iter: T) -> Self {
-        let mut e = Vec::new();
-        let mut rev = Vec::new();
+    pub fn from_directed_iter<T: IntoIterator<Item = (V, V, W)>>(iter: T) -> Self {
+        let mut e = BMV::new();
+        let mut rev = BMV::new();
         let mut w = HashMap::new();
 
         for (u, v, w_) in iter {
@@ -64,7 +251,7 @@ impl Graph {
         Self { is_dir: true, e, rev, w }
     }
 
-    pub fn from_undirected_iter<T: IntoIterator<Item = (usize, usize, isize)>>(iter: T) -> Self {
+    pub fn from_undirected_iter<T: IntoIterator<Item = (V, V, W)>>(iter: T) -> Self {
         let mut g = Self::from_directed_iter(iter
             .into_iter()
             .map(|(u, v, w)| [
@@ -91,9 +278,9 @@ impl Graph {
     /// Push new edge or update old edge with new weight
     pub fn insert(
         &mut self,
-        edge: (usize, usize),
-        w: isize,
-    ) -> Option<(usize, usize, isize)> {
+        edge: (V, V),
+        w: W,
+    ) -> Option<(V, V, W)> {
         let (u, v) = edge;
 
         let old;
@@ -110,18 +297,17 @@ impl Graph {
         old
     }
 
-    pub fn anypoint(&self) -> usize {
-        for i in 0..self.e.len() {
-            if !self.e[i].is_empty() {
-                return i;
-            }
-        }
-
-        unreachable!("There is no edge.")
+    pub fn anypoint(&self) -> V {
+        self.e
+            .0
+            .iter()
+            .find(|(_, tos)| !tos.is_empty())
+            .map(|(u, _)| *u)
+            .expect("There is no edge.")
     }
 
     /// O(|E|)
-    pub fn vertexs(&self) -> impl Iterator<Item = usize> {
+    pub fn vertexs(&self) -> impl Iterator<Item = V> {
         let mut vertexs = BTreeSet::new();
 
         for (u, v, _w) in self.edges() {
@@ -134,17 +320,17 @@ impl Graph {
 
     pub fn edges<'a>(
         &'a self,
-    ) -> impl Iterator<Item = (usize, usize, isize)> + 'a {
+    ) -> impl Iterator<Item = (V, V, W)> + 'a {
         std::iter::from_coroutine(#[coroutine] || {
-            for (u, tos) in self.e.iter().cloned().enumerate() {
-                for v in tos {
+            for (&u, tos) in self.e.0.iter() {
+                for &v in tos.iter() {
                     yield (u, v, get!(self.w => (u, v)))
                 }
             }
         })
     }
 
-    pub fn contains_edge(&self, edge: (usize, usize)) -> bool {
+    pub fn contains_edge(&self, edge: (V, V)) -> bool {
         // self.w 与 self.e 可能是独立修改的
         // getopt!(self.w => edge).is_some()
 
@@ -158,13 +344,13 @@ impl Graph {
     }
 
     /// O(|E|) for undirected graph or directed graph
-    pub fn components(&self) -> Vec<Vec<usize>> {
+    pub fn components(&self) -> Vec<Vec<V>> {
         if self.is_dir {
             return scc_tarjan(self);
         }
 
         let mut dsu = UnionFind::new(Some(SZ));
-        let vertexs: Vec<usize> = self.vertexs().collect();
+        let vertexs: Vec<V> = self.vertexs().collect();
 
         for v in vertexs.iter().cloned() {
             dsu.insert(v);
@@ -174,7 +360,7 @@ impl Graph {
             dsu.cunion(u, v);
         }
 
-        let mut comps: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut comps: HashMap<V, Vec<V>> = HashMap::new();
 
         for v in vertexs.iter().cloned() {
             let p = dsu.cfind(v);
@@ -232,12 +418,40 @@ impl Graph {
 
 
 
+impl_graph_view_for_ref!(&G, &mut G);
+
+
+impl<V: Vertex, W: Weight> GraphView<V, W> for Graph<V, W> {
+    fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+
+    fn vertexs(&self) -> impl Iterator<Item = V> {
+        Graph::vertexs(self)
+    }
+
+    fn adj(&self, u: V) -> impl Iterator<Item = (V, W)> {
+        self.e
+            .0
+            .get(&u)
+            .into_iter()
+            .flatten()
+            .map(move |&v| (v, get!(self.w => (u, v))))
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (V, V, W)> {
+        Graph::edges(self)
+    }
+}
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //// Functions
 
-pub fn to_undirected_vec<T: IntoIterator<Item = (usize, usize, isize)>>(
+pub fn to_undirected_vec<V: Copy, W: Copy, T: IntoIterator<Item = (V, V, W)>>(
     iter: T,
-) -> Vec<(usize, usize, isize)> {
+) -> Vec<(V, V, W)> {
     let mut res = vec![];
 
     for (u, v, w) in iter {
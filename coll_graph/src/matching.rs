@@ -0,0 +1,361 @@
+//! Bipartite graph and matching (二分图与匹配, undirected graph)
+//!
+
+
+use std::collections::{HashSet, VecDeque};
+
+use coll::get;
+
+use crate::Graph;
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Functions
+
+/// BFS 二染色
+///
+/// Ok((left, right)) sorted, Err(odd cycle)
+pub fn bipartite_partition(
+    g: &Graph,
+) -> Result<(Vec<usize>, Vec<usize>), Vec<usize>> {
+    debug_assert!(!g.is_dir);
+
+    let n = g.vertexs().max().map(|x| x + 1).unwrap_or(0);
+
+    let mut color: Vec<Option<bool>> = vec![None; n];
+    let mut pre: Vec<Option<usize>> = vec![None; n];
+
+    for root in g.vertexs() {
+        if color[root].is_some() {
+            continue;
+        }
+
+        color[root] = Some(false);
+
+        let mut queue = VecDeque::from([root]);
+
+        while let Some(u) = queue.pop_front() {
+            for v in get!(g.e => u => vec![]) {
+                if color[v].is_none() {
+                    color[v] = color[u].map(|c| !c);
+                    pre[v] = Some(u);
+                    queue.push_back(v);
+                } else if color[v] == color[u] {
+                    /* BFS 树上同色相邻的两点深度相同, 一起上溯到 LCA */
+
+                    let mut a = u;
+                    let mut b = v;
+                    let mut path_a = vec![a];
+                    let mut path_b = vec![b];
+
+                    while a != b {
+                        a = pre[a].unwrap();
+                        b = pre[b].unwrap();
+
+                        path_a.push(a);
+                        path_b.push(b);
+                    }
+
+                    path_b.pop();
+
+                    // lca -> .. -> u -> v -> .. -> (lca)
+                    path_a.reverse();
+                    path_a.extend(path_b);
+
+                    return Err(path_a);
+                }
+            }
+        }
+    }
+
+    Ok(g.vertexs().partition(|v| color[*v] == Some(false)))
+}
+
+
+/// Hopcroft-Karp 最大匹配 (每轮 BFS 分层 + DFS 同时增广多条最短增广路)
+///
+/// O(e√v)
+///
+/// return matching edges (l, r), l ∈ left
+pub fn matching_hopcroft_karp(g: &Graph, left: &[usize]) -> Vec<(usize, usize)> {
+    debug_assert!(!g.is_dir);
+
+    /// only augment along the shortest paths, free right vertex is accepted
+    /// at the layer `free_dist`
+    fn dfs(
+        g: &Graph,
+        u: usize,
+        free_dist: usize,
+        dist: &mut Vec<Option<usize>>,
+        mate: &mut Vec<Option<usize>>,
+    ) -> bool {
+        let next_dist = dist[u].map(|d| d + 1);
+
+        for v in get!(g.e => u => vec![]) {
+            let next = match mate[v] {
+                None => next_dist == Some(free_dist),
+                Some(w) => {
+                    dist[w] == next_dist && dfs(g, w, free_dist, dist, mate)
+                }
+            };
+
+            if next {
+                mate[u] = Some(v);
+                mate[v] = Some(u);
+
+                return true;
+            }
+        }
+
+        // 本轮不再访问
+        dist[u] = None;
+
+        false
+    }
+
+    let n = g.vertexs().max().map(|x| x + 1).unwrap_or(0);
+
+    let mut mate: Vec<Option<usize>> = vec![None; n];
+
+    loop {
+        /* BFS from free left vertexs */
+
+        let mut dist: Vec<Option<usize>> = vec![None; n];
+        let mut queue = VecDeque::new();
+
+        for u in left.iter().cloned() {
+            if mate[u].is_none() {
+                dist[u] = Some(0);
+                queue.push_back(u);
+            }
+        }
+
+        // layer of the first free right vertex reached, that's the length of
+        // the shortest augmenting paths
+        let mut free_dist = None;
+
+        while let Some(u) = queue.pop_front() {
+            let d = dist[u].unwrap();
+
+            // don't expand past the shortest layer
+            if free_dist.is_some_and(|fd| d >= fd) {
+                break;
+            }
+
+            for v in get!(g.e => u => vec![]) {
+                match mate[v] {
+                    None => free_dist = Some(d + 1),
+                    Some(w) => {
+                        if dist[w].is_none() {
+                            dist[w] = dist[u].map(|d| d + 1);
+                            queue.push_back(w);
+                        }
+                    }
+                }
+            }
+        }
+
+        let Some(free_dist) = free_dist else {
+            break;
+        };
+
+        /* DFS augment */
+
+        for u in left.iter().cloned() {
+            if mate[u].is_none() && dist[u].is_some() {
+                dfs(g, u, free_dist, &mut dist, &mut mate);
+            }
+        }
+    }
+
+    left.iter()
+        .cloned()
+        .filter_map(|u| mate[u].map(|v| (u, v)))
+        .collect()
+}
+
+
+/// König's theorem: |min vertex cover| = |max matching|
+///
+/// Z 为从左侧未匹配点出发沿交错路 (左->右走非匹配边, 右->左走匹配边) 可达的点集,
+/// 则 (L \ Z) ∪ (R ∩ Z) 是最小点覆盖
+///
+/// return sorted cover
+pub fn vertex_cover_konig(
+    g: &Graph,
+    left: &[usize],
+    matching: &[(usize, usize)],
+) -> Vec<usize> {
+    debug_assert!(!g.is_dir);
+
+    let n = g.vertexs().max().map(|x| x + 1).unwrap_or(0);
+
+    let mut mate: Vec<Option<usize>> = vec![None; n];
+
+    for (u, v) in matching.iter().cloned() {
+        mate[u] = Some(v);
+        mate[v] = Some(u);
+    }
+
+    let left_set: HashSet<usize> = left.iter().cloned().collect();
+    let mut z = HashSet::new();
+    let mut queue = VecDeque::new();
+
+    for u in left.iter().cloned() {
+        if mate[u].is_none() {
+            z.insert(u);
+            queue.push_back(u);
+        }
+    }
+
+    while let Some(u) = queue.pop_front() {
+        for v in get!(g.e => u => vec![]) {
+            if mate[u] == Some(v) || z.contains(&v) {
+                continue;
+            }
+
+            z.insert(v);
+
+            if let Some(w) = mate[v]
+                && !z.contains(&w)
+            {
+                z.insert(w);
+                queue.push_back(w);
+            }
+        }
+    }
+
+    g.vertexs()
+        .filter(|v| left_set.contains(v) != z.contains(v))
+        .collect()
+}
+
+
+/// Complement of min vertex cover
+///
+/// return sorted independent set
+pub fn independent_set_konig(
+    g: &Graph,
+    left: &[usize],
+    matching: &[(usize, usize)],
+) -> Vec<usize> {
+    let cover: HashSet<usize> = vertex_cover_konig(g, left, matching)
+        .into_iter()
+        .collect();
+
+    g.vertexs().filter(|v| !cover.contains(v)).collect()
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use common::random_range;
+
+    use super::*;
+    use crate::test::{batch_graph, GraphGenOptions};
+
+    fn setup_data() -> Vec<Graph> {
+        let data = vec![
+            // no0
+            // 1 - 5
+            //   X
+            // 2 - 6
+            //   \
+            // 3   7
+            //   \
+            // 4 - 8
+            vec![
+                (1, 5, 1),
+                (1, 6, 1),
+                (2, 5, 1),
+                (2, 6, 1),
+                (2, 7, 1),
+                (3, 8, 1),
+                (4, 8, 1),
+            ],
+            // no1 (even cycle)
+            vec![(1, 2, 1), (2, 3, 1), (3, 4, 1), (4, 1, 1)],
+        ];
+
+        data.into_iter()
+            .map(|x| Graph::from_undirected_iter(x))
+            .collect()
+    }
+
+    fn gen_bipartite_graph(l: usize, r: usize, m: usize) -> Graph {
+        let mut edges = HashSet::new();
+
+        for _ in 0..m {
+            edges.insert((random_range!(1..=l), random_range!(l + 1..=l + r), 1));
+        }
+
+        Graph::from_undirected_iter(edges)
+    }
+
+    fn check(g: &Graph, max: Option<usize>) {
+        let (left, right) = bipartite_partition(g).unwrap();
+
+        assert_eq!(g.verify_bipartite(&left, &right), Ok(()));
+
+        let matching = matching_hopcroft_karp(g, &left);
+        let cover = vertex_cover_konig(g, &left, &matching);
+        let independent = independent_set_konig(g, &left, &matching);
+
+        if let Some(max) = max {
+            assert_eq!(matching.len(), max);
+        }
+
+        assert_eq!(g.verify_max_matching(&matching, &cover), Ok(()));
+        assert_eq!(g.verify_independent_set(&independent), Ok(()));
+        assert_eq!(
+            independent.len() + cover.len(),
+            g.vertexs().count()
+        );
+    }
+
+    #[test]
+    fn test_matching_fixeddata() {
+        let g = setup_data();
+
+        check(&g[0], Some(3));
+        check(&g[1], Some(2));
+
+        /* odd cycle */
+
+        let g = Graph::from_undirected_iter([
+            (1, 2, 1),
+            (2, 3, 1),
+            (3, 4, 1),
+            (4, 5, 1),
+            (5, 1, 1),
+            (5, 6, 1),
+        ]);
+
+        let cycle = bipartite_partition(&g).unwrap_err();
+
+        assert_eq!(g.verify_odd_cycle(&cycle), Ok(()));
+    }
+
+    #[test]
+    fn test_matching_randomdata() {
+        for _ in 0..100 {
+            let l = random_range!(1..30);
+            let r = random_range!(1..30);
+            let m = random_range!(1..l * r + 1);
+
+            check(&gen_bipartite_graph(l, r, m), None);
+        }
+
+        for g in batch_graph(50, 30, 1..2, &GraphGenOptions::undir_conn()) {
+            match bipartite_partition(&g) {
+                Ok((left, right)) => {
+                    assert_eq!(g.verify_bipartite(&left, &right), Ok(()));
+                }
+                Err(cycle) => {
+                    assert_eq!(g.verify_odd_cycle(&cycle), Ok(()));
+                }
+            }
+        }
+    }
+}
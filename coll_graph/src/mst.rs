@@ -13,7 +13,7 @@ use coll::{
 };
 use coll_heap::dary::DaryHeap;
 
-use super::Graph;
+use super::{Graph, Vertex, Weight};
 
 
 ///
@@ -23,7 +23,7 @@ use super::Graph;
 ///
 /// O(eloge) + O(elogv) = O(eloge)
 ///
-pub fn mst_kruskal(g: &Graph) -> Vec<(usize, usize)> {
+pub fn mst_kruskal<V: Vertex, W: Weight>(g: &Graph<V, W>) -> Vec<(V, V)> {
     /* init sorted edge set */
     // let mut sorted_edges = FibHeap::new();
     let mut sorted_edges = vec![];
@@ -69,7 +69,7 @@ pub fn mst_kruskal(g: &Graph) -> Vec<(usize, usize)> {
 /// | O(vlogv + e) | O(vlogv + elogv) | O(v + e) |
 ///
 ///
-pub fn mst_prim(g: &Graph) -> Vec<(usize, usize)> {
+pub fn mst_prim<V: Vertex, W: Weight>(g: &Graph<V, W>) -> Vec<(V, V)> {
     debug_assert!(g.is_connected());
 
     let mut res = vec![];
@@ -87,27 +87,26 @@ pub fn mst_prim(g: &Graph) -> Vec<(usize, usize)> {
 
     /* setup rest collection */
 
-    let mut rest: HashSet<usize> = HashSet::new();
+    let mut rest: HashSet<V> = HashSet::new();
 
     /* init dis heap && dis edge map */
 
     // let mut dis = FibHeap::new();
-    let mut dis = DaryHeap::<3, usize, isize>::with_capacity(rest.len() + 1);
+    let mut dis = DaryHeap::<3, V, W>::with_capacity(rest.len() + 1);
 
     let mut dis_edge = HashMap::new();
 
-    dis.insert(root, 0);
+    // 只有已探到的点才进堆 (W 没有 MAX)
+    dis.insert(root, W::zero());
     dis_edge.insert(root, Some(root));
 
     for v in viter {
         rest.insert(v);
-        dis.insert(v, isize::MAX);
         dis_edge.insert(v, None);
     }
 
-    while !rest.is_empty() {
-        // u is current vertex
-        let (u, _uw) = dis.pop_item().unwrap().clone();
+    // u is current vertex
+    while let Some((u, _uw)) = dis.pop_item() {
 
         // "decrease-key" (It's increase-key actually for min-heap)
         // dis.update(u, isize::MAX);
@@ -125,11 +124,18 @@ pub fn mst_prim(g: &Graph) -> Vec<(usize, usize)> {
 
         /* update dis heap */
         for v in adjs.into_iter().filter(|v| rest.contains(v)) {
-            let w_uv: isize = get!(g.w => (u, v));
-
-            if w_uv < get!(dis => v) {
-                dis.decrease_key(v, w_uv);
-                dis_edge.insert(v, Some(u));
+            let w_uv: W = get!(g.w => (u, v));
+
+            match dis.get(&v) {
+                None => {
+                    dis.insert(v, w_uv);
+                    dis_edge.insert(v, Some(u));
+                }
+                Some(dis_v) if w_uv < *dis_v => {
+                    dis.decrease_key(v, w_uv);
+                    dis_edge.insert(v, Some(u));
+                }
+                _ => (),
             }
         }
     }
@@ -142,7 +148,7 @@ pub fn mst_prim(g: &Graph) -> Vec<(usize, usize)> {
 ///
 /// 最小生成森林（非连通图）/ 最小生成树（连通图）
 ///
-pub fn mst_boruvka(g: &Graph) -> Vec<(usize, usize)> {
+pub fn mst_boruvka<V: Vertex, W: Weight>(g: &Graph<V, W>) -> Vec<(V, V)> {
     let mut res = HashSet::new();
 
     // using lexicograph order
@@ -153,13 +159,16 @@ pub fn mst_boruvka(g: &Graph) -> Vec<(usize, usize)> {
     }
 
     // components cheapest edges: (weight, usize)
-    let mut cand_edges: HashSet<(usize, usize, isize)> = g.edges().collect();
+    let mut cand_edges: HashSet<(V, V)> =
+        g.edges().map(|(u, v, _)| (u, v)).collect();
 
     loop {
-        let mut comp_min_edges: HashMap<usize, Option<(isize, usize, usize)>> =
+        let mut comp_min_edges: HashMap<V, Option<(W, V, V)>> =
             HashMap::new();
 
-        for (u, v, w) in cand_edges.iter().cloned() {
+        for (u, v) in cand_edges.iter().cloned() {
+            let w = get!(g.w => (u, v));
+
             let pu = dsu.cfind(u);
             let pv = dsu.cfind(v);
 
@@ -183,10 +192,10 @@ pub fn mst_boruvka(g: &Graph) -> Vec<(usize, usize)> {
         let mut continue_flag = false;
 
         for (_, opt) in comp_min_edges.into_iter() {
-            if let Some((w, u, v)) = opt {
+            if let Some((_w, u, v)) = opt {
                 res.insert((u, v));
                 dsu.cunion(u, v);
-                cand_edges.remove(&(u, v, w));
+                cand_edges.remove(&(u, v));
 
                 continue_flag = true;
             }
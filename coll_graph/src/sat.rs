@@ -0,0 +1,261 @@
+//! 2-SAT by SCC of implication graph
+//!
+//! Literal is DIMACS style: variable x_i (1 <= i <= n) is `i`, ¬x_i is `-i`.
+
+use std::collections::{HashMap, VecDeque};
+
+use common::Itertools;
+
+use crate::{
+    scc::{condense, scc_tarjan},
+    toposort::toposort_kahn,
+    Graph,
+};
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Structures
+
+/// Conjunction of clauses (a ∨ b)
+#[derive(Debug, Clone, Default)]
+pub struct TwoSAT {
+    n: usize,
+    clauses: Vec<(isize, isize)>,
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Implementations
+
+impl TwoSAT {
+    /// n variables x_1, ..., x_n
+    pub fn new(n: usize) -> Self {
+        Self { n, clauses: vec![] }
+    }
+
+    pub fn var_num(&self) -> usize {
+        self.n
+    }
+
+    pub fn clauses(&self) -> &[(isize, isize)] {
+        &self.clauses
+    }
+
+    /// a ∨ b, return index of the clause
+    pub fn add_clause(&mut self, a: isize, b: isize) -> usize {
+        for x in [a, b] {
+            assert!(
+                x != 0 && x.unsigned_abs() <= self.n,
+                "invalid literal {x}"
+            );
+        }
+
+        self.clauses.push((a, b));
+        self.clauses.len() - 1
+    }
+
+    /// a (= a ∨ a)
+    pub fn add_unit(&mut self, a: isize) -> usize {
+        self.add_clause(a, a)
+    }
+
+    /// a -> b (= ¬a ∨ b)
+    pub fn add_imply(&mut self, a: isize, b: isize) -> usize {
+        self.add_clause(-a, b)
+    }
+
+    /// O(n + m)
+    ///
+    /// Ok: value of x_i is res[i - 1],
+    ///
+    /// Err: unsatisfiable core (sorted clause indexes), the clauses along
+    /// the implication cycle x -> ... -> ¬x -> ... -> x
+    pub fn solve(&self) -> Result<Vec<bool>, Vec<usize>> {
+        /* implication graph, clause a ∨ b is ¬a -> b and ¬b -> a,
+           weight is the clause index */
+
+        let g = Graph::from_directed_iter(
+            self.clauses
+                .iter()
+                .enumerate()
+                .flat_map(|(i, &(a, b))| [(-a, b, i), (-b, a, i)]),
+        );
+
+        let sccs = scc_tarjan(&g);
+        let (dag, comp_of) = condense(&g, &sccs);
+
+        if let Some(x) = (1..=self.n as isize).find(|&x| {
+            comp_of.contains_key(&x) && comp_of.get(&x) == comp_of.get(&-x)
+        }) {
+            return Err(Self::core(&g, x));
+        }
+
+        /* x is true iff comp(¬x) precedes comp(x) in toposort,
+           orphan comp of DAG can be put anywhere (the end) */
+
+        let mut rk: HashMap<usize, usize> = toposort_kahn(&dag)
+            .unwrap()
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| (c, i))
+            .collect();
+
+        for c in 0..sccs.len() {
+            let len = rk.len();
+            rk.entry(c).or_insert(len);
+        }
+
+        let rk_of = |x: isize| comp_of.get(&x).map(|c| rk[c]);
+
+        Ok((1..=self.n as isize).map(|x| rk_of(x) > rk_of(-x)).collect())
+    }
+
+    fn core(g: &Graph<isize, usize>, x: isize) -> Vec<usize> {
+        /* BFS path of clause indexes */
+
+        let path = |src: isize, dst: isize| {
+            let mut pre = HashMap::from([(src, src)]);
+            let mut q = VecDeque::from([src]);
+
+            while let Some(u) = q.pop_front() {
+                if u == dst {
+                    break;
+                }
+
+                for v in g.e.0.get(&u).into_iter().flatten() {
+                    if !pre.contains_key(v) {
+                        pre.insert(*v, u);
+                        q.push_back(*v);
+                    }
+                }
+            }
+
+            let mut clauses = vec![];
+            let mut v = dst;
+
+            while v != src {
+                let u = pre[&v];
+
+                clauses.push(g.w[&(u, v)]);
+                v = u;
+            }
+
+            clauses
+        };
+
+        path(x, -x)
+            .into_iter()
+            .chain(path(-x, x))
+            .sorted_unstable()
+            .dedup()
+            .collect()
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use common::{random_range, Itertools};
+
+    use super::*;
+
+
+    fn eval(sat: &TwoSAT, assign: &[bool]) -> bool {
+        let lit = |x: isize| assign[x.unsigned_abs() - 1] == (x > 0);
+
+        sat.clauses().iter().all(|&(a, b)| lit(a) || lit(b))
+    }
+
+    fn brute_force(sat: &TwoSAT) -> bool {
+        let n = sat.var_num();
+
+        (0..1usize << n).any(|mask| {
+            let assign = (0..n).map(|i| mask & (1 << i) != 0).collect_vec();
+
+            eval(sat, &assign)
+        })
+    }
+
+    #[test]
+    fn test_two_sat_fixeddata() {
+        /* (x1 ∨ x2) (¬x1 ∨ x3) (¬x2 ∨ ¬x3) (x1 -> x2) */
+
+        let mut sat = TwoSAT::new(3);
+
+        sat.add_clause(1, 2);
+        sat.add_clause(-1, 3);
+        sat.add_clause(-2, -3);
+        sat.add_imply(1, 2);
+
+        let res = sat.solve().unwrap();
+
+        assert!(eval(&sat, &res));
+        assert_eq!(res, vec![false, true, false]);
+
+        /* all four combinations of x1, x2 */
+
+        let mut sat = TwoSAT::new(3);
+
+        sat.add_clause(1, 2);
+        sat.add_clause(3, 3);
+        sat.add_clause(-1, 2);
+        sat.add_clause(1, -2);
+        sat.add_clause(-1, -2);
+
+        assert_eq!(sat.solve(), Err(vec![0, 2, 3, 4]));
+
+        /* contradict units */
+
+        let mut sat = TwoSAT::new(2);
+
+        sat.add_clause(1, 2);
+        sat.add_unit(-2);
+        sat.add_unit(2);
+
+        assert_eq!(sat.solve(), Err(vec![1, 2]));
+
+        /* no clause */
+
+        assert_eq!(TwoSAT::new(2).solve(), Ok(vec![false, false]));
+    }
+
+    #[test]
+    fn test_two_sat_randomdata() {
+        let lit = |n: usize| {
+            let x = random_range!(1..=n) as isize;
+
+            if random_range!(0..2) == 0 { x } else { -x }
+        };
+
+        for _ in 0..500 {
+            let n = random_range!(1..8);
+            let m = random_range!(1..3 * n);
+
+            let mut sat = TwoSAT::new(n);
+
+            for _ in 0..m {
+                sat.add_clause(lit(n), lit(n));
+            }
+
+            match sat.solve() {
+                Ok(assign) => {
+                    assert_eq!(assign.len(), n);
+                    assert!(eval(&sat, &assign));
+                }
+                Err(core) => {
+                    assert!(!brute_force(&sat));
+
+                    let mut sub = TwoSAT::new(n);
+
+                    for i in core {
+                        let (a, b) = sat.clauses()[i];
+                        sub.add_clause(a, b);
+                    }
+
+                    assert!(!brute_force(&sub));
+                }
+            }
+        }
+    }
+}
@@ -2,7 +2,10 @@
 //!
 //!
 
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::{
+    cmp::min,
+    collections::{BTreeMap, HashMap, HashSet},
+};
 
 use coll::{
     aux::{VerifyError, VerifyResult},
@@ -10,16 +13,16 @@ use coll::{
 };
 use common::{hashset, Itertools, ordered_insert};
 
-use super::Graph;
+use super::{Graph, GraphView, Vertex, Weight};
 
 
-impl Graph {
-    pub fn verify_sccs(&self, sccs: &[Vec<usize>]) -> VerifyResult {
+impl<V: Vertex, W: Weight> Graph<V, W> {
+    pub fn verify_sccs(&self, sccs: &[Vec<V>]) -> VerifyResult {
         debug_assert!(self.is_dir);
 
         /* vertexs are disjoint and complete */
 
-        let vertexs_origin = self.vertexs().sorted().collect::<Vec<usize>>();
+        let vertexs_origin = self.vertexs().sorted().collect::<Vec<V>>();
         let mut vertexs_scc = vec![];
 
         for scc in sccs {
@@ -43,14 +46,14 @@ impl Graph {
         Ok(())
     }
 
-    fn verify_scc(&self, scc: &[usize]) -> VerifyResult {
+    fn verify_scc(&self, scc: &[V]) -> VerifyResult {
         if scc.is_empty() {
             return Err(VerifyError::Inv("Invalid empty scc".to_owned()));
         }
 
-        let scc_vertexs = scc.iter().cloned().sorted().collect::<Vec<usize>>();
+        let scc_vertexs = scc.iter().cloned().sorted().collect::<Vec<V>>();
 
-        let mut acc: HashSet<usize> = scc.into_iter().cloned().collect();
+        let mut acc: HashSet<V> = scc.into_iter().cloned().collect();
 
         for u in scc.iter().cloned() {
             let mut visited = hashset![u];
@@ -68,7 +71,7 @@ impl Graph {
             acc = acc.intersection(&visited).cloned().collect();
         }
 
-        let visited_vertexs = acc.into_iter().sorted().collect::<Vec<usize>>();
+        let visited_vertexs = acc.into_iter().sorted().collect::<Vec<V>>();
 
         if visited_vertexs != scc_vertexs {
             return Err(
@@ -83,7 +86,9 @@ impl Graph {
 }
 
 
-pub fn scc_tarjan(g: &Graph) -> Vec<Vec<usize>> {
+pub fn scc_tarjan<V: Vertex, W: Weight, G: GraphView<V, W>>(
+    g: &G,
+) -> Vec<Vec<V>> {
     #[derive(Default, Clone, Copy)]
     struct DFSMeta {
         index: Option<usize>,
@@ -91,93 +96,199 @@ pub fn scc_tarjan(g: &Graph) -> Vec<Vec<usize>> {
         on_stack: bool,
     }
 
-    fn dfs_scc(
-        g: &Graph,
-        u: usize,
-        comps: &mut Vec<Vec<usize>>,
-        stack: &mut Vec<usize>,
+    fn dfs_scc<V: Vertex, W: Weight, G: GraphView<V, W>>(
+        g: &G,
+        u: V,
+        comps: &mut Vec<Vec<V>>,
+        stack: &mut Vec<V>,
         index: &mut usize,
-        vertexs: &mut Vec<DFSMeta>,
+        vertexs: &mut HashMap<V, DFSMeta>,
     ) {
-        vertexs[u] = DFSMeta {
+        set!(vertexs => u => DFSMeta {
             index: Some(*index),
             lowlink: *index,
             on_stack: true,
-        };
+        });
 
         *index += 1;
         stack.push(u);
 
-        for v in get!(g.e => u => vec![]) {
-            if vertexs[v].index.is_none() {
+        for (v, _) in g.adj(u) {
+            let meta_v = get!(vertexs => v => DFSMeta::default());
+
+            if meta_v.index.is_none() {
                 dfs_scc(g, v, comps, stack, index, vertexs);
 
-                if vertexs[v].lowlink < vertexs[u].lowlink {
-                    vertexs[u].lowlink = vertexs[v].lowlink;
+                let lowlink_v = get!(vertexs => v).lowlink;
+                let meta_u = vertexs.get_mut(&u).unwrap();
+
+                if lowlink_v < meta_u.lowlink {
+                    meta_u.lowlink = lowlink_v;
                 }
 
-            } else if vertexs[v].index < vertexs[u].index
-                && vertexs[v].on_stack
-            {
-                if vertexs[v].index.unwrap() < vertexs[u].lowlink {
-                    vertexs[u].lowlink = vertexs[v].index.unwrap();
+            } else {
+                let meta_u = vertexs.get_mut(&u).unwrap();
+
+                if meta_v.index < meta_u.index
+                    && meta_v.on_stack
+                    && meta_v.index.unwrap() < meta_u.lowlink
+                {
+                    meta_u.lowlink = meta_v.index.unwrap();
                 }
             }
         }
 
         /* start a new scc */
 
-        if vertexs[u].lowlink == vertexs[u].index.unwrap() {
+        let meta_u = get!(vertexs => u);
+
+        if meta_u.lowlink == meta_u.index.unwrap() {
             let mut new_comp = Vec::new();
 
             while let Some(s) = stack.pop() {
                 ordered_insert!(&mut new_comp, s);
-                vertexs[s].on_stack = false;
+                vertexs.get_mut(&s).unwrap().on_stack = false;
 
                 if s == u {
                     break;
                 }
             }
 
-            ordered_insert!(comps, new_comp, |x: &Vec<usize>| x[0]);
+            ordered_insert!(comps, new_comp, |x: &Vec<V>| x[0]);
         }
     }
 
     let mut comps = Vec::new();
 
-    if g.e.is_empty() {
-        return comps;
+    let mut stack = vec![];
+    let mut index = 0;
+
+    let mut vertexs = HashMap::new();
+
+    for u in g.vertexs() {
+        if !vertexs.contains_key(&u) {
+            dfs_scc(g, u, &mut comps, &mut stack, &mut index, &mut vertexs)
+        }
+    }
+
+    comps
+}
+
+
+/// Non-recursive version of `scc_tarjan` (explicit stack), same output
+pub fn scc_tarjan_nr<V: Vertex, W: Weight, G: GraphView<V, W>>(
+    g: &G,
+) -> Vec<Vec<V>> {
+    #[derive(Clone, Copy)]
+    struct DFSMeta {
+        index: usize,
+        lowlink: usize,
+        on_stack: bool,
     }
 
+    let mut comps = Vec::new();
+
     let mut stack = vec![];
     let mut index = 0;
 
-    let max_v = g.vertexs().max().unwrap();
+    let mut vertexs: HashMap<V, DFSMeta> = HashMap::new();
 
-    let mut vertexs = vec![DFSMeta::default(); max_v + 1];
+    for s in g.vertexs() {
+        if vertexs.contains_key(&s) {
+            continue;
+        }
 
-    for u in g.vertexs() {
-        if vertexs[u].index.is_none() {
-            dfs_scc(g, u, &mut comps, &mut stack, &mut index, &mut vertexs)
+        set!(vertexs => s => DFSMeta {
+            index,
+            lowlink: index,
+            on_stack: true,
+        });
+
+        index += 1;
+        stack.push(s);
+
+        // (u, rest adjacent vertexs of u)
+        let mut frames = vec![(s, g.adj(s))];
+
+        while let Some((u, tos)) = frames.last_mut() {
+            let u = *u;
+
+            if let Some((v, _)) = tos.next() {
+                if let Some(&meta_v) = vertexs.get(&v) {
+                    let meta_u = vertexs.get_mut(&u).unwrap();
+
+                    if meta_v.on_stack && meta_v.index < meta_u.lowlink {
+                        meta_u.lowlink = meta_v.index;
+                    }
+                } else {
+                    set!(vertexs => v => DFSMeta {
+                        index,
+                        lowlink: index,
+                        on_stack: true,
+                    });
+
+                    index += 1;
+                    stack.push(v);
+                    frames.push((v, g.adj(v)));
+                }
+
+                continue;
+            }
+
+            frames.pop();
+
+            /* start a new scc */
+
+            let meta_u = get!(vertexs => u);
+
+            if meta_u.lowlink == meta_u.index {
+                let mut new_comp = Vec::new();
+
+                while let Some(s) = stack.pop() {
+                    new_comp.push(s);
+                    vertexs.get_mut(&s).unwrap().on_stack = false;
+
+                    if s == u {
+                        break;
+                    }
+                }
+
+                new_comp.sort_unstable();
+                comps.push(new_comp);
+            }
+
+            /* return to parent */
+
+            if let Some((p, _)) = frames.last() {
+                let meta_p = vertexs.get_mut(p).unwrap();
+
+                if meta_u.lowlink < meta_p.lowlink {
+                    meta_p.lowlink = meta_u.lowlink;
+                }
+            }
         }
     }
 
+    comps.sort_unstable_by_key(|x| x[0]);
+
     comps
 }
 
 
 
 /// or Gabow
-pub fn scc_path_based(g: &Graph) -> Vec<Vec<usize>> {
-    fn find_comp(
-        g: &Graph,
-        u: usize,
-        stack: &mut Vec<usize>,
-        path: &mut Vec<usize>,
+pub fn scc_path_based<V: Vertex, W: Weight, G: GraphView<V, W>>(
+    g: &G,
+) -> Vec<Vec<V>> {
+    fn find_comp<V: Vertex, W: Weight, G: GraphView<V, W>>(
+        g: &G,
+        u: V,
+        stack: &mut Vec<V>,
+        path: &mut Vec<V>,
         index: &mut usize,
-        vertexs: &mut HashMap<usize, Option<usize>>,
-        assigned: &mut HashSet<usize>,
-        comps: &mut Vec<Vec<usize>>,
+        vertexs: &mut HashMap<V, Option<usize>>,
+        assigned: &mut HashSet<V>,
+        comps: &mut Vec<Vec<V>>,
     ) {
         set!(vertexs => u => Some(*index));
         *index += 1;
@@ -185,7 +296,7 @@ pub fn scc_path_based(g: &Graph) -> Vec<Vec<usize>> {
         stack.push(u);
         path.push(u);
 
-        for v in get!(g.e => u => vec![]) {
+        for (v, _) in g.adj(u) {
             if get!(vertexs => v).is_none() {
                 find_comp(g, v, stack, path, index, vertexs, assigned, comps);
             } else if !assigned.contains(&v) {
@@ -206,7 +317,7 @@ pub fn scc_path_based(g: &Graph) -> Vec<Vec<usize>> {
                 if s == u { break }
             }
 
-            ordered_insert!(comps, new_comp, |x: &Vec<usize>| x[0]);
+            ordered_insert!(comps, new_comp, |x: &Vec<V>| x[0]);
             path.pop();
         }
     }
@@ -218,10 +329,10 @@ pub fn scc_path_based(g: &Graph) -> Vec<Vec<usize>> {
     let mut vertexs = g
         .vertexs()
         .map(|u| (u, None))
-        .collect::<HashMap<usize, Option<usize>>>();
+        .collect::<HashMap<V, Option<usize>>>();
 
     let mut assigned = HashSet::new();
-    let mut comps: Vec<Vec<usize>> = Vec::new();
+    let mut comps: Vec<Vec<V>> = Vec::new();
 
     for u in g.vertexs() {
         if get!(vertexs => u).is_none() {
@@ -243,13 +354,13 @@ pub fn scc_path_based(g: &Graph) -> Vec<Vec<usize>> {
 
 
 /// 大概读作 'kao ser ra zhu'
-pub fn scc_kosaraju(g: &Graph) -> Vec<Vec<usize>> {
+pub fn scc_kosaraju<V: Vertex, W: Weight>(g: &Graph<V, W>) -> Vec<Vec<V>> {
     /// Visit each Vertexs in Postfix order
-    fn visit(
-        g: &Graph,
-        u: usize,
-        vis: &mut HashSet<usize>,
-        l: &mut Vec<usize>,
+    fn visit<V: Vertex, W: Weight>(
+        g: &Graph<V, W>,
+        u: V,
+        vis: &mut HashSet<V>,
+        l: &mut Vec<V>,
     ) {
         if !vis.contains(&u) {
             vis.insert(u);
@@ -263,12 +374,12 @@ pub fn scc_kosaraju(g: &Graph) -> Vec<Vec<usize>> {
     }
 
     /// Assign Components
-    fn assign(
-        g: &Graph,
-        u: usize,
-        root: usize,
-        comps: &mut BTreeMap<usize, Vec<usize>>,
-        vis: &mut HashSet<usize>,
+    fn assign<V: Vertex, W: Weight>(
+        g: &Graph<V, W>,
+        u: V,
+        root: V,
+        comps: &mut BTreeMap<V, Vec<V>>,
+        vis: &mut HashSet<V>,
     ) {
         if !vis.contains(&u) {
             vis.insert(u);
@@ -286,8 +397,8 @@ pub fn scc_kosaraju(g: &Graph) -> Vec<Vec<usize>> {
     /* DFS 1 */
 
     let mut vis = HashSet::new();
-    let mut l: Vec<usize> = vec![];
-    let mut comps: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    let mut l: Vec<V> = vec![];
+    let mut comps: BTreeMap<V, Vec<V>> = BTreeMap::new();
 
     for u in g.vertexs() {
         visit(g, u, &mut vis, &mut l);
@@ -308,15 +419,57 @@ pub fn scc_kosaraju(g: &Graph) -> Vec<Vec<usize>> {
 }
 
 
+/// Condensation DAG, vertex is index of scc in sccs,
+/// weight of multiple edges between two sccs is the minimum one.
+///
+/// Return (DAG, component of each vertex), the DAG is built from edges, so an
+/// isolated scc (no edge to or from any other scc) isn't a vertex of it.
+pub fn condense<V: Vertex, W: Weight>(
+    g: &Graph<V, W>,
+    sccs: &[Vec<V>],
+) -> (Graph<usize, W>, HashMap<V, usize>) {
+    debug_assert!(g.is_dir);
+
+    let comp_of: HashMap<V, usize> = sccs
+        .iter()
+        .enumerate()
+        .flat_map(|(i, scc)| scc.iter().map(move |&v| (v, i)))
+        .collect();
+
+    let mut w: HashMap<(usize, usize), W> = HashMap::new();
+
+    for (u, v, w_uv) in g.edges() {
+        let (cu, cv) = (get!(comp_of => u), get!(comp_of => v));
+
+        if cu == cv {
+            continue;
+        }
+
+        w.entry((cu, cv))
+            .and_modify(|cur| *cur = min(*cur, w_uv))
+            .or_insert(w_uv);
+    }
+
+    let dag = Graph::from_directed_iter(
+        w.into_iter().sorted().map(|((cu, cv), w)| (cu, cv, w)),
+    );
+
+    (dag, comp_of)
+}
+
+
 
 #[cfg(test)]
 mod tests {
 
     use coll::aux::VerifyError::*;
-    use common::random_range;
+    use common::{random_range, thread_rng};
 
     use super::*;
-    use crate::{test::GraphGenOptions, Graph};
+    use crate::{
+        test::{model::gen_chain, GraphGenOptions},
+        Graph,
+    };
 
     fn setup_dir_data() -> Vec<Graph> {
         let opt = GraphGenOptions {
@@ -381,6 +534,7 @@ mod tests {
             // }
 
             assert_eq!(comps_kosaraju, comps_tarjan);
+            assert_eq!(comps_tarjan, scc_tarjan_nr(&g));
             // assert_eq!(comps_kosaraju, comps_tarjan2, "\ng:{g:?}");
             assert_eq!(comps_kosaraju, comps_path_based);
         }
@@ -394,6 +548,72 @@ mod tests {
 
             // println!("{}", comps_msu.len());
             assert_eq!(comps_msu, comps_tarjan);
+            assert_eq!(comps_tarjan, scc_tarjan_nr(&g));
+        }
+    }
+
+    #[test]
+    fn test_scc_nr_chain() {
+        let n = 1_000_000;
+        let g = gen_chain(n, true, 1..2, &mut thread_rng());
+
+        let comps = scc_tarjan_nr(&g);
+
+        assert_eq!(comps.len(), n);
+        assert!(comps.iter().enumerate().all(|(i, c)| c == &[i + 1]));
+
+        /* close the chain */
+
+        let g = Graph::from_directed_iter(g.edges().chain([(n, 1, 1)]));
+
+        assert_eq!(scc_tarjan_nr(&g), vec![(1..=n).collect::<Vec<_>>()]);
+    }
+
+    #[test]
+    fn test_condense() {
+        let g = Graph::from_directed_iter([
+            (1, 2, 5),
+            (2, 1, 1),
+            (2, 3, 4),
+            (1, 3, 2),
+            (3, 4, 1),
+            (4, 3, 1),
+            (5, 5, 1),
+            (4, 6, 3),
+        ]);
+
+        let sccs = scc_tarjan(&g);
+        let (dag, comp_of) = condense(&g, &sccs);
+
+        assert_eq!(sccs, vec![vec![1, 2], vec![3, 4], vec![5], vec![6]]);
+        assert_eq!(
+            dag.edges().sorted().collect::<Vec<_>>(),
+            vec![(0, 1, 2), (1, 3, 3)]
+        );
+        assert_eq!(comp_of[&5], 2);
+
+        for g in setup_dir_data() {
+            let sccs = scc_tarjan(&g);
+            let (dag, comp_of) = condense(&g, &sccs);
+
+            assert!(dag.components().iter().all(|comp| comp.len() == 1));
+
+            for (u, v, _) in g.edges() {
+                let (cu, cv) = (comp_of[&u], comp_of[&v]);
+
+                assert_eq!(cu == cv, sccs[cu].contains(&v));
+                assert!(cu == cv || dag.contains_edge((cu, cv)));
+            }
+
+            for (cu, cv, w) in dag.edges() {
+                let expect = g
+                    .edges()
+                    .filter(|&(u, v, _)| comp_of[&u] == cu && comp_of[&v] == cv)
+                    .map(|(_, _, w)| w)
+                    .min();
+
+                assert_eq!(Some(w), expect);
+            }
         }
     }
 }
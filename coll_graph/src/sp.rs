@@ -3,12 +3,20 @@
 ////////////////////////////////////////////////////////////////////////////////
 //// Structures
 
-use std::collections::{HashMap, HashSet};
-
-use coll::{apush, easycoll::M2, get, getopt, set, stack};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    marker::PhantomData,
+    ops::Sub,
+};
+
+use coll::{
+    apush,
+    easycoll::{M2, MV},
+    get, getopt, set, stack,
+};
 use coll_heap::{ dary, sdary };
 
-use super::Graph;
+use super::{Graph, GraphView, Vertex, Weight};
 
 
 macro_rules! pre_to_path {
@@ -26,7 +34,7 @@ macro_rules! pre_to_path {
                 cur = prev;
             }
 
-            path.into_iter().rev().collect::<Vec<usize>>()
+            path.into_iter().rev().collect::<Vec<_>>()
         }
 
     };
@@ -36,56 +44,109 @@ pub(crate) use pre_to_path;
 
 /// Floyd algorithm (DP) 全源最短路径
 #[allow(unused)]
-pub struct SPFloyd<'a> {
-    g: &'a Graph,
+pub struct SPFloyd<'a, V = usize, W = isize> {
+    g: &'a Graph<V, W>,
     /// shortest path weight
-    spw: HashMap<(usize, usize), isize>,
+    spw: HashMap<(V, V), W>,
     //// shortest path paths
-    next: M2<usize, usize, usize>,
+    next: M2<V, V, V>,
 }
 
 
 #[allow(unused)]
-pub struct SPBellmanFord<'a> {
-    g: &'a Graph,
+pub struct SPBellmanFord<'a, V = usize, W = isize, G = Graph<V, W>> {
+    g: &'a G,
     /// shortest path weight
-    src: usize,
-    spw: HashMap<usize, isize>,
+    src: V,
+    spw: HashMap<V, W>,
     //// shortest path paths
-    pre: HashMap<usize, usize>,
+    pre: HashMap<V, V>,
 }
 
 
 #[allow(unused)]
-pub struct SPFA<'a> {
-    g: &'a Graph,
+pub struct SPFA<'a, V = usize, W = isize, G = Graph<V, W>> {
+    g: &'a G,
     /// shortest path weight
-    src: usize,
-    spw: HashMap<usize, isize>,
+    src: V,
+    spw: HashMap<V, W>,
     //// shortest path paths
-    pre: HashMap<usize, usize>,
+    pre: HashMap<V, V>,
 }
 
 
 /// /ˈdaɪkstrəz/
 #[allow(unused)]
-pub struct SPDijkstra<'a> {
-    g: &'a Graph,
+pub struct SPDijkstra<'a, V = usize, W = isize, G = Graph<V, W>> {
+    g: &'a G,
+    /// shortest path weight
+    src: V,
+    spw: HashMap<V, W>,
+    //// shortest path paths
+    pre: HashMap<V, V>,
+}
+
+
+/// A* 启发式搜索 (point to point)
+///
+/// h(v, dst) 估计 v 到 dst 的距离, 需要 admissible (不高估),
+/// 不要求 consistent (被重新松弛的点会重新打开)
+pub struct SPAStar<'a, H, V = usize, W = isize, G = Graph<V, W>> {
+    g: &'a G,
+    src: V,
+    h: H,
+    _marker: PhantomData<W>,
+}
+
+
+/// Bidirectional Dijkstra (point to point), 两端交替扩展,
+/// 两侧堆顶之和不小于当前最优值时提前终止
+pub struct SPBiDijkstra<'a, V = usize, W = isize, G = Graph<V, W>> {
+    g: &'a G,
+    src: V,
+    /// in-edges (u, w) of v
+    radj: MV<V, (V, W)>,
+}
+
+
+/// Yen's k shortest loopless paths (point to point), 要求非负权
+pub struct SPYen<'a, V = usize, W = isize, G = Graph<V, W>> {
+    g: &'a G,
+    src: V,
+    _marker: PhantomData<W>,
+}
+
+
+/// Shortest path DAG, 保留所有等距前驱 (零权环会使其不再是 DAG)
+#[allow(unused)]
+pub struct SPDAG<'a, V = usize, W = isize, G = Graph<V, W>> {
+    g: &'a G,
     /// shortest path weight
-    src: usize,
-    spw: HashMap<usize, isize>,
+    src: V,
+    spw: HashMap<V, W>,
     //// shortest path paths
-    pre: HashMap<usize, usize>,
+    pre: HashMap<V, V>,
+    /// all predecessors (u, w_uv) of v at equal distance
+    pres: MV<V, (V, W)>,
+}
+
+
+/// Graph view without some vertexs and arcs
+struct Masked<'a, V, W, G> {
+    g: &'a G,
+    vs: HashSet<V>,
+    es: HashSet<(V, V)>,
+    _marker: PhantomData<W>,
 }
 
 
 #[allow(unused)]
-pub struct SPJohnson<'a> {
-    g: &'a Graph,
-    h: HashMap<usize, isize>,
-    spw: M2<usize, usize, isize>,
+pub struct SPJohnson<'a, V = usize, W = isize> {
+    g: &'a Graph<V, W>,
+    h: HashMap<V, W>,
+    spw: M2<V, V, W>,
     //// shortest path paths
-    sppre: M2<usize, usize, usize>,
+    sppre: M2<V, V, V>,
 }
 
 
@@ -93,61 +154,256 @@ pub struct SPJohnson<'a> {
 ////////////////////////////////////////////////////////////////////////////////
 //// Implementations
 
-impl<'a> SPFA<'a> {
-    pub fn new(g: &'a Graph, src: usize) -> Result<Self, Vec<usize>> {
+impl<'a, V: Vertex, W: Weight, G: GraphView<V, W>> SPFA<'a, V, W, G> {
+    pub fn new(g: &'a G, src: V) -> Result<Self, Vec<V>> {
         let (spw, pre) = sp_fa_early_termination(g, src)?;
 
         Ok(Self { g, src, spw, pre })
     }
 
-    pub fn query(&self, dst: usize) -> (isize, Vec<usize>) {
+    pub fn query(&self, dst: V) -> (W, Vec<V>) {
         (get!(self.spw => dst), pre_to_path!(dst, &self.pre))
     }
 }
 
 
-impl<'a> SPBellmanFord<'a> {
-    pub fn new(g: &'a Graph, src: usize) -> Result<Self, Vec<usize>> {
+impl<'a, V: Vertex, W: Weight, G: GraphView<V, W>>
+    SPBellmanFord<'a, V, W, G>
+{
+    pub fn new(g: &'a G, src: V) -> Result<Self, Vec<V>> {
         let (spw, pre) = sp_bellman_ford(g, src)?;
 
         Ok(Self { g, src, spw, pre })
     }
 
-    pub fn query(&self, dst: usize) -> (isize, Vec<usize>) {
+    pub fn query(&self, dst: V) -> (W, Vec<V>) {
         (get!(self.spw => dst), pre_to_path!(dst, &self.pre))
     }
 }
 
 
-impl<'a> SPDijkstra<'a> {
-    pub fn new(g: &'a Graph, src: usize) -> Self {
+impl<'a, V: Vertex, W: Weight, G: GraphView<V, W>> SPDijkstra<'a, V, W, G> {
+    pub fn new(g: &'a G, src: V) -> Self {
         let (spw, pre) = sp_dijkstra(g, src);
 
         Self { g, src, spw, pre }
     }
 
-    pub fn new2(g: &'a Graph, src: usize) -> Self {
+    pub fn new2(g: &'a G, src: V) -> Self {
         let (spw, pre) = sp_dijkstra2(g, src);
 
         Self { g, src, spw, pre }
     }
 
-    pub fn query(&self, dst: usize) -> (isize, Vec<usize>) {
+    pub fn query(&self, dst: V) -> (W, Vec<V>) {
         (get!(self.spw => dst), pre_to_path!(dst, &self.pre))
     }
 }
 
 
+impl<'a, H, V, W, G> SPAStar<'a, H, V, W, G>
+where
+    H: Fn(V, V) -> W,
+    V: Vertex,
+    W: Weight,
+    G: GraphView<V, W>,
+{
+    pub fn new(g: &'a G, src: V, h: H) -> Self {
+        Self {
+            g,
+            src,
+            h,
+            _marker: PhantomData,
+        }
+    }
+
+    /// None if dst is unreachable
+    pub fn query(&self, dst: V) -> Option<(W, Vec<V>)> {
+        let h = |v| (self.h)(v, dst);
+        let (w, pre) = sp_astar(self.g, self.src, dst, h)?;
+
+        Some((w, pre_to_path!(dst, &pre)))
+    }
+}
+
+
+impl<'a, V: Vertex, W: Weight, G: GraphView<V, W>> SPBiDijkstra<'a, V, W, G> {
+    pub fn new(g: &'a G, src: V) -> Self {
+        let mut radj = MV::new();
+
+        for (u, v, w) in g.edges() {
+            apush!(radj => v => (u, w));
+        }
+
+        Self { g, src, radj }
+    }
+
+    /// None if dst is unreachable
+    pub fn query(&self, dst: V) -> Option<(W, Vec<V>)> {
+        let radj = |v: V| {
+            self.radj.0.get(&v).into_iter().flatten().cloned()
+        };
+
+        let (w, pre, (a, b), succ) =
+            sp_bidijkstra(self.g, radj, self.src, dst)?;
+
+        if a == b {
+            return Some((w, vec![]));
+        }
+
+        let mut path = pre_to_path!(a, &pre);
+        let mut cur = b;
+
+        path.push(cur);
+
+        while cur != dst {
+            cur = get!(succ => cur);
+            path.push(cur);
+        }
+
+        Some((w, path))
+    }
+}
+
+
+impl<'a, V: Vertex, W: Weight, G: GraphView<V, W>> SPYen<'a, V, W, G> {
+    pub fn new(g: &'a G, src: V) -> Self {
+        Self {
+            g,
+            src,
+            _marker: PhantomData,
+        }
+    }
+
+    /// At most k paths, in ascending order of weight
+    pub fn query(&self, dst: V, k: usize) -> Vec<(W, Vec<V>)> {
+        sp_yen(self.g, self.src, dst, k)
+            .into_iter()
+            .map(|(w, path)| (w, path[1..].to_vec()))
+            .collect()
+    }
+}
+
+
+impl<'a, V: Vertex, W: Weight, G: GraphView<V, W>> SPDAG<'a, V, W, G> {
+    /// Based on Bellman-Ford, Err(negative cycle)
+    pub fn new(g: &'a G, src: V) -> Result<Self, Vec<V>> {
+        let (spw, pre) = sp_bellman_ford(g, src)?;
+        let mut pres = MV::new();
+
+        for (u, v, w) in g.edges() {
+            if let Some(dis_u) = getopt!(spw => u)
+                && dis_u + w == get!(spw => v)
+            {
+                apush!(pres => v => (u, w));
+            }
+        }
+
+        Ok(Self {
+            g,
+            src,
+            spw,
+            pre,
+            pres,
+        })
+    }
+
+    /// One of the shortest paths
+    pub fn query(&self, dst: V) -> (W, Vec<V>) {
+        (get!(self.spw => dst), pre_to_path!(dst, &self.pre))
+    }
+
+    pub fn pres(&self, v: V) -> impl Iterator<Item = V> {
+        self.pres.0.get(&v).into_iter().flatten().map(|(u, _)| *u)
+    }
+
+    /// All (simple) shortest paths, it may be exponentially many
+    pub fn paths(&self, dst: V) -> Vec<Vec<V>> {
+        fn dfs<V: Vertex, W: Weight, G: GraphView<V, W>>(
+            dag: &SPDAG<V, W, G>,
+            u: V,
+            rpath: &mut Vec<V>,
+            visited: &mut HashSet<V>,
+            res: &mut Vec<Vec<V>>,
+        ) {
+            if u == dag.src {
+                res.push(rpath.iter().rev().skip(1).cloned().collect());
+                return;
+            }
+
+            for p in dag.pres(u) {
+                if visited.insert(p) {
+                    rpath.push(p);
+                    dfs(dag, p, rpath, visited, res);
+                    rpath.pop();
+                    visited.remove(&p);
+                }
+            }
+        }
+
+        let mut res = vec![];
+
+        if self.spw.contains_key(&dst) {
+            let mut rpath = vec![dst];
+            let mut visited = HashSet::from([dst]);
+
+            dfs(self, dst, &mut rpath, &mut visited, &mut res);
+        }
+
+        res
+    }
+
+    pub fn to_graph(&self) -> Graph<V, W> {
+        Graph::from_directed_iter(self.pres.0.iter().flat_map(|(v, pres)| {
+            pres.iter().map(move |(u, w)| (*u, *v, *w))
+        }))
+    }
+}
+
+
+impl<'a, V: Vertex, W: Weight, G: GraphView<V, W>> Masked<'a, V, W, G> {
+    fn new(g: &'a G) -> Self {
+        Self {
+            g,
+            vs: HashSet::new(),
+            es: HashSet::new(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+
+impl<'a, V: Vertex, W: Weight, G: GraphView<V, W>> GraphView<V, W>
+    for Masked<'a, V, W, G>
+{
+    fn is_dir(&self) -> bool {
+        self.g.is_dir()
+    }
+
+    fn vertexs(&self) -> impl Iterator<Item = V> {
+        self.g.vertexs().filter(|v| !self.vs.contains(v))
+    }
+
+    fn adj(&self, u: V) -> impl Iterator<Item = (V, W)> {
+        let masked = self.vs.contains(&u);
+
+        self.g.adj(u).filter(move |(v, _)| {
+            !masked && !self.vs.contains(v) && !self.es.contains(&(u, *v))
+        })
+    }
+}
+
+
 /// 对于无向图，探不到负环，或者说每条边都是负环
-impl<'a> SPFloyd<'a> {
-    pub fn new(g: &'a Graph) -> Result<Self, Vec<usize>> {
+impl<'a, V: Vertex, W: Weight> SPFloyd<'a, V, W> {
+    pub fn new(g: &'a Graph<V, W>) -> Result<Self, Vec<V>> {
         let (spw, next) = sp_floyd(g)?;
 
         Ok(Self { g, spw, next })
     }
 
     /// (total_weight, (src), .. , k1, k2, .. dst)
-    pub fn query(&self, src: usize, dst: usize) -> (isize, Vec<usize>) {
+    pub fn query(&self, src: V, dst: V) -> (W, Vec<V>) {
         (
             get!(self.spw => (src, dst)),
             next_to_path((src, dst), &self.next),
@@ -156,15 +412,15 @@ impl<'a> SPFloyd<'a> {
 }
 
 
-impl<'a> SPJohnson<'a> {
-    pub fn new(g: &'a Graph) -> Result<Self, (Graph, Vec<usize>)> {
+impl<'a, V: Vertex, W: Weight + Sub<Output = W>> SPJohnson<'a, V, W> {
+    pub fn new(g: &'a Graph<V, W>) -> Result<Self, (Graph<V, W>, Vec<V>)> {
         let (h, spw, sppre) = sp_johnson(g)?;
 
         Ok(Self { g, h, spw, sppre })
     }
 
     /// (total_weight, (src), .. , k1, k2, .. dst)
-    pub fn query(&self, src: usize, dst: usize) -> (isize, Vec<usize>) {
+    pub fn query(&self, src: V, dst: V) -> (W, Vec<V>) {
         (
             get!(self.spw => (src, dst)) - get!(self.h => src)
                 + get!(self.h => dst),
@@ -177,16 +433,16 @@ impl<'a> SPJohnson<'a> {
 ////////////////////////////////////////////////////////////////////////////////
 //// Functions
 
-fn sp_floyd(
-    g: &Graph,
-) -> Result<(HashMap<(usize, usize), isize>, M2<usize, usize, usize>), Vec<usize>>
+fn sp_floyd<V: Vertex, W: Weight>(
+    g: &Graph<V, W>,
+) -> Result<(HashMap<(V, V), W>, M2<V, V, V>), Vec<V>>
 {
-    let mut vertexs: Vec<usize> = g.vertexs().collect();
+    let mut vertexs: Vec<V> = g.vertexs().collect();
     vertexs.sort_unstable();
 
     let n = vertexs.len();
-    let mut spw = HashMap::<(usize, usize), isize>::new();
-    let mut next = M2::<usize, usize, usize>::new();
+    let mut spw = HashMap::<(V, V), W>::new();
+    let mut next = M2::<V, V, V>::new();
 
     /* init sp */
     for x in 0..n {
@@ -198,7 +454,7 @@ fn sp_floyd(
                 set!(spw => (x, y) => w);
                 set!(next => (x, y) => y); // 便于算法实现
             } else if x == y {
-                set!(spw => (x, y) => 0);
+                set!(spw => (x, y) => W::zero());
             }
         }
     }
@@ -219,7 +475,7 @@ fn sp_floyd(
                         set!(spw => (x, y) => w_xk + w_ky);
                         set!(next => (x, y) => get!(next => (x, k)));
 
-                        if x == y && w_xk + w_ky < 0 {
+                        if x == y && w_xk + w_ky < W::zero() {
                             let mut cycle = vec![x];
                             let mut c = get!(next => (x, y));
 
@@ -240,15 +496,15 @@ fn sp_floyd(
 }
 
 
-fn sp_bellman_ford(
-    g: &Graph,
-    src: usize,
-) -> Result<(HashMap<usize, isize>, HashMap<usize, usize>), Vec<usize>> {
+fn sp_bellman_ford<V: Vertex, W: Weight, G: GraphView<V, W>>(
+    g: &G,
+    src: V,
+) -> Result<(HashMap<V, W>, HashMap<V, V>), Vec<V>> {
     let mut pre = HashMap::new();
     let mut dis = HashMap::new();
     let n = g.vertexs().count();
 
-    set!(dis => src => 0);
+    set!(dis => src => W::zero());
 
     for _ in 1..=n - 1 {
         for (u, v, w) in g.edges() {
@@ -285,16 +541,16 @@ fn sp_bellman_ford(
 /// Shortest Path Faster Algorithm, imporoved Bellman-Ford algorithm
 ///
 /// O(ev), single source,
-pub fn sp_fa(
-    g: &Graph,
-    src: usize,
-) -> Result<(HashMap<usize, isize>, HashMap<usize, usize>), Vec<usize>> {
+pub fn sp_fa<V: Vertex, W: Weight, G: GraphView<V, W>>(
+    g: &G,
+    src: V,
+) -> Result<(HashMap<V, W>, HashMap<V, V>), Vec<V>> {
     let mut pre = HashMap::new();
     let mut dis = HashMap::new();
-    let mut cnt: HashMap<usize, usize> = HashMap::new(); // 穿过的边数
+    let mut cnt: HashMap<V, usize> = HashMap::new(); // 穿过的边数
     let n = g.vertexs().count();
 
-    set!(dis => src => 0);
+    set!(dis => src => W::zero());
 
     // avoid duplicated elements
     let mut vis = HashSet::new();
@@ -306,17 +562,15 @@ pub fn sp_fa(
     'outer: while let Some(u) = stack.pop() {
         vis.remove(&u);
 
-        for v in get!(g.e => u => vec![]) {
+        for (v, w_uv) in g.adj(u) {
             // 无向图不存在这条路径
-            if !g.is_dir && getopt!(pre => u) == Some(v) {
+            if !g.is_dir() && getopt!(pre => u) == Some(v) {
                 continue;
             }
 
             if let Some(dis_u) = getopt!(dis => u) {
-                if getopt!(dis => v).is_none()
-                    || dis_u + get!(g.w => (u, v)) < get!(dis => v)
-                {
-                    set!(dis => v => dis_u + get!(g.w => (u, v)));
+                if getopt!(dis => v).is_none() || dis_u + w_uv < get!(dis => v) {
+                    set!(dis => v => dis_u + w_uv);
                     set!(pre => v => u);
                     set!(cnt => v => get!(cnt => u => 0) + 1);
 
@@ -349,15 +603,15 @@ pub fn sp_fa(
 /// Shortest Path Faster Algorithm, imporoved Bellman-Ford algorithm
 ///
 /// O(ev), single source,
-pub fn sp_fa_early_termination(
-    g: &Graph,
-    src: usize,
-) -> Result<(HashMap<usize, isize>, HashMap<usize, usize>), Vec<usize>> {
+pub fn sp_fa_early_termination<V: Vertex, W: Weight, G: GraphView<V, W>>(
+    g: &G,
+    src: V,
+) -> Result<(HashMap<V, W>, HashMap<V, V>), Vec<V>> {
     let mut pre = HashMap::new();
     let mut dis = HashMap::new();
     let n = g.vertexs().count();
 
-    set!(dis => src => 0);
+    set!(dis => src => W::zero());
 
     // use stack instead of queue dst quick find negative circle
     let mut vis = HashSet::new();
@@ -368,17 +622,15 @@ pub fn sp_fa_early_termination(
     while let Some(u) = stack.pop() {
         vis.remove(&u);
 
-        for v in get!(g.e => u => vec![]) {
+        for (v, w_uv) in g.adj(u) {
             // 无向图不存在这条路径
-            if !g.is_dir && getopt!(pre => u) == Some(v) {
+            if !g.is_dir() && getopt!(pre => u) == Some(v) {
                 continue;
             }
 
             if let Some(dis_u) = getopt!(dis => u) {
-                if getopt!(dis => v).is_none()
-                    || dis_u + get!(g.w => (u, v)) < get!(dis => v)
-                {
-                    set!(dis => v => dis_u + get!(g.w => (u, v)));
+                if getopt!(dis => v).is_none() || dis_u + w_uv < get!(dis => v) {
+                    set!(dis => v => dis_u + w_uv);
                     set!(pre => v => u);
 
                     i += 1;
@@ -403,23 +655,23 @@ pub fn sp_fa_early_termination(
 }
 
 
-fn sp_dijkstra(
-    g: &Graph,
-    src: usize,
-) -> (HashMap<usize, isize>, HashMap<usize, usize>) {
+fn sp_dijkstra<V: Vertex, W: Weight, G: GraphView<V, W>>(
+    g: &G,
+    src: V,
+) -> (HashMap<V, W>, HashMap<V, V>) {
     let mut pre = HashMap::new();
     let mut dis_m1 = HashMap::new();
 
     let mut dis = dary::DaryHeap::<3, _, _>::new();
 
-    dis.insert(src, 0);
+    dis.insert(src, W::zero());
 
     while let Some((u, dis_u)) = dis.pop_item() {
         set!(dis_m1 => u => dis_u);
 
-        for v in get!(g.e => u) {
+        for (v, w_uv) in g.adj(u) {
             if !dis_m1.contains_key(&v) {
-                let w = dis_u + get!(g.w => (u, v));
+                let w = dis_u + w_uv;
                 let maybe_dis_v  = dis.get(&v).cloned();
 
                 if maybe_dis_v.is_none() || w < maybe_dis_v.unwrap() {
@@ -433,26 +685,26 @@ fn sp_dijkstra(
     (dis_m1, pre)
 }
 
-fn sp_dijkstra2(
-    g: &Graph,
-    src: usize,
-) -> (HashMap<usize, isize>, HashMap<usize, usize>) {
+fn sp_dijkstra2<V: Vertex, W: Weight, G: GraphView<V, W>>(
+    g: &G,
+    src: V,
+) -> (HashMap<V, W>, HashMap<V, V>) {
     let mut pre = HashMap::new();
     let mut dis_m1 = HashMap::new();
 
     let mut dis = sdary::DaryHeap::<3, _>::new();
 
-    dis.push((0, src));
+    dis.push((W::zero(), src));
 
     while let Some((dis_u, u)) = dis.pop() {
         if dis_m1.contains_key(&u) { continue }
 
         set!(dis_m1 => u => dis_u);
 
-        for v in get!(g.e => u) {
+        for (v, w_uv) in g.adj(u) {
             if dis_m1.contains_key(&v) { continue }
 
-            let w: isize = dis_u + get!(g.w => (u, v));
+            let w: W = dis_u + w_uv;
             let maybe_dis_v  = dis_m1.get(&v).cloned();
 
             if maybe_dis_v.is_none() || w < maybe_dis_v.unwrap() {
@@ -465,34 +717,250 @@ fn sp_dijkstra2(
     (dis_m1, pre)
 }
 
-fn sp_johnson(
-    g: &Graph,
-) -> Result<
-    (
-        HashMap<usize, isize>,
-        M2<usize, usize, isize>,
-        M2<usize, usize, usize>,
-    ),
-    (Graph, Vec<usize>),
-> {
-    /* Create a new map with special node q*/
-    let mut g2 = g.clone();
+/// (spw(dst), pre)
+fn sp_astar<V: Vertex, W: Weight, G: GraphView<V, W>>(
+    g: &G,
+    src: V,
+    dst: V,
+    h: impl Fn(V) -> W,
+) -> Option<(W, HashMap<V, V>)> {
+    let mut pre = HashMap::new();
+    // g score
+    let mut dis = HashMap::new();
+    // f = g + h
+    let mut open = dary::DaryHeap::<3, _, _>::new();
 
-    let mut vertexs: Vec<usize> = g2.vertexs().collect();
-    vertexs.sort_unstable();
+    set!(dis => src => W::zero());
+    open.insert(src, h(src));
+
+    while let Some((u, _f)) = open.pop_item() {
+        let dis_u = get!(dis => u);
+
+        if u == dst {
+            return Some((dis_u, pre));
+        }
+
+        for (v, w_uv) in g.adj(u) {
+            let w = dis_u + w_uv;
+
+            if getopt!(dis => v).is_none() || w < get!(dis => v) {
+                set!(dis => v => w);
+                set!(pre => v => u);
 
-    let q = *vertexs.last().unwrap() + 1;
-    for v in vertexs.iter().cloned() {
-        set!(g2.w => (q, v) => 0);
-        apush!(g2.e => q => v);
+                if open.get(&v).is_some() {
+                    open.decrease_key(v, w + h(v));
+                } else {
+                    // new or reopened
+                    open.insert(v, w + h(v));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+
+/// (spw(dst), pre, meeting edge (a, b), succ)
+///
+/// 路径为 src -> .. -> a -> b -> .. -> dst, 若 src == dst, 则 a == b == src
+fn sp_bidijkstra<V, W, G, R, I>(
+    g: &G,
+    radj: R,
+    src: V,
+    dst: V,
+) -> Option<(W, HashMap<V, V>, (V, V), HashMap<V, V>)>
+where
+    V: Vertex,
+    W: Weight,
+    G: GraphView<V, W>,
+    R: Fn(V) -> I,
+    I: Iterator<Item = (V, W)>,
+{
+    if src == dst {
+        return Some((W::zero(), HashMap::new(), (src, src), HashMap::new()));
     }
 
-    /* Using SPFA dst calc h((q, v)) */
-    let h = match sp_fa(&g2, q) {
-        Ok((h, _)) => h,
-        Err(cycle) => return Err((g2, cycle)),
+    // forward, backward
+    let mut pre = HashMap::new();
+    let mut succ = HashMap::new();
+    let mut dis_f = HashMap::new();
+    let mut dis_b = HashMap::new();
+    let mut done_f = HashSet::new();
+    let mut done_b = HashSet::new();
+    let mut heap_f = dary::DaryHeap::<3, _, _>::new();
+    let mut heap_b = dary::DaryHeap::<3, _, _>::new();
+
+    set!(dis_f => src => W::zero());
+    set!(dis_b => dst => W::zero());
+    heap_f.insert(src, W::zero());
+    heap_b.insert(dst, W::zero());
+
+    // (best, meeting edge)
+    let mut best: Option<(W, (V, V))> = None;
+
+    // 一侧耗尽时, 该侧可达点都已确定, best 即为最优
+    while let (Some(&top_f), Some(&top_b)) = (heap_f.top(), heap_b.top()) {
+        if let Some((mu, _)) = best
+            && top_f + top_b >= mu
+        {
+            break;
+        }
+
+        if top_f <= top_b {
+            let (u, dis_u) = heap_f.pop_item().unwrap();
+            done_f.insert(u);
+
+            for (v, w_uv) in g.adj(u) {
+                if let Some(dis_v) = getopt!(dis_b => v) {
+                    let mu = dis_u + w_uv + dis_v;
+
+                    if best.is_none_or(|(best_mu, _)| mu < best_mu) {
+                        best = Some((mu, (u, v)));
+                    }
+                }
+
+                if done_f.contains(&v) {
+                    continue;
+                }
+
+                let w = dis_u + w_uv;
+
+                if getopt!(dis_f => v).is_none() {
+                    heap_f.insert(v, w);
+                } else if w < get!(dis_f => v) {
+                    heap_f.decrease_key(v, w);
+                } else {
+                    continue;
+                }
+
+                set!(dis_f => v => w);
+                set!(pre => v => u);
+            }
+        } else {
+            let (v, dis_v) = heap_b.pop_item().unwrap();
+            done_b.insert(v);
+
+            for (u, w_uv) in radj(v) {
+                if let Some(dis_u) = getopt!(dis_f => u) {
+                    let mu = dis_u + w_uv + dis_v;
+
+                    if best.is_none_or(|(best_mu, _)| mu < best_mu) {
+                        best = Some((mu, (u, v)));
+                    }
+                }
+
+                if done_b.contains(&u) {
+                    continue;
+                }
+
+                let w = dis_v + w_uv;
+
+                if getopt!(dis_b => u).is_none() {
+                    heap_b.insert(u, w);
+                } else if w < get!(dis_b => u) {
+                    heap_b.decrease_key(u, w);
+                } else {
+                    continue;
+                }
+
+                set!(dis_b => u => w);
+                set!(succ => u => v);
+            }
+        }
+    }
+
+    best.map(|(mu, edge)| (mu, pre, edge, succ))
+}
+
+
+/// [(weight, src, .., dst)], in ascending order of (weight, path)
+fn sp_yen<V: Vertex, W: Weight, G: GraphView<V, W>>(
+    g: &G,
+    src: V,
+    dst: V,
+    k: usize,
+) -> Vec<(W, Vec<V>)> {
+    let mut res: Vec<(W, Vec<V>)> = vec![];
+
+    if k == 0 {
+        return res;
+    }
+
+    let (spw, pre) = sp_dijkstra(g, src);
+
+    let Some(w) = getopt!(spw => dst) else {
+        return res;
     };
 
+    res.push((w, [vec![src], pre_to_path!(dst, &pre)].concat()));
+
+    // candidates
+    let mut cands = BTreeSet::new();
+
+    while res.len() < k {
+        let prev = res.last().unwrap().1.clone();
+        // weight of prev[..=i]
+        let mut root_w = W::zero();
+
+        for i in 0..prev.len() - 1 {
+            let spur = prev[i];
+            let root = &prev[..=i];
+            let mut masked = Masked::new(g);
+
+            // forbid the found paths sharing the same root
+            for (_, path) in res.iter() {
+                if path.len() > i + 1 && &path[..=i] == root {
+                    masked.es.insert((path[i], path[i + 1]));
+                }
+            }
+
+            // keep loopless
+            masked.vs.extend(root[..i].iter().cloned());
+
+            let (spw, pre) = sp_dijkstra(&masked, spur);
+
+            if let Some(spur_w) = getopt!(spw => dst) {
+                cands.insert((
+                    root_w + spur_w,
+                    [root.to_vec(), pre_to_path!(dst, &pre)].concat(),
+                ));
+            }
+
+            root_w = root_w + weight_of(g, prev[i], prev[i + 1]);
+        }
+
+        let next = loop {
+            match cands.pop_first() {
+                Some((_, path)) if res.iter().any(|(_, p)| p == &path) => (),
+                next => break next,
+            }
+        };
+
+        match next {
+            Some(next) => res.push(next),
+            None => break,
+        }
+    }
+
+    res
+}
+
+
+fn sp_johnson<V: Vertex, W: Weight + Sub<Output = W>>(
+    g: &Graph<V, W>,
+) -> Result<
+    (
+        HashMap<V, W>,
+        M2<V, V, W>,
+        M2<V, V, V>,
+    ),
+    (Graph<V, W>, Vec<V>),
+> {
+    let (mut g2, h) = johnson_potential(g)?;
+
+    let vertexs: Vec<V> = g.vertexs().collect();
+
     /* Reweight */
     for (u, v, w) in g.edges() {
         set!(g2.w => (u, v) => w + get!(h => u) - get!(h => v));
@@ -500,8 +968,8 @@ fn sp_johnson(
 
     /* Calc spw and spp usign Dijkstra */
 
-    let mut spw = M2::<usize, usize, isize>::new();
-    let mut sppre = M2::<usize, usize, usize>::new();
+    let mut spw = M2::<V, V, W>::new();
+    let mut sppre = M2::<V, V, V>::new();
 
     for v in vertexs {
         let (sspw, sspp) = sp_dijkstra(&g2, v);
@@ -514,10 +982,75 @@ fn sp_johnson(
 }
 
 
-fn next_to_path(
-    edge: (usize, usize),
-    next: &M2<usize, usize, usize>,
-) -> Vec<usize> {
+/// Johnson potential h(v) = δ(q, v), which q is a special node linked to all
+/// vertexs with zero weight edge.
+///
+/// 等价于以所有点为源点 (初始距离为 0) 的 SPFA, 不必真的添加 q
+///
+/// (g2 (g for reweight), h)
+pub(crate) fn johnson_potential<V: Vertex, W: Weight>(
+    g: &Graph<V, W>,
+) -> Result<(Graph<V, W>, HashMap<V, W>), (Graph<V, W>, Vec<V>)> {
+    let g2 = g.clone();
+
+    let vertexs: Vec<V> = g.vertexs().collect();
+    let n = vertexs.len();
+
+    let mut pre = HashMap::new();
+    let mut dis: HashMap<V, W> =
+        vertexs.iter().map(|v| (*v, W::zero())).collect();
+    let mut cnt: HashMap<V, usize> = HashMap::new(); // 穿过的边数
+
+    let mut vis: HashSet<V> = vertexs.iter().cloned().collect();
+    let mut stack = vertexs;
+
+    let mut c = None;
+
+    'outer: while let Some(u) = stack.pop() {
+        vis.remove(&u);
+
+        for v in get!(g.e => u => vec![]) {
+            // 无向图不存在这条路径
+            if !g.is_dir && getopt!(pre => u) == Some(v) {
+                continue;
+            }
+
+            let dis_u = get!(dis => u);
+
+            if dis_u + get!(g.w => (u, v)) < get!(dis => v) {
+                set!(dis => v => dis_u + get!(g.w => (u, v)));
+                set!(pre => v => u);
+                set!(cnt => v => get!(cnt => u => 0) + 1);
+
+                if get!(cnt => v => 0) >= n {
+                    c = Some(v);
+                    break 'outer;
+                }
+
+                if !vis.contains(&v) {
+                    stack.push(v);
+                    vis.insert(v);
+                }
+            }
+        }
+    }
+
+    if let Some(mut c) = c {
+        for _ in 1..=n {
+            c = get!(pre => c);
+        }
+
+        Err((g2, install_cycle(c, &pre)))
+    } else {
+        Ok((g2, dis))
+    }
+}
+
+
+fn next_to_path<V: Vertex>(
+    edge: (V, V),
+    next: &M2<V, V, V>,
+) -> Vec<V> {
     let (src, dst) = edge;
 
     let mut path = vec![];
@@ -532,7 +1065,12 @@ fn next_to_path(
 }
 
 
-fn install_cycle(c: usize, pre: &HashMap<usize, usize>) -> Vec<usize> {
+fn weight_of<V: Vertex, W: Weight, G: GraphView<V, W>>(g: &G, u: V, v: V) -> W {
+    g.adj(u).find(|(x, _)| *x == v).unwrap().1
+}
+
+
+fn install_cycle<V: Vertex>(c: V, pre: &HashMap<V, V>) -> Vec<V> {
     let mut cycle = vec![c];
     let mut cur = get!(pre => c);
 
@@ -547,11 +1085,11 @@ fn install_cycle(c: usize, pre: &HashMap<usize, usize>) -> Vec<usize> {
 }
 
 /// for pre array
-fn detect_negative_cycle(
+fn detect_negative_cycle<V: Vertex>(
     n: usize,
-    mut cur: usize,
-    pre: &HashMap<usize, usize>,
-) -> Option<Vec<usize>> {
+    mut cur: V,
+    pre: &HashMap<V, V>,
+) -> Option<Vec<V>> {
     let mut i = 0;
 
     let c = loop {
@@ -575,14 +1113,21 @@ fn detect_negative_cycle(
 
 #[cfg(test)]
 mod tests {
-    use common::{min, same};
+    use common::{min, rngs::StdRng, same, SeedableRng};
     use resource_config::RES;
 
     use super::{SPBellmanFord, SPFloyd};
     use crate::{
-        sp::{SPDijkstra, SPJohnson, SPFA},
-        test::{batch_graph, GraphGenOptions},
-        Graph,
+        sp::{
+            SPAStar, SPBiDijkstra, SPDijkstra, SPJohnson, SPYen, SPDAG, SPFA,
+        },
+        test::{
+            batch_graph,
+            model::{gen_gnp, gen_grid, gen_random_dag},
+            path::Path,
+            GraphGenOptions,
+        },
+        Graph, LexPair, OrdF64,
     };
 
 
@@ -616,6 +1161,289 @@ mod tests {
     }
 
 
+    #[test]
+    fn test_sp_generic() {
+        /* string vertex, float weight */
+
+        let g = Graph::from_undirected_iter([
+            ("a", "b", OrdF64(1.5)),
+            ("b", "c", OrdF64(0.25)),
+            ("a", "c", OrdF64(2.0)),
+            ("c", "d", OrdF64(0.5)),
+        ]);
+
+        let sp_dijkstra = SPDijkstra::new(&g, "a");
+        let sp_johnson =
+            SPJohnson::new(&g).map_err(|(_g2, cycle)| cycle).unwrap();
+
+        assert_eq!(sp_dijkstra.query("d"), (OrdF64(2.25), vec!["b", "c", "d"]));
+        assert_eq!(sp_johnson.query("a", "d").0, OrdF64(2.25));
+        assert_eq!(SPFloyd::new(&g).unwrap().query("a", "d").0, OrdF64(2.25));
+
+        /* (hops, cost) lexicographic weight */
+
+        let g = Graph::from_directed_iter([
+            (1, 2, LexPair(1, 5)),
+            (2, 4, LexPair(1, 5)),
+            (1, 3, LexPair(1, 1)),
+            (3, 5, LexPair(1, 1)),
+            (5, 4, LexPair(1, 1)),
+        ]);
+
+        let (w, path) = SPFA::new(&g, 1).unwrap().query(4);
+
+        assert_eq!(w, LexPair(2, 10));
+        assert_eq!(path, vec![2, 4]);
+        assert_eq!(SPBellmanFord::new(&g, 1).unwrap().query(4), (w, path));
+    }
+
+
+    #[test]
+    fn test_sp_p2p_fixeddata() {
+        let g = Graph::from_directed_iter([
+            (1, 2, 7),
+            (1, 3, 9),
+            (1, 6, 14),
+            (2, 3, 10),
+            (2, 4, 15),
+            (3, 4, 11),
+            (3, 6, 2),
+            (4, 5, 6),
+            (6, 5, 9),
+            (7, 1, 1),
+        ]);
+
+        let sp_astar = SPAStar::new(&g, 1, |_, _| 0);
+        let sp_bidijkstra = SPBiDijkstra::new(&g, 1);
+
+        for (dst, res) in [
+            (5, Some((20, vec![3, 6, 5]))),
+            (4, Some((20, vec![3, 4]))),
+            (1, Some((0, vec![]))),
+            (7, None),
+        ] {
+            assert_eq!(sp_astar.query(dst), res);
+            assert_eq!(sp_bidijkstra.query(dst), res);
+        }
+
+        // admissible but inconsistent
+        let h = |v, _dst| if v == 2 { 12 } else { 0 };
+        let sp_astar = SPAStar::new(&g, 1, h);
+
+        assert_eq!(sp_astar.query(5).unwrap().0, 20);
+        assert_eq!(sp_astar.query(4).unwrap().0, 20);
+    }
+
+
+    #[test]
+    fn test_sp_p2p_randomdata() {
+        for g in batch_graph(20, 30, 1..100, &GraphGenOptions::undir_conn())
+            .into_iter()
+            .chain(batch_graph(20, 30, 1..100, &GraphGenOptions::dir_conn()))
+        {
+            for src in g.vertexs() {
+                let sp_dijkstra = SPDijkstra::new(&g, src);
+                let sp_astar = SPAStar::new(&g, src, |_, _| 0);
+                let sp_bidijkstra = SPBiDijkstra::new(&g, src);
+
+                for dst in g.vertexs() {
+                    let expect = sp_dijkstra.spw.get(&dst).cloned();
+                    let res_astar = sp_astar.query(dst);
+                    let res_bidijkstra = sp_bidijkstra.query(dst);
+
+                    assert_eq!(res_astar.as_ref().map(|x| x.0), expect);
+                    assert_eq!(res_bidijkstra.as_ref().map(|x| x.0), expect);
+
+                    for (w, path) in res_astar.into_iter().chain(res_bidijkstra) {
+                        let full_path = [vec![src], path].concat();
+
+                        assert_eq!(Path::new(&g, &full_path).weight(), w);
+                    }
+                }
+            }
+        }
+
+        /* grid with manhattan distance */
+
+        let rng = &mut StdRng::seed_from_u64(0);
+        let (rows, cols) = (12, 15);
+        let g = gen_grid(rows, cols, false, 1..10, rng);
+        let pos = |v: usize| ((v - 1) / cols, (v - 1) % cols);
+        let manhattan = |u, v| {
+            let ((r1, c1), (r2, c2)) = (pos(u), pos(v));
+            (r1.abs_diff(r2) + c1.abs_diff(c2)) as isize
+        };
+
+        for src in [1, 17, rows * cols] {
+            let sp_dijkstra = SPDijkstra::new(&g, src);
+            let sp_astar = SPAStar::new(&g, src, manhattan);
+
+            for dst in g.vertexs() {
+                assert_eq!(
+                    sp_astar.query(dst).unwrap().0,
+                    sp_dijkstra.query(dst).0
+                );
+            }
+        }
+    }
+
+
+    /// [(weight, path without src)], sorted
+    fn all_simple_paths(
+        g: &Graph,
+        src: usize,
+        dst: usize,
+    ) -> Vec<(isize, Vec<usize>)> {
+        fn dfs(
+            g: &Graph,
+            u: usize,
+            dst: usize,
+            path: &mut Vec<usize>,
+            res: &mut Vec<(isize, Vec<usize>)>,
+        ) {
+            if u == dst {
+                let w = Path::new(g, path).weight();
+                res.push((w, path[1..].to_vec()));
+                return;
+            }
+
+            for v in g.e.0.get(&u).cloned().unwrap_or_default() {
+                if !path.contains(&v) {
+                    path.push(v);
+                    dfs(g, v, dst, path, res);
+                    path.pop();
+                }
+            }
+        }
+
+        let mut res = vec![];
+        dfs(g, src, dst, &mut vec![src], &mut res);
+        res.sort();
+        res
+    }
+
+
+    #[test]
+    fn test_sp_kpaths_fixeddata() {
+        let g = Graph::from_directed_iter([
+            ('C', 'D', 3),
+            ('C', 'E', 2),
+            ('D', 'F', 4),
+            ('E', 'D', 1),
+            ('E', 'F', 2),
+            ('E', 'G', 3),
+            ('F', 'G', 2),
+            ('F', 'H', 1),
+            ('G', 'H', 2),
+        ]);
+
+        let sp_yen = SPYen::new(&g, 'C');
+
+        assert_eq!(
+            sp_yen.query('H', 3),
+            vec![
+                (5, vec!['E', 'F', 'H']),
+                (7, vec!['E', 'G', 'H']),
+                (8, vec!['D', 'F', 'H']),
+            ]
+        );
+        assert_eq!(sp_yen.query('H', 100).len(), 7);
+        assert_eq!(sp_yen.query('H', 0), vec![]);
+        assert_eq!(sp_yen.query('C', 2), vec![(0, vec![])]);
+        assert_eq!(SPYen::new(&g, 'H').query('C', 2), vec![]);
+
+        let sp_dag = SPDAG::new(&g, 'C').unwrap();
+
+        assert_eq!(sp_dag.query('H').0, 5);
+        assert_eq!(sp_dag.pres('D').collect::<Vec<_>>(), vec!['C', 'E']);
+        assert_eq!(sp_dag.paths('D'), vec![vec!['D'], vec!['E', 'D']]);
+        assert_eq!(sp_dag.paths('G'), vec![vec!['E', 'G']]);
+
+        // tie
+        let g = Graph::from_undirected_iter([
+            (1, 2, 1),
+            (1, 3, 1),
+            (2, 4, 1),
+            (3, 4, 1),
+            (4, 5, 0),
+        ]);
+        let sp_dag = SPDAG::new(&g, 1).unwrap();
+        let mut paths = sp_dag.paths(5);
+        paths.sort();
+
+        assert_eq!(paths, vec![vec![2, 4, 5], vec![3, 4, 5]]);
+        // 4 <-> 5 both in dag for zero weight
+        assert_eq!(sp_dag.to_graph().edges().count(), 6);
+    }
+
+
+    #[test]
+    fn test_sp_kpaths_randomdata() {
+        let rng = &mut StdRng::seed_from_u64(0);
+
+        for i in 0..60 {
+            let g = match i % 3 {
+                0 => gen_gnp(7, 0.4, true, 0..5, rng),
+                1 => gen_gnp(7, 0.4, false, 1..4, rng),
+                _ => gen_random_dag(8, 3, 0.3, -5..5, rng),
+            };
+
+            if g.edges().count() == 0 {
+                continue;
+            }
+
+            for src in g.vertexs() {
+                let sp_yen = SPYen::new(&g, src);
+                let sp_dag = SPDAG::new(&g, src).unwrap();
+
+                for dst in g.vertexs() {
+                    let expect = all_simple_paths(&g, src, dst);
+
+                    /* sp dag */
+
+                    let mut paths = sp_dag.paths(dst);
+                    paths.sort();
+
+                    let min_w = expect.first().map(|x| x.0);
+                    let shortest = expect
+                        .iter()
+                        .filter(|(w, _)| Some(*w) == min_w)
+                        .map(|(_, path)| path.clone())
+                        .collect::<Vec<_>>();
+
+                    // zero weight edge may leads to non-simple path
+                    if g.w.values().all(|&w| w != 0) {
+                        assert_eq!(paths, shortest);
+                    }
+
+                    if g.w.values().any(|&w| w < 0) {
+                        continue;
+                    }
+
+                    /* yen */
+
+                    let res = sp_yen.query(dst, 1000);
+
+                    assert_eq!(
+                        res.iter().map(|x| x.0).collect::<Vec<_>>(),
+                        expect.iter().map(|x| x.0).collect::<Vec<_>>()
+                    );
+
+                    for (w, path) in res {
+                        let full_path = [vec![src], path].concat();
+
+                        assert_eq!(Path::new(&g, &full_path).weight(), w);
+                    }
+
+                    let k = expect.len() / 2;
+
+                    assert_eq!(sp_yen.query(dst, k).len(), k);
+                }
+            }
+        }
+    }
+
+
     #[test]
     fn test_sp_randomdata_pw() {
         let mut i = 0;
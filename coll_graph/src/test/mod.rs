@@ -1,7 +1,11 @@
-use std::{collections::HashSet, ops::Range};
+use std::{
+    collections::{BTreeSet, HashSet},
+    ops::Range,
+};
 
 use coll::union_find::{MergeBy, UnionFind};
 use common::*;
+use indexmap::IndexSet;
 
 use crate::{
     to_undirected_vec, Graph,
@@ -9,6 +13,7 @@ use crate::{
 
 
 pub mod path;
+pub mod model;
 mod sp;
 mod verify;
 
@@ -81,13 +86,26 @@ impl Graph {
         vrange: usize,
         sparsity: usize,
         wrange: Range<isize>,
+    ) -> Graph {
+        let rng = &mut thread_rng();
+
+        Self::generate_with_rng(opt, vrange, sparsity, wrange, rng)
+    }
+
+    /// Same graph for the same rng state (e.g. `StdRng::seed_from_u64`)
+    pub fn generate_with_rng<R: Rng>(
+        opt: &GraphGenOptions,
+        vrange: usize,
+        sparsity: usize,
+        wrange: Range<isize>,
+        rng: &mut R,
     ) -> Graph {
         debug_assert!(sparsity <= 10);
         let mut g;
 
         if sparsity == 10 {
             assert!(opt.allow_cycle);
-            g = gen_scc_graph(vrange, wrange.clone());
+            g = gen_scc_graph(vrange, wrange.clone(), rng);
             g.is_dir = opt.is_dir;
         } else {
             let mut max = vrange * (vrange - 1);
@@ -108,7 +126,8 @@ impl Graph {
                 dsu.insert(i);
             }
 
-            let mut rem_edges = HashSet::new();
+            // keep insertion order, so that choosing is reproducible
+            let mut rem_edges = IndexSet::new();
 
             for u in 1..=vrange-1 {
                 for v in u+1..=vrange {
@@ -118,27 +137,23 @@ impl Graph {
             }
 
             for _ in 0..edge_limit {
-                let (u, v) = rem_edges
-                    .iter()
-                    .choose(&mut common::thread_rng())
-                    .cloned()
-                    .unwrap();
-                let w = random_range!(wrange.clone());
+                let i = rng.gen_range(0..rem_edges.len());
+                let (u, v) = rem_edges.swap_remove_index(i).unwrap();
+                let w = rng.gen_range(wrange.clone());
 
                 g.insert((u, v), w);
-                rem_edges.remove(&(u, v));
                 dsu.cunion(u, v);
 
                 if !opt.is_dir {
                     g.insert((v, u), w);
-                    rem_edges.remove(&(v, u));
+                    rem_edges.swap_remove(&(v, u));
                 }
             }
 
             if opt.weak_conn {
                 /* ensure weak connected */
 
-                let mut comps = HashSet::new();
+                let mut comps = BTreeSet::new();
 
                 for v in 1..=vrange {
                     comps.insert(dsu.cfind(v));
@@ -150,7 +165,7 @@ impl Graph {
                     let u = comps_iter.next().unwrap();
 
                     for v in comps_iter {
-                        let w = random_range!(wrange.clone());
+                        let w = rng.gen_range(wrange.clone());
 
                         g.insert((u, v), w);
                         dsu.cunion(u, v);
@@ -160,9 +175,9 @@ impl Graph {
                         } else {
                             let w2;
                             if opt.non_negative_cycle && w < 0 {
-                                w2 = w.abs() + random_range!(0..wrange.end);
+                                w2 = w.abs() + rng.gen_range(0..wrange.end);
                             } else {
-                                w2 = random_range!(wrange.clone());
+                                w2 = rng.gen_range(wrange.clone());
                             }
 
                             g.insert((v, u), w2);
@@ -214,12 +229,16 @@ impl Graph {
 //// Functions
 
 /// Generate (Undirected) Simple Completed Connected Graph
-fn gen_scc_graph(vrange: usize, wrange: Range<isize>) -> Graph {
+fn gen_scc_graph<R: Rng>(
+    vrange: usize,
+    wrange: Range<isize>,
+    rng: &mut R,
+) -> Graph {
     let mut edges = vec![];
 
     for u in 1..vrange {
         for v in u + 1..=vrange {
-            let w = random_range!(wrange.clone());
+            let w = rng.gen_range(wrange.clone());
             edges.push((u, v, w));
             edges.push((v, u, w));
         }
@@ -235,13 +254,31 @@ pub fn batch_graph(
     vrange: usize,
     wrange: Range<isize>,
     opt: &GraphGenOptions,
+) -> Vec<Graph> {
+    batch_graph_with_rng(n, vrange, wrange, opt, &mut thread_rng())
+}
+
+
+/// Reproducible `batch_graph`
+pub fn batch_graph_with_rng<R: Rng>(
+    n: usize,
+    vrange: usize,
+    wrange: Range<isize>,
+    opt: &GraphGenOptions,
+    rng: &mut R,
 ) -> Vec<Graph> {
     let mut res = vec![];
 
     for _ in 0..n {
-        let sparsity = random::<usize>() % 10 + 1;
-
-        let g = Graph::generate(opt, vrange, sparsity, wrange.clone());
+        let sparsity = rng.gen_range(1..=10);
+
+        let g = Graph::generate_with_rng(
+            opt,
+            vrange,
+            sparsity,
+            wrange.clone(),
+            rng,
+        );
         res.push(g);
     }
 
@@ -278,6 +315,65 @@ mod tests {
         g.render(&render_opt, &mut dotf).unwrap();
     }
 
+    #[test]
+    fn test_graph_adj() {
+        let g = Graph::from_directed_iter([(3, 1, 2), (1, 2, 1), (1, 3, 4)]);
+
+        assert_eq!(g.e[1], [2, 3]);
+        assert_eq!(g.rev[3], [1]);
+        assert!(g.e[2].is_empty());
+        assert!(g.e[9].is_empty());
+
+        /* as Vec<Vec<usize>> */
+
+        assert_eq!(g.e.len(), 4);
+        assert_eq!(g.rev.len(), 4);
+        assert_eq!(
+            g.e.iter().enumerate().collect::<Vec<_>>(),
+            [(0, &vec![]), (1, &vec![2, 3]), (2, &vec![]), (3, &vec![1])]
+        );
+        assert_eq!(g.e[3].clone(), vec![1]);
+
+        assert_eq!(
+            g.edges().collect::<Vec<_>>(),
+            [(1, 2, 1), (1, 3, 4), (3, 1, 2)]
+        );
+        assert_eq!(g.anypoint(), 1);
+    }
+
+    #[test]
+    fn test_gen_seeded() {
+        use common::rand::{rngs::StdRng, SeedableRng};
+
+        for opt in [GraphGenOptions::undir_conn(), GraphGenOptions::dir_conn()] {
+            let seeded = |seed| {
+                Graph::generate_with_rng(
+                    &opt,
+                    20,
+                    3,
+                    -10..20,
+                    &mut StdRng::seed_from_u64(seed),
+                )
+            };
+
+            assert_eq!(
+                seeded(6).edges().collect_vec(),
+                seeded(6).edges().collect_vec()
+            );
+
+            let batch = |seed| {
+                let rng = &mut StdRng::seed_from_u64(seed);
+
+                batch_graph_with_rng(5, 20, 1..10, &opt, rng)
+                    .into_iter()
+                    .map(|g| g.edges().collect_vec())
+                    .collect_vec()
+            };
+
+            assert_eq!(batch(7), batch(7));
+        }
+    }
+
     #[test]
     fn test_batch_graph() {
         for _g in batch_graph(100, 100, 1..50, &GraphGenOptions::undir_conn())
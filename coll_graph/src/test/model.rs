@@ -0,0 +1,512 @@
+//! Standard random graph models, all reproducible by a seeded rng
+//!
+//! vertex in 1..=n (as `Graph::generate`), weight in wrange.
+//!
+//! `Graph` ignores orphan vertex, so that isolated vertexs just disappear.
+
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, BinaryHeap},
+    iter::repeat,
+    ops::Range,
+};
+
+use common::{Itertools, Rng, SliceRandom};
+
+use crate::Graph;
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Functions
+
+/// Erdős–Rényi G(n, p): each (ordered if is_dir) pair is an edge with
+/// probability p
+pub fn gen_gnp<R: Rng>(
+    n: usize,
+    p: f64,
+    is_dir: bool,
+    wrange: Range<isize>,
+    rng: &mut R,
+) -> Graph {
+    let mut edges = vec![];
+
+    for u in 1..=n {
+        for v in 1..=n {
+            if u == v || !is_dir && u > v {
+                continue;
+            }
+
+            if rng.gen_bool(p) {
+                edges.push((u, v, rng.gen_range(wrange.clone())));
+            }
+        }
+    }
+
+    if is_dir {
+        Graph::from_directed_iter(edges)
+    } else {
+        Graph::from_undirected_iter(edges)
+    }
+}
+
+
+/// Barabási–Albert preferential attachment (undirected)
+///
+/// Start from complete graph of m+1 vertexs, then each new vertex attaches to
+/// m distinct vertexs with probability proportional to their degree.
+pub fn gen_barabasi_albert<R: Rng>(
+    n: usize,
+    m: usize,
+    wrange: Range<isize>,
+    rng: &mut R,
+) -> Graph {
+    assert!(m >= 1 && m < n, "require 1 <= m < n");
+
+    let mut edges = vec![];
+    // each vertex repeats deg times
+    let mut targets = vec![];
+
+    for u in 1..=m + 1 {
+        for v in u + 1..=m + 1 {
+            edges.push((u, v, rng.gen_range(wrange.clone())));
+            targets.extend([u, v]);
+        }
+    }
+
+    for v in m + 2..=n {
+        let mut chosen = BTreeSet::new();
+
+        while chosen.len() < m {
+            chosen.insert(*targets.choose(rng).unwrap());
+        }
+
+        for u in chosen {
+            edges.push((u, v, rng.gen_range(wrange.clone())));
+            targets.extend([u, v]);
+        }
+    }
+
+    Graph::from_undirected_iter(edges)
+}
+
+
+/// Uniform-ish simple d-regular graph (Steger–Wormald pairing)
+///
+/// Pair up the n*d points randomly, and restart when get stuck.
+pub fn gen_random_regular<R: Rng>(
+    n: usize,
+    d: usize,
+    wrange: Range<isize>,
+    rng: &mut R,
+) -> Graph {
+    assert!(d < n, "require d < n");
+    assert!(n * d % 2 == 0, "require n * d be even");
+
+    loop {
+        let mut points = (1..=n)
+            .flat_map(|v| repeat(v).take(d))
+            .collect_vec();
+        let mut edges = BTreeSet::new();
+
+        let suitable = |edges: &BTreeSet<_>, u: usize, v: usize| {
+            u != v && !edges.contains(&(u.min(v), u.max(v)))
+        };
+
+        while !points.is_empty() {
+            let len = points.len();
+            let mut found = None;
+
+            for _ in 0..len {
+                let i = rng.gen_range(0..len);
+                let j = rng.gen_range(0..len);
+
+                if suitable(&edges, points[i], points[j]) {
+                    found = Some((i, j));
+                    break;
+                }
+            }
+
+            if found.is_none() {
+                found = (0..len)
+                    .tuple_combinations()
+                    .find(|&(i, j)| suitable(&edges, points[i], points[j]));
+            }
+
+            let Some((i, j)) = found else {
+                break;
+            };
+
+            let (u, v) = (points[i], points[j]);
+
+            edges.insert((u.min(v), u.max(v)));
+            points.swap_remove(i.max(j));
+            points.swap_remove(i.min(j));
+        }
+
+        if points.is_empty() {
+            return Graph::from_undirected_iter(
+                edges
+                    .into_iter()
+                    .map(|(u, v)| (u, v, rng.gen_range(wrange.clone())))
+                    .collect_vec(),
+            );
+        }
+    }
+}
+
+
+/// rows x cols grid (undirected), vertex of (r, c) is r*cols+c+1
+///
+/// torus wraps around the side longer than 2.
+pub fn gen_grid<R: Rng>(
+    rows: usize,
+    cols: usize,
+    torus: bool,
+    wrange: Range<isize>,
+    rng: &mut R,
+) -> Graph {
+    let id = |r: usize, c: usize| r * cols + c + 1;
+    let mut edges = vec![];
+
+    for r in 0..rows {
+        for c in 0..cols {
+            if c + 1 < cols {
+                edges.push((id(r, c), id(r, c + 1)));
+            } else if torus && cols > 2 {
+                edges.push((id(r, c), id(r, 0)));
+            }
+
+            if r + 1 < rows {
+                edges.push((id(r, c), id(r + 1, c)));
+            } else if torus && rows > 2 {
+                edges.push((id(r, c), id(0, c)));
+            }
+        }
+    }
+
+    Graph::from_undirected_iter(
+        edges
+            .into_iter()
+            .map(|(u, v)| (u, v, rng.gen_range(wrange.clone())))
+            .collect_vec(),
+    )
+}
+
+
+/// Path graph 1 - 2 - ... - n (1 -> 2 -> ... -> n if is_dir),
+/// the deepest case for DFS
+pub fn gen_chain<R: Rng>(
+    n: usize,
+    is_dir: bool,
+    wrange: Range<isize>,
+    rng: &mut R,
+) -> Graph {
+    let edges = (1..n)
+        .map(|u| (u, u + 1, rng.gen_range(wrange.clone())))
+        .collect_vec();
+
+    if is_dir {
+        Graph::from_directed_iter(edges)
+    } else {
+        Graph::from_undirected_iter(edges)
+    }
+}
+
+
+/// Uniform labelled tree of n vertexs (by random Prüfer sequence)
+pub fn gen_random_tree<R: Rng>(
+    n: usize,
+    wrange: Range<isize>,
+    rng: &mut R,
+) -> Graph {
+    let vs = (1..=n).collect_vec();
+
+    Graph::from_undirected_iter(random_tree_edges(&vs, wrange, rng))
+}
+
+
+/// Forest of exactly k trees, each tree has at least 2 vertexs
+pub fn gen_random_forest<R: Rng>(
+    n: usize,
+    k: usize,
+    wrange: Range<isize>,
+    rng: &mut R,
+) -> Graph {
+    assert!(k >= 1 && n >= 2 * k, "require n >= 2k");
+
+    let mut vs = (1..=n).collect_vec();
+    vs.shuffle(rng);
+
+    /* split into k pieces, each has at least 2 */
+
+    let mut sizes = vec![2; k];
+
+    for _ in 0..n - 2 * k {
+        sizes[rng.gen_range(0..k)] += 1;
+    }
+
+    let mut edges = vec![];
+    let mut lo = 0;
+
+    for size in sizes {
+        let piece = &vs[lo..lo + size];
+
+        edges.extend(random_tree_edges(piece, wrange.clone(), rng));
+        lo += size;
+    }
+
+    Graph::from_undirected_iter(edges)
+}
+
+
+/// DAG whose longest path has exactly depth edges
+///
+/// Vertexs are shuffled into depth+1 nonempty layers, each vertex out of the
+/// first layer has an in-edge from the previous layer, and any other forward
+/// (lower layer to higher layer) pair is an edge with probability p.
+pub fn gen_random_dag<R: Rng>(
+    n: usize,
+    depth: usize,
+    p: f64,
+    wrange: Range<isize>,
+    rng: &mut R,
+) -> Graph {
+    assert!(n > depth, "require n > depth");
+
+    let mut vs = (1..=n).collect_vec();
+    vs.shuffle(rng);
+
+    let mut layers = vec![vec![]; depth + 1];
+
+    for (i, v) in vs.into_iter().enumerate() {
+        let l = if i <= depth { i } else { rng.gen_range(0..=depth) };
+
+        layers[l].push(v);
+    }
+
+    let mut edges = BTreeSet::new();
+
+    for l in 1..=depth {
+        for &v in layers[l].iter() {
+            edges.insert((*layers[l - 1].choose(rng).unwrap(), v));
+        }
+    }
+
+    for l1 in 0..depth {
+        for l2 in l1 + 1..=depth {
+            for &u in layers[l1].iter() {
+                for &v in layers[l2].iter() {
+                    if rng.gen_bool(p) {
+                        edges.insert((u, v));
+                    }
+                }
+            }
+        }
+    }
+
+    Graph::from_directed_iter(
+        edges
+            .into_iter()
+            .map(|(u, v)| (u, v, rng.gen_range(wrange.clone())))
+            .collect_vec(),
+    )
+}
+
+
+/// Decode random Prüfer sequence over vs
+fn random_tree_edges<R: Rng>(
+    vs: &[usize],
+    wrange: Range<isize>,
+    rng: &mut R,
+) -> Vec<(usize, usize, isize)> {
+    let n = vs.len();
+
+    if n < 2 {
+        return vec![];
+    }
+
+    let prufer = (0..n - 2).map(|_| rng.gen_range(0..n)).collect_vec();
+    let mut deg = vec![1; n];
+
+    for &i in prufer.iter() {
+        deg[i] += 1;
+    }
+
+    let mut leaves: BinaryHeap<Reverse<usize>> =
+        (0..n).filter(|&i| deg[i] == 1).map(Reverse).collect();
+    let mut edges = vec![];
+
+    for i in prufer {
+        let Reverse(leaf) = leaves.pop().unwrap();
+
+        edges.push((vs[leaf], vs[i], rng.gen_range(wrange.clone())));
+        deg[i] -= 1;
+
+        if deg[i] == 1 {
+            leaves.push(Reverse(i));
+        }
+    }
+
+    let Reverse(u) = leaves.pop().unwrap();
+    let Reverse(v) = leaves.pop().unwrap();
+
+    edges.push((vs[u], vs[v], rng.gen_range(wrange)));
+
+    edges
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use common::rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::toposort::toposort_tarjan;
+
+
+    fn degs(g: &Graph) -> HashMap<usize, usize> {
+        g.vertexs().map(|u| (u, g.e.0[&u].len())).collect()
+    }
+
+    /// no loop, no multiple edge, undirected edge in pair
+    fn assert_simple(g: &Graph) {
+        let edges = g.edges().collect_vec();
+
+        assert!(edges.iter().all(|(u, v, _)| u != v));
+        assert!(edges.iter().map(|(u, v, _)| (u, v)).all_unique());
+
+        if !g.is_dir {
+            assert!(edges.iter().all(|&(u, v, w)| g.w[&(v, u)] == w));
+        }
+    }
+
+    /// count of vertexs on the longest path
+    fn longest_path(g: &Graph) -> usize {
+        let mut dp = HashMap::new();
+
+        for u in toposort_tarjan(g).unwrap() {
+            let d = *dp.entry(u).or_insert(1);
+
+            for v in g.e.0.get(&u).into_iter().flatten() {
+                let dv = dp.entry(*v).or_insert(1);
+                *dv = (*dv).max(d + 1);
+            }
+        }
+
+        dp.into_values().max().unwrap_or(0)
+    }
+
+    #[test]
+    fn test_gen_models() {
+        let rng = &mut StdRng::seed_from_u64(10);
+
+        /* G(n, p) */
+
+        assert_eq!(gen_gnp(10, 0.0, false, 1..2, rng).edges().count(), 0);
+        assert_eq!(gen_gnp(10, 1.0, false, 1..2, rng).edges().count(), 90);
+        assert_eq!(gen_gnp(10, 1.0, true, 1..2, rng).edges().count(), 90);
+
+        let g = gen_gnp(30, 0.3, true, 1..10, rng);
+        assert!(g.is_dir);
+        assert_simple(&g);
+
+        /* BA */
+
+        for (n, m) in [(30, 1), (30, 3), (5, 4)] {
+            let g = gen_barabasi_albert(n, m, 1..10, rng);
+
+            assert_simple(&g);
+            assert!(g.is_connected());
+            assert_eq!(
+                g.edges().count(),
+                2 * (m * (m + 1) / 2 + (n - m - 1) * m)
+            );
+        }
+
+        /* regular */
+
+        for (n, d) in [(10, 3), (30, 4), (7, 6), (20, 1)] {
+            let g = gen_random_regular(n, d, 1..10, rng);
+
+            assert_simple(&g);
+            assert_eq!(g.vertexs().count(), n);
+            assert!(degs(&g).into_values().all(|x| x == d));
+        }
+
+        /* grid / torus */
+
+        let g = gen_grid(3, 4, false, 1..10, rng);
+        assert_eq!(g.edges().count(), 2 * (3 * 3 + 2 * 4));
+        assert!(g.is_connected());
+
+        let g = gen_grid(3, 4, true, 1..10, rng);
+        assert_simple(&g);
+        assert!(degs(&g).into_values().all(|x| x == 4));
+
+        let g = gen_grid(2, 5, true, 1..10, rng);
+        assert_simple(&g);
+        assert!(degs(&g).into_values().all(|x| x == 3));
+
+        /* chain */
+
+        let g = gen_chain(10, true, 1..10, rng);
+        assert_eq!(g.edges().count(), 9);
+        assert_eq!(longest_path(&g), 10);
+
+        let g = gen_chain(10, false, 1..10, rng);
+        assert_simple(&g);
+        assert!(g.is_connected());
+
+        /* tree / forest */
+
+        for n in [2, 3, 10, 50] {
+            let g = gen_random_tree(n, 1..10, rng);
+
+            assert_eq!(g.vertexs().count(), n);
+            assert_eq!(g.edges().count(), 2 * (n - 1));
+            assert!(g.is_connected());
+        }
+
+        for (n, k) in [(2, 1), (20, 3), (20, 10)] {
+            let g = gen_random_forest(n, k, 1..10, rng);
+
+            assert_eq!(g.vertexs().count(), n);
+            assert_eq!(g.edges().count(), 2 * (n - k));
+            assert_eq!(g.components().len(), k);
+        }
+
+        /* dag */
+
+        for (n, depth, p) in [(10, 9, 0.5), (30, 4, 0.1), (30, 0, 1.0)] {
+            let g = gen_random_dag(n, depth, p, 1..10, rng);
+
+            if depth > 0 {
+                assert_eq!(g.vertexs().count(), n);
+                assert_eq!(longest_path(&g), depth + 1);
+            } else {
+                assert_eq!(g.edges().count(), 0);
+            }
+        }
+
+        /* reproducible */
+
+        let seeded = |seed| {
+            let rng = &mut StdRng::seed_from_u64(seed);
+
+            [
+                gen_gnp(20, 0.2, false, 1..10, rng),
+                gen_barabasi_albert(20, 2, 1..10, rng),
+                gen_random_regular(20, 3, 1..10, rng),
+                gen_random_forest(20, 3, 1..10, rng),
+                gen_random_dag(20, 5, 0.2, 1..10, rng),
+            ]
+            .map(|g| g.edges().collect_vec())
+        };
+
+        assert_eq!(seeded(3), seeded(3));
+    }
+}
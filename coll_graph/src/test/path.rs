@@ -2,71 +2,71 @@ use std::{iter::once, collections::HashMap};
 
 use coll::get;
 
-use crate::{Graph, sp};
+use crate::{Graph, Vertex, Weight, sp};
 
 
 ////////////////////////////////////////////////////////////////////////////////
 //// Structures
 
 
-pub struct Path<'a> {
-    g: &'a Graph,
-    path: Vec<usize>,
+pub struct Path<'a, V = usize, W = isize> {
+    g: &'a Graph<V, W>,
+    path: Vec<V>,
 }
 
 
 /// Freezed path
-pub struct FPath {
-    weight: isize,
-    path: Vec<(usize, usize, isize)>,
+pub struct FPath<V = usize, W = isize> {
+    weight: W,
+    path: Vec<(V, V, W)>,
 }
 
 
 ////////////////////////////////////////////////////////////////////////////////
 //// Implementations
 
-impl FPath {
+impl<V: Vertex, W: Weight> FPath<V, W> {
     pub fn iter<'a>(
         &'a self,
-    ) -> impl Iterator<Item = (usize, usize, isize)> + 'a {
+    ) -> impl Iterator<Item = (V, V, W)> + 'a {
         self.path.iter().cloned()
     }
 
-    pub const fn weight(&self) -> isize {
+    pub const fn weight(&self) -> W {
         self.weight
     }
 }
 
 
-impl<'a> Path<'a> {
-    pub fn new(g: &'a Graph, path: &[usize]) -> Self {
+impl<'a, V: Vertex, W: Weight> Path<'a, V, W> {
+    pub fn new(g: &'a Graph<V, W>, path: &[V]) -> Self {
         Self {
             g,
             path: path.into_iter().cloned().collect(),
         }
     }
 
-    pub fn from_cycle(g: &'a Graph, cycle: &[usize]) -> Self {
+    pub fn from_cycle(g: &'a Graph<V, W>, cycle: &[V]) -> Self {
         Self {
             g,
             path: cycle.into_iter().cloned().chain(once(cycle[0])).collect(),
         }
     }
 
-    pub fn from_pre(g: &'a Graph, dst: usize, pre: &HashMap<usize, usize>) -> Self {
+    pub fn from_pre(g: &'a Graph<V, W>, dst: V, pre: &HashMap<V, V>) -> Self {
         let path = sp::pre_to_path!(dst, pre);
 
         Self { g, path }
     }
 
     /// Empty path weight is zero
-    pub fn weight(&self) -> isize {
-        self.iter().map(|x| x.2).sum::<isize>()
+    pub fn weight(&self) -> W {
+        self.iter().fold(W::zero(), |acc, x| acc + x.2)
     }
 
     pub fn iter<'b>(
         &'b self,
-    ) -> impl Iterator<Item = (usize, usize, isize)> + 'b {
+    ) -> impl Iterator<Item = (V, V, W)> + 'b {
         std::iter::from_coroutine(#[coroutine] || {
             let mut cur = self.path[0];
 
@@ -77,7 +77,7 @@ impl<'a> Path<'a> {
         })
     }
 
-    pub fn freeze(&self) -> FPath {
+    pub fn freeze(&self) -> FPath<V, W> {
         FPath {
             weight: self.weight(),
             path: self.iter().collect(),
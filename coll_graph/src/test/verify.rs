@@ -1,25 +1,26 @@
-use std::{collections::HashMap, iter::once};
+use std::{collections::{HashMap, HashSet}, iter::once};
 
 use coll::{aux::{VerifyResult, VerifyError}, get};
+use common::Itertools;
 
-use crate::Graph;
+use crate::{sp::johnson_potential, Graph, Vertex, Weight};
 
 
 
-impl Graph {
+impl<V: Vertex, W: Weight> Graph<V, W> {
     ////////////////////////////////////////////////////////////////////////////
     /// Verify
 
     /// verify spanning tree
-    pub fn verify_st(&self, st: &[(usize, usize)]) -> VerifyResult {
+    pub fn verify_st(&self, st: &[(V, V)]) -> VerifyResult {
         let mut vertx = self
             .vertexs()
             .map(|v| (v, ()))
-            .collect::<HashMap<usize, ()>>();
+            .collect::<HashMap<V, ()>>();
 
         for (u, v) in st {
             if !self.contains_edge((*u, *v)) {
-                return Err(VerifyError::Inv(format!("No edge {u}->{v}")));
+                return Err(VerifyError::Inv(format!("No edge {u:?}->{v:?}")));
             }
 
             vertx.remove(u);
@@ -37,17 +38,19 @@ impl Graph {
     /// verify minimal spanning tree
     pub fn verify_mst(
         &self,
-        min: isize,
-        st: &[(usize, usize)],
+        min: W,
+        st: &[(V, V)],
     ) -> VerifyResult {
         self.verify_st(st)?;
 
-        let tot: isize = st.into_iter().map(|x| get!(self.w => x)).sum();
+        let tot = st
+            .into_iter()
+            .fold(W::zero(), |acc, x| acc + get!(self.w => x));
 
         if tot == min {
             Ok(())
         } else if tot < min {
-            Err(VerifyError::Inv(format!("cur_tot: {tot} < min: {min}")))
+            Err(VerifyError::Inv(format!("cur_tot: {tot:?} < min: {min:?}")))
         } else {
             Err(VerifyError::Fail(format!("too big")))
         }
@@ -55,9 +58,9 @@ impl Graph {
 
     pub fn verify_path(
         &self,
-        src: usize,
-        dst: usize,
-        path: &[usize],
+        src: V,
+        dst: V,
+        path: &[V],
     ) -> VerifyResult {
         let mut u = src;
 
@@ -66,7 +69,7 @@ impl Graph {
                 u = *v;
             } else {
                 return Err(VerifyError::Inv(format!(
-                    "No edge {u}-{v} for {path:?} src: {src} dst: {dst}"
+                    "No edge {u:?}-{v:?} for {path:?} src: {src:?} dst: {dst:?}"
                 )));
             }
         }
@@ -74,11 +77,11 @@ impl Graph {
         if u == dst {
             Ok(())
         } else {
-            Err(VerifyError::Inv(format!("Not end in {dst}, found {u}")))
+            Err(VerifyError::Inv(format!("Not end in {dst:?}, found {u:?}")))
         }
     }
 
-    pub fn verify_cycle(&self, cycle: &[usize]) -> VerifyResult {
+    pub fn verify_cycle(&self, cycle: &[V]) -> VerifyResult {
         if cycle.len() == 0 {
             return Err(VerifyError::Inv(format!("Empty negative cycle")));
         }
@@ -97,29 +100,299 @@ impl Graph {
         if self.contains_edge((dst, src)) {
             Ok(())
         } else {
-            Err(VerifyError::Fail(format!("{dst} can't goback to {src}")))
+            Err(VerifyError::Fail(format!("{dst:?} can't goback to {src:?}")))
         }
     }
 
     pub fn verify_negative_cycle(
         &self,
-        cycle: &[usize],
+        cycle: &[V],
     ) -> VerifyResult {
         self.verify_cycle(cycle)?;
 
         /* verify negative weights sumeration */
 
-        let sumw: isize = cycle
+        let sumw = cycle
             .iter()
             .cloned()
             .zip(cycle[1..].iter().cloned().chain(once(cycle[0])))
-            .map(|(u, v)| get!(self.w => (u, v)))
+            .fold(W::zero(), |acc, (u, v)| acc + get!(self.w => (u, v)));
+
+        if sumw < W::zero() {
+            Ok(())
+        } else {
+            Err(VerifyError::Fail(format!("weight sum: {sumw:?} >= 0, for cycle {cycle:?}")))
+        }
+    }
+
+}
+
+
+impl Graph {
+    /// verify feasible flow: capacity constraint and flow conservation
+    pub fn verify_flow(
+        &self,
+        src: usize,
+        sink: usize,
+        value: isize,
+        flow: &HashMap<(usize, usize), isize>,
+    ) -> VerifyResult {
+        let mut excess = HashMap::<usize, isize>::new();
+
+        for (&(u, v), &f) in flow.iter() {
+            if !self.contains_edge((u, v)) {
+                return Err(VerifyError::Inv(format!("No edge {u}->{v}")));
+            }
+
+            let cap = get!(self.w => (u, v));
+
+            if f < 0 || f > cap {
+                return Err(VerifyError::Inv(format!(
+                    "flow {f} out of capacity {cap} for {u}->{v}"
+                )));
+            }
+
+            *excess.entry(u).or_default() -= f;
+            *excess.entry(v).or_default() += f;
+        }
+
+        for (v, ex) in excess {
+            if v == src || v == sink {
+                continue;
+            }
+
+            if ex != 0 {
+                return Err(VerifyError::Fail(format!(
+                    "flow isn't conserved on {v}, excess: {ex}"
+                )));
+            }
+        }
+
+        let out: isize = flow
+            .iter()
+            .filter(|((u, _), _)| *u == src)
+            .map(|(_, f)| *f)
+            .sum();
+        let into: isize = flow
+            .iter()
+            .filter(|((_, v), _)| *v == src)
+            .map(|(_, f)| *f)
             .sum();
 
-        if sumw < 0 {
+        if out - into == value {
+            Ok(())
+        } else {
+            Err(VerifyError::Fail(format!(
+                "flow value {value} != net out flow of src {}", out - into
+            )))
+        }
+    }
+
+    /// verify maximum flow with the min-cut S side (max-flow min-cut theorem)
+    pub fn verify_max_flow(
+        &self,
+        src: usize,
+        sink: usize,
+        value: isize,
+        flow: &HashMap<(usize, usize), isize>,
+        s_side: &[usize],
+    ) -> VerifyResult {
+        self.verify_flow(src, sink, value, flow)?;
+
+        let s_side: HashSet<usize> = s_side.iter().cloned().collect();
+
+        if !s_side.contains(&src) || s_side.contains(&sink) {
+            return Err(VerifyError::Inv(format!(
+                "Invalid s-t cut, src: {src} sink: {sink}, S: {s_side:?}"
+            )));
+        }
+
+        let cut: isize = self
+            .edges()
+            .unique()
+            .filter(|(u, v, _)| s_side.contains(u) && !s_side.contains(v))
+            .map(|(_, _, w)| w)
+            .sum();
+
+        if cut == value {
+            Ok(())
+        } else if cut < value {
+            Err(VerifyError::Inv(format!("cut: {cut} < flow: {value}")))
+        } else {
+            Err(VerifyError::Fail(format!("cut: {cut} > flow: {value}")))
+        }
+    }
+
+    /// verify min cost flow: no negative cycle on the residual network
+    pub fn verify_min_cost_flow(
+        &self,
+        cost_g: &Graph,
+        src: usize,
+        sink: usize,
+        value: isize,
+        cost: isize,
+        flow: &HashMap<(usize, usize), isize>,
+    ) -> VerifyResult {
+        self.verify_flow(src, sink, value, flow)?;
+
+        let tot: isize =
+            flow.iter().map(|(e, f)| f * get!(cost_g.w => e)).sum();
+
+        if tot != cost {
+            return Err(VerifyError::Inv(format!(
+                "cost: {cost} != flow cost: {tot}"
+            )));
+        }
+
+        /* build residual network with cost */
+
+        let mut residual = HashMap::new();
+
+        for (u, v, w) in self.edges().unique() {
+            let f = get!(flow => (u, v) => 0);
+            let c = get!(cost_g.w => (u, v));
+
+            let mut add_arc = |e: (usize, usize), c: isize| {
+                let old = residual.entry(e).or_insert(c);
+
+                if c < *old {
+                    *old = c;
+                }
+            };
+
+            if w > f {
+                add_arc((u, v), c);
+            }
+
+            if f > 0 {
+                add_arc((v, u), -c);
+            }
+        }
+
+        if residual.is_empty() {
+            return Ok(());
+        }
+
+        let residual_g = Graph::from_directed_iter(
+            residual.into_iter().map(|((u, v), c)| (u, v, c)),
+        );
+
+        if let Err((_g2, cycle)) = johnson_potential(&residual_g) {
+            Err(VerifyError::Fail(format!(
+                "negative cycle {cycle:?} on residual network"
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// verify bipartite partition (left, right)
+    pub fn verify_bipartite(&self, left: &[usize], right: &[usize]) -> VerifyResult {
+        let left: HashSet<usize> = left.iter().cloned().collect();
+        let right: HashSet<usize> = right.iter().cloned().collect();
+
+        if !left.is_disjoint(&right) {
+            return Err(VerifyError::Inv(format!(
+                "Overlapped left and right: {:?}",
+                left.intersection(&right).collect::<Vec<_>>()
+            )));
+        }
+
+        let vertexs: HashSet<usize> = self.vertexs().collect();
+
+        if vertexs != left.union(&right).cloned().collect() {
+            return Err(VerifyError::Fail(format!(
+                "Unmatched vertexs set"
+            )));
+        }
+
+        for (u, v, _) in self.edges() {
+            if left.contains(&u) == left.contains(&v) {
+                return Err(VerifyError::Fail(format!(
+                    "edge {u}-{v} in the same side"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn verify_odd_cycle(&self, cycle: &[usize]) -> VerifyResult {
+        self.verify_cycle(cycle)?;
+
+        if cycle.len() % 2 == 1 {
             Ok(())
         } else {
-            Err(VerifyError::Fail(format!("weight sum: {sumw} >= 0, for cycle {cycle:?}")))
+            Err(VerifyError::Fail(format!("even cycle {cycle:?}")))
+        }
+    }
+
+    pub fn verify_matching(&self, matching: &[(usize, usize)]) -> VerifyResult {
+        let mut matched = HashSet::new();
+
+        for (u, v) in matching.iter().cloned() {
+            if !self.contains_edge((u, v)) {
+                return Err(VerifyError::Inv(format!("No edge {u}-{v}")));
+            }
+
+            for x in [u, v] {
+                if !matched.insert(x) {
+                    return Err(VerifyError::Fail(format!(
+                        "{x} matched more than once"
+                    )));
+                }
+            }
         }
+
+        Ok(())
+    }
+
+    pub fn verify_vertex_cover(&self, cover: &[usize]) -> VerifyResult {
+        let cover: HashSet<usize> = cover.iter().cloned().collect();
+
+        for (u, v, _) in self.edges() {
+            if !cover.contains(&u) && !cover.contains(&v) {
+                return Err(VerifyError::Fail(format!(
+                    "uncovered edge {u}-{v}"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// verify maximum matching with a vertex cover of the same size
+    /// (König's theorem, for bipartite graph)
+    pub fn verify_max_matching(
+        &self,
+        matching: &[(usize, usize)],
+        cover: &[usize],
+    ) -> VerifyResult {
+        self.verify_matching(matching)?;
+        self.verify_vertex_cover(cover)?;
+
+        if matching.len() == cover.len() {
+            Ok(())
+        } else {
+            Err(VerifyError::Fail(format!(
+                "matching: {} != cover: {}",
+                matching.len(),
+                cover.len()
+            )))
+        }
+    }
+
+    pub fn verify_independent_set(&self, set: &[usize]) -> VerifyResult {
+        let set: HashSet<usize> = set.iter().cloned().collect();
+
+        for (u, v, _) in self.edges() {
+            if set.contains(&u) && set.contains(&v) {
+                return Err(VerifyError::Fail(format!(
+                    "adjacent vertexs {u}-{v} in independent set"
+                )));
+            }
+        }
+
+        Ok(())
     }
 }
@@ -1,14 +1,34 @@
 //! Can used for detect ring
 //!
 
-use coll::get;
+use std::collections::{BTreeSet, HashMap, HashSet};
 
-use crate::Graph;
+use coll::{apush, easycoll::MV, get, set};
 
+use crate::{scc::scc_tarjan, Graph, GraphView, Vertex, Weight};
 
-impl Graph {
+
+////////////////////////////////////////////////////////////////////////////////
+//// Structures
+
+/// Online topological order maintenance (Pearce-Kelly)
+///
+/// Insert edge one by one, only the affected region between the two
+/// endpoints is reordered, insertion closing a cycle is rejected.
+#[derive(Debug, Clone)]
+pub struct ToposortPK<V = usize> {
+    e: MV<V, V>,
+    rev: MV<V, V>,
+    /// topological index
+    ord: HashMap<V, usize>,
+    /// rev of ord
+    rk: Vec<V>,
+}
+
+
+impl<V: Vertex, W: Weight> Graph<V, W> {
     #[cfg(test)]
-    fn verify_toposort(&self, res: Option<Vec<usize>>) {
+    fn verify_toposort(&self, res: Option<Vec<V>>) {
         debug_assert!(self.is_dir);
 
         if self.components().into_iter().any(|comp| comp.len() > 1) {
@@ -21,7 +41,7 @@ impl Graph {
         for u in res.unwrap() {
             for p in get!(self.rev => u => vec![]) {
                 if !cond.contains(&p) {
-                    panic!("Lacking cond {p} for {u}");
+                    panic!("Lacking cond {p:?} for {u:?}");
                 }
             }
 
@@ -32,7 +52,253 @@ impl Graph {
 
 
 
-pub fn toposort_tarjan(g: &Graph) -> Result<Vec<usize>, (usize, usize)> {
+////////////////////////////////////////////////////////////////////////////////
+//// Implementations
+
+impl<V: Vertex> ToposortPK<V> {
+    pub fn new() -> Self {
+        Self {
+            e: MV::new(),
+            rev: MV::new(),
+            ord: HashMap::new(),
+            rk: vec![],
+        }
+    }
+
+    pub fn contains(&self, v: V) -> bool {
+        self.ord.contains_key(&v)
+    }
+
+    pub fn contains_edge(&self, u: V, v: V) -> bool {
+        self.e.0.get(&u).is_some_and(|tos| tos.contains(&v))
+    }
+
+    /// Insert vertex at the end of order, return false if it exists
+    pub fn insert_vertex(&mut self, v: V) -> bool {
+        if self.contains(v) {
+            return false;
+        }
+
+        set!(self.ord => v => self.rk.len());
+        self.rk.push(v);
+
+        true
+    }
+
+    /// Return the cycle (u, v, ..., u) if edge u->v closes a cycle,
+    /// and the edge isn't inserted.
+    pub fn insert_edge(&mut self, u: V, v: V) -> Result<(), Vec<V>> {
+        self.insert_vertex(u);
+        self.insert_vertex(v);
+
+        if u == v {
+            return Err(vec![u, u]);
+        }
+
+        if self.contains_edge(u, v) {
+            return Ok(());
+        }
+
+        let lb = get!(self.ord => v);
+        let ub = get!(self.ord => u);
+
+        if lb < ub {
+            /* discovery: forward from v, backward from u in [lb, ub] */
+
+            let mut pre = HashMap::new();
+            let mut delta_f = vec![v];
+            let mut stack = vec![v];
+
+            pre.insert(v, v);
+
+            while let Some(x) = stack.pop() {
+                for y in get!(self.e => x => vec![]) {
+                    if y == u {
+                        let mut path = vec![u];
+                        let mut z = x;
+
+                        while z != v {
+                            path.push(z);
+                            z = get!(pre => z);
+                        }
+
+                        path.push(v);
+                        path.push(u);
+                        path.reverse();
+
+                        return Err(path);
+                    }
+
+                    if !pre.contains_key(&y) && get!(self.ord => y) < ub {
+                        pre.insert(y, x);
+                        delta_f.push(y);
+                        stack.push(y);
+                    }
+                }
+            }
+
+            let mut visited = HashSet::from([u]);
+            let mut delta_b = vec![u];
+            let mut stack = vec![u];
+
+            while let Some(x) = stack.pop() {
+                for y in get!(self.rev => x => vec![]) {
+                    if !visited.contains(&y) && get!(self.ord => y) > lb {
+                        visited.insert(y);
+                        delta_b.push(y);
+                        stack.push(y);
+                    }
+                }
+            }
+
+            /* reassign: delta_b before delta_f, reuse their slots */
+
+            delta_f.sort_unstable_by_key(|x| get!(self.ord => x));
+            delta_b.sort_unstable_by_key(|x| get!(self.ord => x));
+
+            let mut slots = delta_b
+                .iter()
+                .chain(delta_f.iter())
+                .map(|x| get!(self.ord => x))
+                .collect::<Vec<usize>>();
+
+            slots.sort_unstable();
+
+            for (x, i) in delta_b.into_iter().chain(delta_f).zip(slots) {
+                set!(self.ord => x => i);
+                self.rk[i] = x;
+            }
+        }
+
+        apush!(self.e => u => v);
+        apush!(self.rev => v => u);
+
+        Ok(())
+    }
+
+    /// Order keeps valid, return false if there is no such edge
+    pub fn remove_edge(&mut self, u: V, v: V) -> bool {
+        if !self.contains_edge(u, v) {
+            return false;
+        }
+
+        self.e.0.get_mut(&u).unwrap().retain(|&x| x != v);
+        self.rev.0.get_mut(&v).unwrap().retain(|&x| x != u);
+
+        true
+    }
+
+    pub fn ord(&self, v: V) -> Option<usize> {
+        self.ord.get(&v).cloned()
+    }
+
+    /// Vertexs in topological order
+    pub fn order(&self) -> impl Iterator<Item = V> + '_ {
+        self.rk.iter().cloned()
+    }
+}
+
+
+impl<V: Vertex> Default for ToposortPK<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Functions
+
+/// Kahn (BFS), return vertexs on cycles (sorted) if failed
+pub fn toposort_kahn<V: Vertex, W: Weight, G: GraphView<V, W>>(
+    g: &G,
+) -> Result<Vec<V>, Vec<V>> {
+    kahn(g, false)
+}
+
+
+/// Lexicographically smallest toposort (Kahn with min-heap),
+/// return vertexs on cycles (sorted) if failed
+pub fn toposort_lex<V: Vertex, W: Weight, G: GraphView<V, W>>(
+    g: &G,
+) -> Result<Vec<V>, Vec<V>> {
+    kahn(g, true)
+}
+
+
+fn kahn<V: Vertex, W: Weight, G: GraphView<V, W>>(
+    g: &G,
+    lex: bool,
+) -> Result<Vec<V>, Vec<V>> {
+    let mut indeg: HashMap<V, usize> =
+        g.vertexs().map(|v| (v, 0)).collect();
+
+    for (_, v, _) in g.edges() {
+        *indeg.get_mut(&v).unwrap() += 1;
+    }
+
+    let mut ans = Vec::with_capacity(indeg.len());
+
+    // stack or min-heap
+    let mut stack = vec![];
+    let mut heap = BTreeSet::new();
+
+    for v in g.vertexs() {
+        if get!(indeg => v) == 0 {
+            if lex {
+                heap.insert(v);
+            } else {
+                stack.push(v);
+            }
+        }
+    }
+
+    while let Some(u) = if lex { heap.pop_first() } else { stack.pop() } {
+        ans.push(u);
+
+        for (v, _) in g.adj(u) {
+            let d = indeg.get_mut(&v).unwrap();
+            *d -= 1;
+
+            if *d == 0 {
+                if lex {
+                    heap.insert(v);
+                } else {
+                    stack.push(v);
+                }
+            }
+        }
+    }
+
+    if ans.len() == indeg.len() {
+        return Ok(ans);
+    }
+
+    /* left vertexs are on cycles or reachable from cycles */
+
+    let left = Graph::from_directed_iter(
+        g.edges()
+            .filter(|(u, v, _)| get!(indeg => u) > 0 && get!(indeg => v) > 0),
+    );
+
+    let mut cycles = scc_tarjan(&left)
+        .into_iter()
+        .filter(|comp| {
+            comp.len() > 1 || left.contains_edge((comp[0], comp[0]))
+        })
+        .flatten()
+        .collect::<Vec<V>>();
+
+    cycles.sort_unstable();
+
+    Err(cycles)
+}
+
+
+pub fn toposort_tarjan<V: Vertex, W: Weight, G: GraphView<V, W>>(
+    g: &G,
+) -> Result<Vec<V>, (V, V)> {
     #[repr(u8)]
     #[derive(Default, Clone, Copy, PartialEq, Eq)]
     enum DFSMeta {
@@ -43,16 +309,16 @@ pub fn toposort_tarjan(g: &Graph) -> Result<Vec<usize>, (usize, usize)> {
     }
     use DFSMeta::*;
 
-    fn dfs(
-        g: &Graph,
-        u: usize,
-        vertexs: &mut Vec<DFSMeta>,
-        ans: &mut Vec<usize>,
-    ) -> Result<(), (usize, usize)> {
-        vertexs[u] = Yellow;
+    fn dfs<V: Vertex, W: Weight, G: GraphView<V, W>>(
+        g: &G,
+        u: V,
+        vertexs: &mut HashMap<V, DFSMeta>,
+        ans: &mut Vec<V>,
+    ) -> Result<(), (V, V)> {
+        set!(vertexs => u => Yellow);
 
-        for v in get!(g.e => u => vec![]) {
-            match vertexs[v] {
+        for (v, _) in g.adj(u) {
+            match get!(vertexs => v => Green) {
                 Green => {
                     dfs(g, v, vertexs, ans)?;
                 }
@@ -63,7 +329,7 @@ pub fn toposort_tarjan(g: &Graph) -> Result<Vec<usize>, (usize, usize)> {
             }
         }
 
-        vertexs[u] = Red;
+        set!(vertexs => u => Red);
         ans.push(u);
 
         Ok(())
@@ -71,12 +337,10 @@ pub fn toposort_tarjan(g: &Graph) -> Result<Vec<usize>, (usize, usize)> {
 
     let mut ans = Vec::new();
 
-    let max_v = g.vertexs().max().unwrap();
-
-    let mut vertexs = vec![DFSMeta::default(); max_v + 1];
+    let mut vertexs = HashMap::new();
 
     for u in g.vertexs() {
-        if vertexs[u] == Green {
+        if get!(vertexs => u => Green) == Green {
             dfs(g, u, &mut vertexs, &mut ans)?;
         }
     }
@@ -87,10 +351,63 @@ pub fn toposort_tarjan(g: &Graph) -> Result<Vec<usize>, (usize, usize)> {
 }
 
 
+/// Non-recursive version of `toposort_tarjan` (explicit stack), same output
+pub fn toposort_tarjan_nr<V: Vertex, W: Weight, G: GraphView<V, W>>(
+    g: &G,
+) -> Result<Vec<V>, (V, V)> {
+    let mut ans = Vec::new();
+
+    // visited vertex => finished (Red) or not (Yellow)
+    let mut vertexs: HashMap<V, bool> = HashMap::new();
+
+    for s in g.vertexs() {
+        if vertexs.contains_key(&s) {
+            continue;
+        }
+
+        set!(vertexs => s => false);
+
+        // (u, rest adjacent vertexs of u)
+        let mut frames = vec![(s, g.adj(s))];
+
+        while let Some((u, tos)) = frames.last_mut() {
+            let u = *u;
+
+            if let Some((v, _)) = tos.next() {
+                match vertexs.get(&v) {
+                    None => {
+                        set!(vertexs => v => false);
+                        frames.push((v, g.adj(v)));
+                    }
+                    Some(false) => {
+                        return Err((u, v)); // found edge on cycle
+                    }
+                    Some(true) => (), // just skip
+                }
+
+                continue;
+            }
+
+            frames.pop();
+
+            set!(vertexs => u => true);
+            ans.push(u);
+        }
+    }
+
+    ans.reverse();
+
+    Ok(ans)
+}
+
+
 
 #[cfg(test)]
 mod tests {
-    use crate::{test::GraphGenOptions, toposort::toposort_tarjan, Graph};
+    use common::{random_range, thread_rng, Itertools};
+
+    use super::*;
+    use crate::test::{model::gen_chain, GraphGenOptions};
 
     fn setup_dir_data() -> Vec<Graph> {
         let opt = GraphGenOptions {
@@ -114,8 +431,12 @@ mod tests {
     #[test]
     fn test_toposort() {
         for (i, g) in setup_dir_data().into_iter().enumerate() {
-            // let topo_kahn = toposort_kahn(&g);
-            let topo_dfs = toposort_tarjan(&g).ok();
+            let topo_kahn = toposort_kahn(&g);
+            let topo_dfs = toposort_tarjan(&g);
+
+            assert_eq!(topo_dfs, toposort_tarjan_nr(&g));
+
+            let topo_dfs = topo_dfs.ok();
 
             if topo_dfs.is_none() {
                 println!("... g-{i:02}");
@@ -123,8 +444,137 @@ mod tests {
                 println!(">>> g-{i:02}");
             }
 
-            // g.verify_toposort(topo_kahn);
+            /* vertexs on cycles are exactly these in non-trivial scc */
+
+            let on_cycles = g
+                .components()
+                .into_iter()
+                .filter(|comp| comp.len() > 1)
+                .flatten()
+                .sorted()
+                .collect_vec();
+
+            match &topo_kahn {
+                Ok(_) => assert!(on_cycles.is_empty()),
+                Err(cycles) => assert_eq!(cycles, &on_cycles),
+            }
+
+            g.verify_toposort(topo_kahn.ok());
+            g.verify_toposort(toposort_lex(&g).ok());
             g.verify_toposort(topo_dfs);
         }
     }
+
+    #[test]
+    fn test_toposort_nr_chain() {
+        let n = 1_000_000;
+        let g = gen_chain(n, true, 1..2, &mut thread_rng());
+
+        assert_eq!(toposort_tarjan_nr(&g), Ok((1..=n).collect_vec()));
+
+        /* close the chain */
+
+        let g = Graph::from_directed_iter(g.edges().chain([(n, 1, 1)]));
+
+        assert_eq!(toposort_tarjan_nr(&g), Err((n, 1)));
+    }
+
+    #[test]
+    fn test_toposort_lex_fixeddata() {
+        let g = Graph::from_directed_iter([
+            (5, 11, 1),
+            (7, 11, 1),
+            (7, 8, 1),
+            (3, 8, 1),
+            (3, 10, 1),
+            (11, 2, 1),
+            (11, 9, 1),
+            (11, 10, 1),
+            (8, 9, 1),
+        ]);
+
+        assert_eq!(toposort_lex(&g), Ok(vec![3, 5, 7, 8, 11, 2, 9, 10]));
+
+        let g = Graph::from_directed_iter([
+            (1, 2, 1),
+            (2, 3, 1),
+            (3, 1, 1),
+            (3, 4, 1),
+            (4, 5, 1),
+            (5, 5, 1),
+            (0, 1, 1),
+        ]);
+
+        assert_eq!(toposort_kahn(&g), Err(vec![1, 2, 3, 5]));
+        assert_eq!(toposort_lex(&g), Err(vec![1, 2, 3, 5]));
+    }
+
+    #[test]
+    fn test_toposort_pk() {
+        for _ in 0..50 {
+            let n = random_range!(2..40);
+            let mut pk = ToposortPK::new();
+            let mut edges = vec![];
+
+            for v in 0..n {
+                pk.insert_vertex(v);
+            }
+
+            for _ in 0..n * 3 {
+                let u = random_range!(0..n);
+                let v = random_range!(0..n);
+
+                let mut next = edges.clone();
+
+                if !next.contains(&(u, v)) {
+                    next.push((u, v));
+                }
+
+                let g = Graph::from_directed_iter(
+                    next.iter().map(|&(u, v)| (u, v, 1)),
+                );
+
+                match pk.insert_edge(u, v) {
+                    Ok(()) => {
+                        assert!(toposort_tarjan(&g).is_ok());
+                        edges = next;
+                    }
+                    Err(cycle) => {
+                        assert!(u == v || toposort_tarjan(&g).is_err());
+                        assert_eq!(cycle.first(), Some(&u));
+                        assert_eq!(cycle.last(), Some(&u));
+                        assert_eq!(cycle[1], v);
+
+                        for (x, y) in cycle.into_iter().tuple_windows() {
+                            assert!(g.contains_edge((x, y)), "{x}->{y}");
+                        }
+                    }
+                }
+
+                for &(x, y) in edges.iter() {
+                    assert!(pk.ord(x) < pk.ord(y));
+                }
+
+                /* remove some */
+
+                if random_range!(0..5) == 0 && !edges.is_empty() {
+                    let i = random_range!(0..edges.len());
+                    let (x, y) = edges.swap_remove(i);
+
+                    assert!(pk.remove_edge(x, y));
+                    assert!(!pk.remove_edge(x, y));
+                }
+            }
+
+            let g = Graph::from_directed_iter(
+                edges.iter().map(|&(u, v)| (u, v, 1)),
+            );
+
+            let vertexs = g.vertexs().collect_vec();
+
+            g.verify_toposort(Some(
+                pk.order().filter(|v| vertexs.contains(v)).collect(),
+            ));
+        }
+    }
 }
@@ -6,11 +6,11 @@ use std::{cmp::max, collections::HashMap};
 use coll::{ get, hashmap };
 
 use super::{distance, furthest_vertex_no_w};
-use crate::Graph;
+use crate::{Graph, Vertex, Weight};
 
 
 /// Two pass bfs (depth)
-pub fn diameter_2bfs_no_w(g: &Graph) -> isize {
+pub fn diameter_2bfs_no_w<V: Vertex, W: Weight>(g: &Graph<V, W>) -> isize {
     /* select entry point */
     let v0 = g.anypoint();
 
@@ -23,7 +23,7 @@ pub fn diameter_2bfs_no_w(g: &Graph) -> isize {
 
 
 /// Save the extra distance array, can't handle negtive weight
-pub fn diameter_2dfs(g: &Graph) -> isize {
+pub fn diameter_2dfs<V: Vertex, W: Weight>(g: &Graph<V, W>) -> W {
     let v0 = g.anypoint();
     let d = distance(g, v0);
     let (v1, _d_v1) = d.into_iter().max_by_key(|x| x.1).unwrap();
@@ -40,26 +40,26 @@ pub fn diameter_2dfs(g: &Graph) -> isize {
 /// That's means the longest and the longest but one
 ///
 /// This method can handle negative weight case.
-pub fn diameter_dp(g: &Graph) -> isize {
+pub fn diameter_dp<V: Vertex, W: Weight>(g: &Graph<V, W>) -> W {
     // zero path weight
-    let zpw = 0;
+    let zpw = W::zero();
 
-    let mut d1: HashMap<usize, isize> = hashmap! {};
-    let mut d2: HashMap<usize, isize> = hashmap! {};
+    let mut d1: HashMap<V, W> = hashmap! {};
+    let mut d2: HashMap<V, W> = hashmap! {};
     let mut res = zpw; //
     let v0 = g.anypoint();
 
     let mut stack = vec![(v0, v0)]
         .into_iter()
         .chain(get!(g.e => v0).into_iter().map(|v| (v, v0)))
-        .collect::<Vec<(usize, usize)>>();
+        .collect::<Vec<(V, V)>>();
 
     while let Some((u, p)) = stack.pop() {
         // 树形结构是连通图，保证了一定会有至少一条边
         let subs = get!(g.e => u)
             .into_iter()
             .filter(|&v| v != p)
-            .collect::<Vec<usize>>();
+            .collect::<Vec<V>>();
 
         // is leaf node
         if subs.is_empty() {
@@ -78,7 +78,7 @@ pub fn diameter_dp(g: &Graph) -> isize {
 
         // after update
         for v in subs.into_iter() {
-            let d1_v = d1.get(&v).unwrap();
+            let d1_v = *d1.get(&v).unwrap();
 
             let mut d1_u = *d1.get(&u).unwrap_or(&zpw);
             let mut d2_u = *d2.get(&u).unwrap_or(&zpw);
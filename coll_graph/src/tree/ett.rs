@@ -0,0 +1,341 @@
+//! Euler tour tree (treap based), dynamic connectivity in forest
+//!
+
+use std::collections::HashMap;
+
+use common::random;
+use coll::get;
+
+use crate::{Graph, Vertex, Weight};
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Constants
+
+const NIL: usize = usize::MAX;
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Structures
+
+/// Euler tour tree, expected O(log n) for link, cut and connected
+///
+/// Each tree is kept as its Euler tour in a treap (implicit key),
+/// vertex v is arc (v, v), edge u-v is the pair of arcs (u, v) and (v, u).
+pub struct EulerTourTree<V = usize> {
+    /// arc -> node
+    arcs: HashMap<(V, V), usize>,
+    nodes: Vec<Node<V>>,
+    /// recycled node
+    free: Vec<usize>,
+}
+
+
+struct Node<V> {
+    arc: (V, V),
+    p: usize,
+    l: usize,
+    r: usize,
+    pri: u32,
+    sz: usize,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Implementations
+
+impl<V: Vertex> EulerTourTree<V> {
+    pub fn new() -> Self {
+        Self {
+            arcs: HashMap::new(),
+            nodes: vec![],
+            free: vec![],
+        }
+    }
+
+    /// Build from a forest (undirected graph)
+    pub fn from_graph<W: Weight>(g: &Graph<V, W>) -> Self {
+        let mut it = Self::new();
+
+        for v in g.vertexs() {
+            it.insert(v);
+        }
+
+        for (u, v, _) in g.edges() {
+            if u < v {
+                let linked = it.link(u, v);
+
+                debug_assert!(linked, "{g:?} isn't a forest");
+            }
+        }
+
+        it
+    }
+
+    pub fn contains(&self, v: V) -> bool {
+        self.arcs.contains_key(&(v, v))
+    }
+
+    /// Insert isolated vertex, return false if it exists
+    pub fn insert(&mut self, v: V) -> bool {
+        if self.contains(v) {
+            return false;
+        }
+
+        let x = self.alloc((v, v));
+        self.arcs.insert((v, v), x);
+
+        true
+    }
+
+    pub fn contains_edge(&self, u: V, v: V) -> bool {
+        u != v && self.arcs.contains_key(&(u, v))
+    }
+
+    /// Add edge u-v, return false if they have been connected
+    pub fn link(&mut self, u: V, v: V) -> bool {
+        if self.connected(u, v) {
+            return false;
+        }
+
+        let tu = self.reroot(get!(self.arcs => (u, u)));
+        let tv = self.reroot(get!(self.arcs => (v, v)));
+
+        let uv = self.alloc((u, v));
+        let vu = self.alloc((v, u));
+
+        self.arcs.insert((u, v), uv);
+        self.arcs.insert((v, u), vu);
+
+        // tour(u), (u, v), tour(v), (v, u)
+        let t = self.merge(tu, uv);
+        let t = self.merge(t, tv);
+        let t = self.merge(t, vu);
+
+        self.nodes[t].p = NIL;
+
+        true
+    }
+
+    /// Remove edge u-v, return false if there is no such edge
+    pub fn cut(&mut self, u: V, v: V) -> bool {
+        if !self.contains_edge(u, v) {
+            return false;
+        }
+
+        let mut e1 = self.arcs.remove(&(u, v)).unwrap();
+        let mut e2 = self.arcs.remove(&(v, u)).unwrap();
+
+        let mut i = self.index(e1);
+        let mut j = self.index(e2);
+
+        if i > j {
+            (e1, e2) = (e2, e1);
+            (i, j) = (j, i);
+        }
+
+        // A, e1, B, e2, C => A + C and B
+        let root = self.root(e1);
+
+        let (lf, rh) = self.split(root, j);
+        let (_e2, c) = self.split(rh, 1);
+        let (a, rh) = self.split(lf, i);
+        let (_e1, _b) = self.split(rh, 1);
+
+        let t = self.merge(a, c);
+
+        if t != NIL {
+            self.nodes[t].p = NIL;
+        }
+
+        self.free.push(e1);
+        self.free.push(e2);
+
+        true
+    }
+
+    pub fn connected(&self, u: V, v: V) -> bool {
+        self.root(get!(self.arcs => (u, u)))
+            == self.root(get!(self.arcs => (v, v)))
+    }
+
+    /// Vertex number of the tree containing v
+    pub fn component_size(&self, v: V) -> usize {
+        let root = self.root(get!(self.arcs => (v, v)));
+
+        // n + 2(n-1) arcs
+        self.nodes[root].sz.div_ceil(3)
+    }
+
+    /// Vertexs of the tree containing v, in Euler tour order
+    pub fn component(&self, v: V) -> Vec<V> {
+        let root = self.root(get!(self.arcs => (v, v)));
+        let mut res = vec![];
+        let mut stack = vec![];
+        let mut x = root;
+
+        // inorder
+        while x != NIL || !stack.is_empty() {
+            while x != NIL {
+                stack.push(x);
+                x = self.nodes[x].l;
+            }
+
+            x = stack.pop().unwrap();
+
+            let (u, v) = self.nodes[x].arc;
+
+            if u == v {
+                res.push(u);
+            }
+
+            x = self.nodes[x].r;
+        }
+
+        res
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+    /// Inner Method
+
+    fn alloc(&mut self, arc: (V, V)) -> usize {
+        let node = Node {
+            arc,
+            p: NIL,
+            l: NIL,
+            r: NIL,
+            pri: random(),
+            sz: 1,
+        };
+
+        if let Some(x) = self.free.pop() {
+            self.nodes[x] = node;
+            x
+        } else {
+            self.nodes.push(node);
+            self.nodes.len() - 1
+        }
+    }
+
+    fn sz(&self, x: usize) -> usize {
+        if x == NIL { 0 } else { self.nodes[x].sz }
+    }
+
+    fn update(&mut self, x: usize) {
+        let Node { l, r, .. } = self.nodes[x];
+
+        self.nodes[x].sz = 1 + self.sz(l) + self.sz(r);
+
+        for c in [l, r] {
+            if c != NIL {
+                self.nodes[c].p = x;
+            }
+        }
+    }
+
+    fn root(&self, mut x: usize) -> usize {
+        while self.nodes[x].p != NIL {
+            x = self.nodes[x].p;
+        }
+
+        x
+    }
+
+    /// position in the tour
+    fn index(&self, x: usize) -> usize {
+        let mut i = self.sz(self.nodes[x].l);
+        let mut y = x;
+
+        while self.nodes[y].p != NIL {
+            let p = self.nodes[y].p;
+
+            if self.nodes[p].r == y {
+                i += self.sz(self.nodes[p].l) + 1;
+            }
+
+            y = p;
+        }
+
+        i
+    }
+
+    fn merge(&mut self, a: usize, b: usize) -> usize {
+        if a == NIL {
+            return b;
+        }
+
+        if b == NIL {
+            return a;
+        }
+
+        if self.nodes[a].pri > self.nodes[b].pri {
+            let r = self.merge(self.nodes[a].r, b);
+            self.nodes[a].r = r;
+            self.update(a);
+
+            a
+        } else {
+            let l = self.merge(a, self.nodes[b].l);
+            self.nodes[b].l = l;
+            self.update(b);
+
+            b
+        }
+    }
+
+    /// (first k, rest), both are detached
+    fn split(&mut self, t: usize, k: usize) -> (usize, usize) {
+        let (a, b) = self.split_(t, k);
+
+        for x in [a, b] {
+            if x != NIL {
+                self.nodes[x].p = NIL;
+            }
+        }
+
+        (a, b)
+    }
+
+    fn split_(&mut self, t: usize, k: usize) -> (usize, usize) {
+        if t == NIL {
+            return (NIL, NIL);
+        }
+
+        let lsz = self.sz(self.nodes[t].l);
+
+        if lsz >= k {
+            let (a, b) = self.split_(self.nodes[t].l, k);
+            self.nodes[t].l = b;
+            self.update(t);
+
+            (a, t)
+        } else {
+            let (a, b) = self.split_(self.nodes[t].r, k - lsz - 1);
+            self.nodes[t].r = a;
+            self.update(t);
+
+            (t, b)
+        }
+    }
+
+    /// Rotate the tour to start from x, return the new treap root
+    fn reroot(&mut self, x: usize) -> usize {
+        let i = self.index(x);
+        let root = self.root(x);
+
+        let (a, b) = self.split(root, i);
+        let t = self.merge(b, a);
+
+        self.nodes[t].p = NIL;
+
+        t
+    }
+}
+
+
+impl<V: Vertex> Default for EulerTourTree<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
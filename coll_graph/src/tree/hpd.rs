@@ -1,13 +1,19 @@
 //! Heavy path decomposition (heavy-light decomposition)
 //!
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    iter::Sum,
+    mem::swap,
+    ops::{Add, AddAssign, Range},
+};
 
 use coll::{
+    segment_tree::{SegmentTree, UpdaterAdd, DFS},
     set, get, contains, getopt, hashmap,
 };
 
-use crate::Graph;
+use crate::{Graph, Vertex, Weight};
 
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -17,27 +23,44 @@ use crate::Graph;
 ///
 ///
 ///
-pub struct HPD {
+pub struct HPD<V = usize> {
     /// depth
-    pub d: HashMap<usize, usize>,
+    pub d: HashMap<V, usize>,
 
     /// parent
-    pub p: HashMap<usize, usize>,
+    pub p: HashMap<V, V>,
 
     /// tree node size
-    pub sz: HashMap<usize, usize>,
+    pub sz: HashMap<V, usize>,
 
     /// heavy son
-    pub hson: HashMap<usize, usize>,
+    pub hson: HashMap<V, V>,
 
     /// heavy link root
-    pub top: HashMap<usize, usize>,
+    pub top: HashMap<V, V>,
 
     /// id of heavy link
-    pub id: HashMap<usize, usize>,
+    pub id: HashMap<V, usize>,
 
     /// rank (rev of id of heavy link)
-    pub rk: Vec<usize>,
+    pub rk: Vec<V>,
+}
+
+
+/// Path / subtree aggregate and range update on tree,
+/// HPD + segment tree (lazy add by `UpdaterAdd`)
+///
+/// T is the segment stats, e.g. `RangeAddSum` (path sum + path add)
+/// or `RangeMax` (path max + path chmax),
+/// path query combines segments out of order,
+/// so T's `Add` should be commutative.
+///
+/// Edge weight is stored on the deeper vertex (`from_edge_weights`),
+/// then query it by `*_path_edges` which excludes the LCA.
+pub struct HPDQuery<T, V = usize> {
+    pub hpd: HPD<V>,
+    st: SegmentTree<T, DFS>,
+    updater: UpdaterAdd<T, DFS>,
 }
 
 
@@ -45,9 +68,9 @@ pub struct HPD {
 ////////////////////////////////////////////////////////////////////////////////
 //// Implementations
 
-impl HPD {
+impl<V: Vertex> HPD<V> {
     /// two pass dfs init
-    pub fn new(g: &Graph, start: Option<usize>) -> Self {
+    pub fn new<W: Weight>(g: &Graph<V, W>, start: Option<V>) -> Self {
         let start = start.unwrap_or(g.anypoint());
 
         let mut it = Self {
@@ -57,11 +80,17 @@ impl HPD {
             hson: hashmap! {},
             top: hashmap! {},
             id: hashmap! {},
-            rk: vec![0; g.vertexs().count()],
+            rk: Vec::with_capacity(g.vertexs().count()),
         };
 
         /// d, p, sz, hson,
-        fn dfs1(g: &Graph, u: usize, p: usize, d: usize,  it: &mut HPD) {
+        fn dfs1<V: Vertex, W: Weight>(
+            g: &Graph<V, W>,
+            u: V,
+            p: V,
+            d: usize,
+            it: &mut HPD<V>,
+        ) {
             set!(it.d => u => d);
             set!(it.p => u => p);
             set!(it.sz => u => 1);
@@ -85,10 +114,16 @@ impl HPD {
         /// top, id, rk,
         ///
         /// Heavy link first search
-        fn dfs2(g: &Graph, u: usize, top: usize, id: &mut usize, it: &mut HPD) {
+        fn dfs2<V: Vertex, W: Weight>(
+            g: &Graph<V, W>,
+            u: V,
+            top: V,
+            id: &mut usize,
+            it: &mut HPD<V>,
+        ) {
             set!(it.top => u => top);
             set!(it.id => u => *id);
-            it.rk[*id] = u;
+            it.rk.push(u);
             *id += 1;
 
             if let Some(hson) = getopt!(it.hson => u) {
@@ -111,3 +146,140 @@ impl HPD {
         it
     }
 }
+
+
+impl<T, V: Vertex> HPDQuery<T, V>
+where
+    T: Clone + Sum + Add<Output = T> + Ord,
+    for<'a> T: AddAssign<&'a T>,
+    for<'a> &'a T: Add<&'a T, Output = T>,
+{
+    /// vertex value by `val`
+    pub fn new<W: Weight, U: Clone + Into<T>>(
+        g: &Graph<V, W>,
+        start: Option<V>,
+        val: impl Fn(V) -> U,
+    ) -> Self {
+        let hpd = HPD::new(g, start);
+        let raw: Vec<U> = hpd.rk.iter().map(|&v| val(v)).collect();
+
+        let st = SegmentTree::new(&raw);
+        let updater = st.create_updater();
+
+        Self { hpd, st, updater }
+    }
+
+    /// vertex value is the weight of edge to its parent (root is zero)
+    pub fn from_edge_weights<W: Weight + Into<T>>(
+        g: &Graph<V, W>,
+        start: Option<V>,
+    ) -> Self {
+        let hpd = HPD::new(g, start);
+
+        let raw: Vec<T> = hpd
+            .rk
+            .iter()
+            .map(|&v| {
+                let p = get!(hpd.p => v);
+
+                if p == v {
+                    W::zero().into()
+                } else {
+                    get!(g.w => (p, v)).into()
+                }
+            })
+            .collect();
+
+        let st = SegmentTree::new(&raw);
+        let updater = st.create_updater();
+
+        Self { hpd, st, updater }
+    }
+
+    /// aggregate of vertexs on path u-v (included)
+    pub fn query_path(&mut self, u: V, v: V) -> T {
+        self.path_ranges(u, v, true)
+            .into_iter()
+            .map(|range| self.updater.query(&mut self.st, range))
+            .sum()
+    }
+
+    /// aggregate of edges on path u-v
+    pub fn query_path_edges(&mut self, u: V, v: V) -> T {
+        self.path_ranges(u, v, false)
+            .into_iter()
+            .map(|range| self.updater.query(&mut self.st, range))
+            .sum()
+    }
+
+    pub fn update_path(&mut self, u: V, v: V, addend: T) {
+        for range in self.path_ranges(u, v, true) {
+            self.updater.assoc(&mut self.st, range, addend.clone());
+        }
+    }
+
+    pub fn update_path_edges(&mut self, u: V, v: V, addend: T) {
+        for range in self.path_ranges(u, v, false) {
+            self.updater.assoc(&mut self.st, range, addend.clone());
+        }
+    }
+
+    pub fn query_subtree(&mut self, u: V) -> T {
+        let range = self.subtree_range(u);
+
+        self.updater.query(&mut self.st, range)
+    }
+
+    pub fn update_subtree(&mut self, u: V, addend: T) {
+        let range = self.subtree_range(u);
+
+        self.updater.assoc(&mut self.st, range, addend)
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+    /// Inner Method
+
+    fn subtree_range(&self, u: V) -> Range<usize> {
+        let id = get!(self.hpd.id => u);
+
+        id..id + get!(self.hpd.sz => u)
+    }
+
+    /// O(log n) continous id ranges covering path u-v
+    fn path_ranges(
+        &self,
+        mut u: V,
+        mut v: V,
+        with_lca: bool,
+    ) -> Vec<Range<usize>> {
+        let hpd = &self.hpd;
+        let mut ranges = vec![];
+
+        while get!(hpd.top => u) != get!(hpd.top => v) {
+            if get!(hpd.d => get!(hpd.top => u))
+                < get!(hpd.d => get!(hpd.top => v))
+            {
+                swap(&mut u, &mut v);
+            }
+
+            let top = get!(hpd.top => u);
+
+            ranges.push(get!(hpd.id => top)..get!(hpd.id => u) + 1);
+            u = get!(hpd.p => top);
+        }
+
+        if get!(hpd.d => u) > get!(hpd.d => v) {
+            swap(&mut u, &mut v);
+        }
+
+        // u is LCA
+        let start = get!(hpd.id => u) + if with_lca { 0 } else { 1 };
+        let end = get!(hpd.id => v) + 1;
+
+        if start < end {
+            ranges.push(start..end);
+        }
+
+        ranges
+    }
+}
@@ -1,14 +1,18 @@
-use std::collections::{HashSet, HashMap};
+use std::{
+    collections::{HashSet, HashMap},
+    ops::Add,
+};
 
 use coll::{
     apush, get, getopt, mv, set,
     {
         easycoll::MV,
+        sparse_table::SparseTable,
         union_find::UnionFind,
     }, hashmap,
 };
 
-use super::{hpd::HPD, Graph};
+use super::{hpd::HPD, EulerSeq2, Graph, Vertex, Weight};
 
 ////////////////////////////////////////////////////////////////////////////////
 //// Structures
@@ -16,35 +20,54 @@ use super::{hpd::HPD, Graph};
 /// Binary lifting, (improved version of native) 对数(2)式跳转
 ///
 /// Obviously, it's online algorithm.
-pub struct LCADP {
-    data: LCADPD,
+pub struct LCADP<V = usize> {
+    data: LCADPD<V>,
 }
 
 /// LCA Binary Lifting Data
-struct LCADPD {
+struct LCADPD<V> {
     /// u, 2^(idx) th ancestor, ancestor id
-    acs: MV<usize, usize>,
+    acs: MV<V, V>,
     /// depth
-    depth: HashMap<usize, usize>,
+    depth: HashMap<V, usize>,
 }
 
 
+/// Euler tour + sparse table RMQ, O(n*log(n)) preprocessing, O(1) query
+///
+/// LCA is the shallowest vertex between the first occurrences of them
+pub struct LCARMQ<V = usize> {
+    /// first occurrence in euler tour
+    first: HashMap<V, usize>,
+    st: SparseTable<DepthMin<V>>,
+}
+
+/// (depth, vertex), combine by min depth
+#[derive(Clone, Copy)]
+struct DepthMin<V>(usize, V);
+
+
 /// LCA Tarjan using
-pub struct LCATarjan<'a> {
-    g: &'a Graph,
-    root: usize,
-    dsu: UnionFind<usize>,
+pub struct LCATarjan<'a, V = usize, W = isize> {
+    g: &'a Graph<V, W>,
+    root: V,
+    dsu: UnionFind<V>,
 }
 
 
 ////////////////////////////////////////////////////////////////////////////////
 //// Implementations
 
-impl LCADP {
+impl<V: Vertex> LCADP<V> {
     /// O(n*log(n))
-    pub fn new(g: &Graph, root: usize) -> Self {
+    pub fn new<W: Weight>(g: &Graph<V, W>, root: V) -> Self {
         /// Recursive version of LCA setup data
-        fn setup_dfs_r(g: &Graph, u: usize, p: usize, data: LCADPD) -> LCADPD {
+        fn setup_dfs_r<V: Vertex, W: Weight>(
+            g: &Graph<V, W>,
+            u: V,
+            p: V,
+            data: LCADPD<V>,
+        ) -> LCADPD<V> {
             let LCADPD { mut acs, mut depth } = data;
 
             for v in get!(g.e => u) {
@@ -92,8 +115,39 @@ impl LCADP {
         Self { data }
     }
 
+    /// Non-recursive version of `new` (explicit stack), same data
+    pub fn new_nr<W: Weight>(g: &Graph<V, W>, root: V) -> Self {
+        let mut acs = mv![root => 0 => root];
+        let mut depth: HashMap<V, usize> = hashmap![root => 0];
+
+        // (u, parent of u)
+        let mut stack = vec![(root, root)];
+
+        while let Some((u, p)) = stack.pop() {
+            let v_depth = get!(depth => u) + 1;
+
+            for v in get!(g.e => u) {
+                if v == p {
+                    continue;
+                }
+
+                set!(depth => v => v_depth);
+                apush!(acs => v => u);
+
+                for j in 1..=v_depth.ilog2() as usize {
+                    let oldstate = get!(acs => get!(acs => v, j - 1), j - 1);
+                    apush!(acs => v => oldstate);
+                }
+
+                stack.push((v, u));
+            }
+        }
+
+        Self { data: LCADPD { acs, depth } }
+    }
+
     /// O(logn) + online
-    pub fn query(&self, mut p: usize, mut q: usize) -> usize {
+    pub fn query(&self, mut p: V, mut q: V) -> V {
         let depth = &self.data.depth;
         let acs = &self.data.acs;
 
@@ -130,7 +184,12 @@ impl LCADP {
 
         /* binary decomposition of unknown x depth before lca */
 
-        for j in (1..=get!(depth => p).ilog2() as usize).rev() {
+        for j in (0..=get!(depth => p).ilog2() as usize).rev() {
+            // p has been lifted beyond 2^j
+            if 1 << j > get!(depth => p) {
+                continue;
+            }
+
             if get!(acs => p,j) != get!(acs => q,j) {
                 p = get!(acs => p,j);
                 q = get!(acs => q,j);
@@ -150,8 +209,56 @@ impl LCADP {
 
 
 
-impl<'a> LCATarjan<'a> {
-    pub fn new(g: &'a Graph, root: usize) -> Self {
+impl<V: Vertex> LCARMQ<V> {
+    pub fn new<W: Weight>(g: &Graph<V, W>, root: V) -> Self {
+        let seq = EulerSeq2::new(g, root).as_seq().collect::<Vec<V>>();
+
+        let mut first = HashMap::new();
+        let mut depth: HashMap<V, usize> = HashMap::new();
+        let mut tour = Vec::with_capacity(seq.len());
+
+        for (i, v) in seq.into_iter().enumerate() {
+            // a new vertex is always child of the previous one
+            let d = *depth.entry(v).or_insert_with(|| {
+                first.insert(v, i);
+
+                tour.last().map(|&DepthMin(d, _)| d + 1).unwrap_or(0)
+            });
+
+            tour.push(DepthMin(d, v));
+        }
+
+        Self {
+            first,
+            st: SparseTable::new(&tour),
+        }
+    }
+
+    /// O(1)
+    pub fn query(&self, p: V, q: V) -> V {
+        let mut i = get!(self.first => p);
+        let mut j = get!(self.first => q);
+
+        if i > j {
+            (i, j) = (j, i);
+        }
+
+        self.st.query(i..=j).1
+    }
+}
+
+
+impl<'a, V: Copy> Add for &'a DepthMin<V> {
+    type Output = DepthMin<V>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        if self.0 <= rhs.0 { *self } else { *rhs }
+    }
+}
+
+
+impl<'a, V: Vertex, W: Weight> LCATarjan<'a, V, W> {
+    pub fn new(g: &'a Graph<V, W>, root: V) -> Self {
         let mut dsu = UnionFind::new(None);
 
         for n in g.vertexs() {
@@ -164,10 +271,10 @@ impl<'a> LCATarjan<'a> {
     /// O((n+m) / m) + offline, 处理大量查询时有优势
     ///
     /// one pass query
-    pub fn queries(&self, queries: &[(usize, usize)]) -> Vec<usize> {
+    pub fn queries(&self, queries: &[(V, V)]) -> Vec<V> {
         // get an independent version of usu for in-place find
         let mut dsu = self.dsu.clone();
-        let mut res = vec![0; queries.len()];
+        let mut res = vec![None; queries.len()];
         let mut visited = HashSet::new();
 
         let mut q = mv![];
@@ -178,13 +285,13 @@ impl<'a> LCATarjan<'a> {
             apush!(q => v => (i, u));
         }
 
-        fn tarjan(
-            g: &Graph,
-            u: usize,
-            visited: &mut HashSet<usize>,
-            dsu: &mut UnionFind<usize>,
-            q: &MV<usize, (usize, usize)>,
-            res: &mut Vec<usize>,
+        fn tarjan<V: Vertex, W: Weight>(
+            g: &Graph<V, W>,
+            u: V,
+            visited: &mut HashSet<V>,
+            dsu: &mut UnionFind<V>,
+            q: &MV<V, (usize, V)>,
+            res: &mut Vec<Option<V>>,
         ) {
             visited.insert(u);
 
@@ -203,7 +310,7 @@ impl<'a> LCATarjan<'a> {
                 for (qi, x) in tox {
                     if visited.contains(&x) {
                         // println!("dsu: {dsu:?}");
-                        res[qi] = dsu.cfind(x);
+                        res[qi] = Some(dsu.cfind(x));
                     }
                 }
             }
@@ -211,16 +318,16 @@ impl<'a> LCATarjan<'a> {
 
         tarjan(self.g, self.root, &mut visited, &mut dsu, &q, &mut res);
 
-        res
+        res.into_iter().map(|x| x.unwrap()).collect()
     }
 }
 
 
-impl HPD {
+impl<V: Vertex> HPD<V> {
     /// O(log(n))
     ///
     /// 同重链， O(1); 不同重链，O(log2(n))
-    pub fn lca(&self, mut x: usize, mut y: usize) -> usize {
+    pub fn lca(&self, mut x: V, mut y: V) -> V {
         /* x, topx is always deptheset */
 
         if get!(self.d => x) < get!(self.d => y) {
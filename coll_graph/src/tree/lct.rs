@@ -0,0 +1,351 @@
+//! Link-cut tree (splay based), dynamic forest with path aggregate
+//!
+
+use std::{collections::HashMap, mem::swap, ops::Add};
+
+use coll::get;
+
+use crate::{Graph, Vertex, Weight};
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Constants
+
+const NIL: usize = usize::MAX;
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Structures
+
+/// Link-cut tree, amortized O(log n) for every operation
+///
+/// Vertex value T is aggregated by `Add` (which should be commutative,
+/// because make root reverses the path).
+pub struct LinkCutTree<V = usize, T = isize> {
+    idx: HashMap<V, usize>,
+    nodes: Vec<Node<V, T>>,
+}
+
+
+struct Node<V, T> {
+    v: V,
+    /// parent in splay or path-parent
+    p: usize,
+    ch: [usize; 2],
+    /// lazy reverse
+    rev: bool,
+    val: T,
+    agg: T,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Implementations
+
+impl<V: Vertex, T: Clone + Add<Output = T>> LinkCutTree<V, T> {
+    pub fn new() -> Self {
+        Self {
+            idx: HashMap::new(),
+            nodes: vec![],
+        }
+    }
+
+    /// Build from a forest (undirected graph)
+    pub fn from_graph<W: Weight>(
+        g: &Graph<V, W>,
+        val: impl Fn(V) -> T,
+    ) -> Self {
+        let mut it = Self::new();
+
+        for v in g.vertexs() {
+            it.insert(v, val(v));
+        }
+
+        for (u, v, _) in g.edges() {
+            if u < v {
+                let linked = it.link(u, v);
+
+                debug_assert!(linked, "{g:?} isn't a forest");
+            }
+        }
+
+        it
+    }
+
+    pub fn contains(&self, v: V) -> bool {
+        self.idx.contains_key(&v)
+    }
+
+    /// Insert isolated vertex or update value of the vertex
+    pub fn insert(&mut self, v: V, val: T) {
+        if let Some(&x) = self.idx.get(&v) {
+            self.splay(x);
+            self.nodes[x].val = val;
+            self.push_up(x);
+        } else {
+            self.idx.insert(v, self.nodes.len());
+            self.nodes.push(Node {
+                v,
+                p: NIL,
+                ch: [NIL, NIL],
+                rev: false,
+                val: val.clone(),
+                agg: val,
+            });
+        }
+    }
+
+    pub fn get(&self, v: V) -> Option<&T> {
+        self.idx.get(&v).map(|&x| &self.nodes[x].val)
+    }
+
+    /// Add edge u-v, return false if they have been connected
+    pub fn link(&mut self, u: V, v: V) -> bool {
+        let x = get!(self.idx => u);
+        let y = get!(self.idx => v);
+
+        self.make_root(x);
+
+        if self.find_root_(y) == x {
+            return false;
+        }
+
+        self.nodes[x].p = y;
+
+        true
+    }
+
+    /// Remove edge u-v, return false if there is no such edge
+    pub fn cut(&mut self, u: V, v: V) -> bool {
+        let x = get!(self.idx => u);
+        let y = get!(self.idx => v);
+
+        if x == y {
+            return false;
+        }
+
+        self.make_root(x);
+        self.access(y);
+
+        // path x-y is exactly x, y
+        if self.nodes[y].ch[0] != x {
+            return false;
+        }
+
+        self.push_down(x);
+
+        if self.nodes[x].ch[1] != NIL {
+            return false;
+        }
+
+        self.nodes[y].ch[0] = NIL;
+        self.nodes[x].p = NIL;
+        self.push_up(y);
+
+        true
+    }
+
+    pub fn find_root(&mut self, v: V) -> V {
+        let x = get!(self.idx => v);
+        let root = self.find_root_(x);
+
+        self.nodes[root].v
+    }
+
+    pub fn connected(&mut self, u: V, v: V) -> bool {
+        self.find_root(u) == self.find_root(v)
+    }
+
+    /// Make v be root of its tree
+    pub fn evert(&mut self, v: V) {
+        let x = get!(self.idx => v);
+
+        self.make_root(x)
+    }
+
+    /// LCA under current root (specified by `evert`)
+    pub fn lca(&mut self, u: V, v: V) -> Option<V> {
+        if !self.connected(u, v) {
+            return None;
+        }
+
+        let x = get!(self.idx => u);
+        let y = get!(self.idx => v);
+
+        self.access(x);
+        let lca = self.access(y);
+
+        Some(self.nodes[lca].v)
+    }
+
+    /// Aggregate of vertex value on path u-v (included)
+    pub fn query_path(&mut self, u: V, v: V) -> Option<T> {
+        if !self.connected(u, v) {
+            return None;
+        }
+
+        let x = get!(self.idx => u);
+        let y = get!(self.idx => v);
+
+        self.make_root(x);
+        self.access(y);
+
+        Some(self.nodes[y].agg.clone())
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+    /// Inner Method
+
+    fn is_root(&self, x: usize) -> bool {
+        let p = self.nodes[x].p;
+
+        p == NIL || !self.nodes[p].ch.contains(&x)
+    }
+
+    fn push_up(&mut self, x: usize) {
+        let [l, r] = self.nodes[x].ch;
+        let mut agg = self.nodes[x].val.clone();
+
+        if l != NIL {
+            agg = self.nodes[l].agg.clone() + agg;
+        }
+
+        if r != NIL {
+            agg = agg + self.nodes[r].agg.clone();
+        }
+
+        self.nodes[x].agg = agg;
+    }
+
+    fn push_down(&mut self, x: usize) {
+        if self.nodes[x].rev {
+            for c in self.nodes[x].ch {
+                if c != NIL {
+                    let node = &mut self.nodes[c];
+
+                    node.ch.swap(0, 1);
+                    node.rev ^= true;
+                }
+            }
+
+            self.nodes[x].rev = false;
+        }
+    }
+
+    fn rotate(&mut self, x: usize) {
+        let y = self.nodes[x].p;
+        let z = self.nodes[y].p;
+        let dir = (self.nodes[y].ch[1] == x) as usize;
+        let b = self.nodes[x].ch[dir ^ 1];
+
+        if !self.is_root(y) {
+            let ydir = (self.nodes[z].ch[1] == y) as usize;
+            self.nodes[z].ch[ydir] = x;
+        }
+
+        self.nodes[x].p = z;
+
+        self.nodes[x].ch[dir ^ 1] = y;
+        self.nodes[y].p = x;
+
+        self.nodes[y].ch[dir] = b;
+
+        if b != NIL {
+            self.nodes[b].p = y;
+        }
+
+        self.push_up(y);
+        self.push_up(x);
+    }
+
+    fn splay(&mut self, x: usize) {
+        // push down lazy tag from splay root
+        let mut stack = vec![x];
+        let mut y = x;
+
+        while !self.is_root(y) {
+            y = self.nodes[y].p;
+            stack.push(y);
+        }
+
+        while let Some(y) = stack.pop() {
+            self.push_down(y);
+        }
+
+        while !self.is_root(x) {
+            let y = self.nodes[x].p;
+
+            if !self.is_root(y) {
+                let z = self.nodes[y].p;
+
+                // zig-zig or zig-zag
+                if (self.nodes[y].ch[1] == x) == (self.nodes[z].ch[1] == y) {
+                    self.rotate(y);
+                } else {
+                    self.rotate(x);
+                }
+            }
+
+            self.rotate(x);
+        }
+    }
+
+    /// Make root-x be preferred path, return the last path-parent jumped to
+    fn access(&mut self, x: usize) -> usize {
+        let mut last = NIL;
+        let mut y = x;
+
+        while y != NIL {
+            self.splay(y);
+            self.nodes[y].ch[1] = last;
+            self.push_up(y);
+
+            last = y;
+            y = self.nodes[y].p;
+        }
+
+        self.splay(x);
+
+        last
+    }
+
+    fn make_root(&mut self, x: usize) {
+        self.access(x);
+
+        let node = &mut self.nodes[x];
+        let [l, r] = &mut node.ch;
+
+        swap(l, r);
+        node.rev ^= true;
+    }
+
+    fn find_root_(&mut self, x: usize) -> usize {
+        self.access(x);
+
+        let mut y = x;
+
+        loop {
+            self.push_down(y);
+
+            let l = self.nodes[y].ch[0];
+
+            if l == NIL {
+                break;
+            }
+
+            y = l;
+        }
+
+        self.splay(y);
+
+        y
+    }
+}
+
+
+impl<V: Vertex, T: Clone + Add<Output = T>> Default for LinkCutTree<V, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -7,11 +7,13 @@ use common::{hashset, Itertools};
 
 use coll::{contains, get, set, hashmap};
 
-use super::Graph;
+use super::{Graph, Vertex, Weight};
 
 pub mod diameter;
 pub mod lca;
 pub mod hpd;
+pub mod lct;
+pub mod ett;
 
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -20,29 +22,29 @@ pub mod hpd;
 /// 从起点到终点再到起点的简单路径，每条路径访问两次（一来一回）
 ///
 /// Kind 1 in-out
-pub struct EulerSeq1 {
+pub struct EulerSeq1<V = usize> {
     /// vertex-timestamp
-    ein: HashMap<usize, usize>,
-    eout: HashMap<usize, usize>,
+    ein: HashMap<V, usize>,
+    eout: HashMap<V, usize>,
 }
 
 
 /// Kind 2 record every node
-pub struct EulerSeq2 {
-    seq: Vec<usize>,
+pub struct EulerSeq2<V = usize> {
+    seq: Vec<V>,
 }
 
 
 #[derive(Debug, Clone, Copy)]
-pub enum Center {
-    One(usize),
-    AdjTwo(usize, usize),
+pub enum Center<V = usize> {
+    One(V),
+    AdjTwo(V, V),
 }
 
 ////////////////////////////////////////////////////////////////////////////////
 //// Implementations
 
-impl PartialEq<Self> for Center {
+impl<V: Vertex> PartialEq<Self> for Center<V> {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::One(l0), Self::One(r0)) => l0 == r0,
@@ -55,8 +57,8 @@ impl PartialEq<Self> for Center {
 }
 
 
-impl EulerSeq1 {
-    pub fn new(g: &Graph, start: usize) -> Self {
+impl<V: Vertex> EulerSeq1<V> {
+    pub fn new<W: Weight>(g: &Graph<V, W>, start: V) -> Self {
         let mut stack = vec![(start, false)];
         let mut ein = HashMap::new();
         let mut eout = HashMap::new();
@@ -91,7 +93,7 @@ impl EulerSeq1 {
     }
 
 
-    pub fn as_seq<'a>(&'a self) -> impl Iterator<Item = usize> + 'a {
+    pub fn as_seq<'a>(&'a self) -> impl Iterator<Item = V> + 'a {
         self.ein
             .iter()
             .chain(self.eout.iter())
@@ -100,21 +102,21 @@ impl EulerSeq1 {
     }
 
     /// Check if x is parent of y
-    pub fn is_paren(&self, x: usize, y: usize) -> bool {
+    pub fn is_paren(&self, x: V, y: V) -> bool {
         get!(self.ein => x) <= get!(self.ein => y)
             && get!(self.eout => y) >= get!(self.eout => y)
     }
 }
 
 
-impl EulerSeq2 {
-    pub fn new(g: &Graph, start: usize) -> Self {
-        fn dfs(
-            g: &Graph,
-            u: usize,
-            p: usize,
-            mut seq: Vec<usize>,
-        ) -> Vec<usize> {
+impl<V: Vertex> EulerSeq2<V> {
+    pub fn new<W: Weight>(g: &Graph<V, W>, start: V) -> Self {
+        fn dfs<V: Vertex, W: Weight>(
+            g: &Graph<V, W>,
+            u: V,
+            p: V,
+            mut seq: Vec<V>,
+        ) -> Vec<V> {
             for v in get!(g.e => u => vec![]) {
                 if v != p {
                     seq.push(u);
@@ -132,7 +134,7 @@ impl EulerSeq2 {
         }
     }
 
-    pub fn as_seq<'a>(&'a self) -> impl Iterator<Item = usize> + 'a {
+    pub fn as_seq<'a>(&'a self) -> impl Iterator<Item = V> + 'a {
         self.seq.iter().cloned()
     }
 }
@@ -142,7 +144,10 @@ impl EulerSeq2 {
 //// Functions
 
 /// Include itself no weight property,
-pub fn furthest_vertex_no_w(g: &Graph, u: usize) -> (isize, Vec<usize>) {
+pub fn furthest_vertex_no_w<V: Vertex, W: Weight>(
+    g: &Graph<V, W>,
+    u: V,
+) -> (isize, Vec<V>) {
     let mut prev_q = vec![u];
     let mut cur_q = get!(g.e => u);
     let mut is_visited = hashset! { u };
@@ -171,12 +176,15 @@ pub fn furthest_vertex_no_w(g: &Graph, u: usize) -> (isize, Vec<usize>) {
 
 
 /// get further vertex from dfs (can also bfs, but dfs is more easy)
-pub fn distance(g: &Graph, u: usize) -> HashMap<usize, isize> {
+pub fn distance<V: Vertex, W: Weight>(
+    g: &Graph<V, W>,
+    u: V,
+) -> HashMap<V, W> {
     let mut d = hashmap! {};
-    let mut q = vec![(u, 0)];
+    let mut q = vec![(u, W::zero())];
     let mut visited = hashset! {u};
 
-    set!(d => u => 0);
+    set!(d => u => W::zero());
 
     while let Some((u, tot)) = q.pop() {
         for v in get!(g.e => u) {
@@ -196,8 +204,12 @@ pub fn distance(g: &Graph, u: usize) -> HashMap<usize, isize> {
 
 
 /// 某个点（或某两个邻接的点）使得最大子树的节点数相等
-pub fn center(g: &Graph) -> Center {
-    fn subsize(g: &Graph, u: usize, p: usize) -> usize {
+pub fn center<V: Vertex, W: Weight>(g: &Graph<V, W>) -> Center<V> {
+    fn subsize<V: Vertex, W: Weight>(
+        g: &Graph<V, W>,
+        u: V,
+        p: V,
+    ) -> usize {
         let mut cnt = 1;
 
         for v in get!(g.e => u) {
@@ -209,7 +221,7 @@ pub fn center(g: &Graph) -> Center {
         cnt
     }
 
-    fn max_subsize(g: &Graph, u: usize) -> usize {
+    fn max_subsize<V: Vertex, W: Weight>(g: &Graph<V, W>, u: V) -> usize {
         get!(g.e => u => vec![])
             .into_iter()
             .map(|v| {  // 最后一颗子树结点数可以利用 n - acc 来直接求出
@@ -248,14 +260,19 @@ pub fn center(g: &Graph) -> Center {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use std::collections::{HashMap, HashSet};
 
-    use common::Itertools;
+    use common::{random_range, thread_rng, Itertools};
+    use coll::segment_tree::{RangeAddSum, RangeMax};
 
     use crate::{
+        test::model::{gen_chain, gen_random_forest, gen_random_tree},
         tree::{
             center, diameter::*, furthest_vertex_no_w, lca::LCATarjan,
-            lca::LCADP, Center, EulerSeq1, EulerSeq2, hpd::HPD,
+            lca::{LCADP, LCARMQ},
+            Center, EulerSeq1, EulerSeq2,
+            hpd::{HPD, HPDQuery},
+            lct::LinkCutTree, ett::EulerTourTree,
         },
         Graph,
     };
@@ -425,6 +442,12 @@ mod tests {
 
             assert_eq!(lca_tarjan.queries(&q), res);
 
+            let lca_rmq = LCARMQ::new(g, start);
+
+            for (p, q, res) in qd.clone() {
+                assert_eq!(lca_rmq.query(p, q), res);
+            }
+
             let hpd = HPD::new(g, Some(start));
 
             for (x, y, res) in qd {
@@ -467,4 +490,409 @@ mod tests {
             assert_eq!(center(&g[gi]), res,);
         }
     }
+
+    #[test]
+    fn test_hpd_query() {
+        let mut rng = thread_rng();
+
+        for _ in 0..20 {
+            let n = random_range!(2..200);
+            let g = gen_random_tree(n, 1..100, &mut rng);
+
+            /* brute force parent / depth (root is 1) */
+
+            let mut p = vec![0; n + 1];
+            let mut d = vec![0; n + 1];
+            let mut stack = vec![1];
+            p[1] = 1;
+
+            while let Some(u) = stack.pop() {
+                for &v in g.e.0[&u].iter() {
+                    if v != p[u] {
+                        p[v] = u;
+                        d[v] = d[u] + 1;
+                        stack.push(v);
+                    }
+                }
+            }
+
+            // (vertexs, edge-bottom vertexs)
+            let path = |mut u: usize, mut v: usize| {
+                let mut vs = vec![];
+
+                while u != v {
+                    if d[u] < d[v] {
+                        std::mem::swap(&mut u, &mut v);
+                    }
+
+                    vs.push(u);
+                    u = p[u];
+                }
+
+                let es = vs.clone();
+                vs.push(u);
+
+                (vs, es)
+            };
+            let subtree = |u: usize| {
+                (1..=n)
+                    .filter(|&v| {
+                        let mut v = v;
+
+                        while v != u && v != 1 {
+                            v = p[v];
+                        }
+
+                        v == u
+                    })
+                    .collect_vec()
+            };
+
+            let mut vals = (0..=n as isize)
+                .map(|_| random_range!(-50..50))
+                .collect_vec();
+            let mut q =
+                HPDQuery::<RangeAddSum<isize>>::new(&g, Some(1), |v| vals[v]);
+
+            // RangeMax takes zero as identity, keep it non-negative
+            let mut maxs = vals.iter().map(|x| x + 50).collect_vec();
+            let mut qmax =
+                HPDQuery::<RangeMax<isize>>::new(&g, Some(1), |v| maxs[v]);
+
+            for _ in 0..100 {
+                let u = random_range!(1..=n);
+                let v = random_range!(1..=n);
+                let x = random_range!(-50..50);
+
+                let (vs, _) = path(u, v);
+                let sub = subtree(u);
+
+                match random_range!(0..4) {
+                    0 => {
+                        vs.iter().for_each(|&w| vals[w] += x);
+                        q.update_path(u, v, RangeAddSum::addend(x));
+
+                        vs.iter().for_each(|&w| maxs[w] = maxs[w].max(x + 50));
+                        qmax.update_path(u, v, (x + 50).into());
+                    }
+                    1 => {
+                        sub.iter().for_each(|&w| vals[w] += x);
+                        q.update_subtree(u, RangeAddSum::addend(x));
+
+                        sub.iter().for_each(|&w| maxs[w] = maxs[w].max(x + 50));
+                        qmax.update_subtree(u, (x + 50).into());
+                    }
+                    2 => {
+                        let expect: isize = vs.iter().map(|&w| vals[w]).sum();
+                        assert_eq!(q.query_path(u, v), expect);
+
+                        let expect = vs.iter().map(|&w| maxs[w]).max().unwrap();
+                        assert_eq!(qmax.query_path(u, v), expect);
+                    }
+                    _ => {
+                        let expect: isize = sub.iter().map(|&w| vals[w]).sum();
+                        assert_eq!(q.query_subtree(u), expect);
+
+                        let expect =
+                            sub.iter().map(|&w| maxs[w]).max().unwrap();
+                        assert_eq!(qmax.query_subtree(u), expect);
+                    }
+                }
+            }
+
+            /* edge weights */
+
+            let mut ws = (0..=n)
+                .map(|v| if v <= 1 { 0 } else { g.w[&(p[v], v)] })
+                .collect_vec();
+            let mut qe =
+                HPDQuery::<RangeAddSum<isize>>::from_edge_weights(&g, Some(1));
+
+            for _ in 0..100 {
+                let u = random_range!(1..=n);
+                let v = random_range!(1..=n);
+                let (_, es) = path(u, v);
+
+                if random_range!(0..2) == 0 {
+                    let x = random_range!(-50..50);
+
+                    es.iter().for_each(|&w| ws[w] += x);
+                    qe.update_path_edges(u, v, RangeAddSum::addend(x));
+                } else {
+                    let expect: isize = es.iter().map(|&w| ws[w]).sum();
+                    assert_eq!(qe.query_path_edges(u, v), expect);
+                }
+            }
+        }
+    }
+
+    /// random link / cut on forest of 1..=n
+    fn random_forest_ops(
+        n: usize,
+        k: usize,
+        edges: &mut Vec<(usize, usize)>,
+        mut link: impl FnMut(usize, usize) -> bool,
+        mut cut: impl FnMut(usize, usize) -> bool,
+    ) {
+        for _ in 0..k {
+            let g = Graph::from_undirected_iter(
+                edges.iter().map(|&(u, v)| (u, v, 1)),
+            );
+            let comps = g.components();
+            let comp = |v: usize| {
+                comps
+                    .iter()
+                    .find(|c| c.contains(&v))
+                    .cloned()
+                    .unwrap_or(vec![v])
+            };
+
+            let u = random_range!(1..=n);
+            let v = random_range!(1..=n);
+
+            if random_range!(0..3) > 0 || edges.is_empty() {
+                let expect = u != v && !comp(u).contains(&v);
+
+                assert_eq!(link(u, v), expect, "link {u}-{v}");
+
+                if expect {
+                    edges.push((u, v));
+                }
+            } else {
+                let i = random_range!(0..edges.len());
+                let (u, v) = edges.swap_remove(i);
+
+                assert!(cut(v, u), "cut {u}-{v}");
+                assert!(!cut(u, v), "cut again {u}-{v}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_lct() {
+        let mut rng = thread_rng();
+
+        for _ in 0..20 {
+            let n = random_range!(2..80);
+            let k = random_range!(1..=n / 2);
+            let g = gen_random_forest(n, k, 1..10, &mut rng);
+            let vals =
+                (0..=n).map(|_| random_range!(-50..50isize)).collect_vec();
+
+            let mut lct = LinkCutTree::from_graph(&g, |v| vals[v]);
+
+            for v in 1..=n {
+                if !lct.contains(v) {
+                    lct.insert(v, vals[v]);
+                }
+            }
+
+            let mut edges = g
+                .edges()
+                .filter(|&(u, v, _)| u < v)
+                .map(|(u, v, _)| (u, v))
+                .collect_vec();
+
+            let lct = std::cell::RefCell::new(lct);
+
+            random_forest_ops(
+                n,
+                50,
+                &mut edges,
+                |u, v| lct.borrow_mut().link(u, v),
+                |u, v| lct.borrow_mut().cut(u, v),
+            );
+
+            let mut lct = lct.into_inner();
+
+            /* verify against LCADP */
+
+            let g = Graph::from_undirected_iter(
+                edges.iter().map(|&(u, v)| (u, v, 1)),
+            );
+
+            for comp in g.components() {
+                let root = comp[random_range!(0..comp.len())];
+                let lcadp = LCADP::new(&g, root);
+
+                // brute force parent
+                let mut p = HashMap::new();
+                let mut stack = vec![root];
+
+                while let Some(x) = stack.pop() {
+                    for &y in g.e.0[&x].iter() {
+                        if p.get(&x) != Some(&y) {
+                            p.insert(y, x);
+                            stack.push(y);
+                        }
+                    }
+                }
+
+                for _ in 0..20 {
+                    let u = comp[random_range!(0..comp.len())];
+                    let v = comp[random_range!(0..comp.len())];
+                    let lca = lcadp.query(u, v);
+
+                    // query_path changes the root
+                    lct.evert(root);
+
+                    assert!(lct.connected(u, v));
+                    assert_eq!(lct.find_root(u), root);
+                    assert_eq!(lct.lca(u, v), Some(lca));
+
+                    // path u-v = u-lca + v-lca - lca
+                    let path_sum = |mut x: usize| {
+                        let mut sum = 0;
+
+                        while x != lca {
+                            sum += vals[x];
+                            x = p[&x];
+                        }
+
+                        sum
+                    };
+
+                    let expect = path_sum(u) + path_sum(v) + vals[lca];
+
+                    assert_eq!(lct.query_path(u, v), Some(expect));
+                }
+            }
+
+            let comps = g.components();
+
+            for u in 1..=n {
+                for v in 1..=n {
+                    let expect = u == v
+                        || comps
+                            .iter()
+                            .any(|c| c.contains(&u) && c.contains(&v));
+
+                    assert_eq!(lct.connected(u, v), expect);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ett() {
+        let mut rng = thread_rng();
+
+        for _ in 0..20 {
+            let n = random_range!(2..80);
+            let k = random_range!(1..=n / 2);
+            let g = gen_random_forest(n, k, 1..10, &mut rng);
+
+            let mut ett = EulerTourTree::from_graph(&g);
+
+            for v in 1..=n {
+                ett.insert(v);
+            }
+
+            let mut edges = g
+                .edges()
+                .filter(|&(u, v, _)| u < v)
+                .map(|(u, v, _)| (u, v))
+                .collect_vec();
+
+            let ett = std::cell::RefCell::new(ett);
+
+            random_forest_ops(
+                n,
+                100,
+                &mut edges,
+                |u, v| ett.borrow_mut().link(u, v),
+                |u, v| ett.borrow_mut().cut(u, v),
+            );
+
+            let ett = ett.into_inner();
+
+            /* verify against Graph::components */
+
+            let g = Graph::from_undirected_iter(
+                edges.iter().map(|&(u, v)| (u, v, 1)),
+            );
+            let comps = g.components();
+
+            for v in 1..=n {
+                let expect = comps
+                    .iter()
+                    .find(|c| c.contains(&v))
+                    .cloned()
+                    .unwrap_or(vec![v]);
+
+                assert_eq!(ett.component_size(v), expect.len());
+                assert_eq!(
+                    ett.component(v).into_iter().sorted().collect_vec(),
+                    expect
+                );
+
+                for u in 1..=n {
+                    assert_eq!(ett.connected(u, v), expect.contains(&u));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lca_randomdata() {
+        let mut rng = thread_rng();
+
+        for _ in 0..20 {
+            let n = random_range!(2..300);
+            let g = gen_random_tree(n, 1..10, &mut rng);
+            let root = random_range!(1..=n);
+
+            let lcadp = LCADP::new(&g, root);
+            let lcadp_nr = LCADP::new_nr(&g, root);
+            let lca_rmq = LCARMQ::new(&g, root);
+
+            let q = (0..200)
+                .map(|_| (random_range!(1..=n), random_range!(1..=n)))
+                .collect_vec();
+            let expect = LCATarjan::new(&g, root).queries(&q);
+
+            for ((u, v), expect) in q.into_iter().zip(expect) {
+                assert_eq!(lcadp.query(u, v), expect);
+                assert_eq!(lcadp_nr.query(u, v), expect);
+                assert_eq!(lca_rmq.query(u, v), expect);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lcadp_query_last_jump() {
+        /*
+            1--2--3--4--5--6
+             \
+              7--8--9--10--11
+         */
+        let g = Graph::from_undirected_iter(
+            [(1, 2), (2, 3), (3, 4), (4, 5), (5, 6)]
+                .into_iter()
+                .chain([(1, 7), (7, 8), (8, 9), (9, 10), (10, 11)])
+                .map(|(u, v)| (u, v, 1)),
+        );
+
+        for lcadp in [LCADP::new(&g, 1), LCADP::new_nr(&g, 1)] {
+            // 2^0 jump is needed after lifting to the same depth
+            assert_eq!(lcadp.query(3, 8), 1);
+            // lifted 2^2 to depth 1, then 2^1 is over the depth
+            assert_eq!(lcadp.query(6, 11), 1);
+            assert_eq!(lcadp.query(5, 10), 1);
+            assert_eq!(lcadp.query(6, 4), 4);
+        }
+    }
+
+    #[test]
+    fn test_lcadp_nr_chain() {
+        let n = 1_000_000;
+        let g = gen_chain(n, false, 1..2, &mut thread_rng());
+
+        let lcadp = LCADP::new_nr(&g, n / 2);
+
+        assert_eq!(lcadp.query(1, n), n / 2);
+        assert_eq!(lcadp.query(n, n - 1), n - 1);
+        assert_eq!(lcadp.query(3, 7), 7);
+        assert_eq!(lcadp.query(n / 2 + 1, n / 2 - 1), n / 2);
+    }
 }
@@ -209,6 +209,7 @@ where
         self.swap(0, self.raw.len() - 1);
 
         let (i, v) = self.raw.pop().unwrap();
+        self.index.remove(&i);
 
         self.sift_down(0);
 
@@ -376,6 +377,17 @@ mod tests {
         assert_eq!(heap.pop().unwrap(), 2);
         assert_eq!(heap.pop().unwrap(), 4);
         assert_eq!(heap.pop(), None);
+
+        /* popped index can be pushed again */
+
+        heap.insert(1, 3);
+        heap.insert(2, 5);
+        assert_eq!(heap.pop_item(), Some((1, 3)));
+        assert_eq!(heap.get(&1), None);
+
+        heap.insert(1, 7);
+        assert_eq!(heap.pop_item(), Some((2, 5)));
+        assert_eq!(heap.pop_item(), Some((1, 7)));
     }
 
     #[test]
@@ -0,0 +1,502 @@
+//! Persistent AVL (path copying)
+//!
+//! Nodes are immutable once built, insert/remove only copy the search path
+
+
+use std::{
+    borrow::Borrow,
+    cmp::{max, Ordering::*},
+    ops::Index,
+};
+
+use coll::*;
+
+
+impl_node!(pub <K, V>, arc);
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Macros
+
+macro_rules! left {
+    ($x:expr) => {
+        &attr!(self_unsafe_sync | $x).left
+    };
+}
+
+
+macro_rules! right {
+    ($x:expr) => {
+        &attr!(self_unsafe_sync | $x).right
+    };
+}
+
+
+macro_rules! key {
+    ($x:expr) => {
+        &attr!(self_unsafe_sync | $x).key
+    };
+}
+
+
+macro_rules! val {
+    ($x:expr) => {
+        &attr!(self_unsafe_sync | $x).val
+    };
+}
+
+
+macro_rules! height {
+    ($x:expr) => {
+        attr!(self_unsafe_sync | $x).height
+    };
+}
+
+
+/// New node from (left, key, val, right)
+macro_rules! node {
+    ($l:expr, $k:expr, $v:expr, $r:expr) => {{
+        let left = $l;
+        let right = $r;
+        let height = 1 + max(left.height(), right.height());
+
+        aux_node!({
+            left: left,
+            right: right,
+            key: $k,
+            val: $v,
+            height: height
+        })
+    }};
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Structures
+
+#[derive(Clone)]
+pub struct PAVL<K, V> {
+    cnt: usize,
+    root: Node<K, V>,
+}
+
+
+struct Node_<K, V> {
+    left: Node<K, V>,
+    right: Node<K, V>,
+    key: K,
+    val: V,
+    height: i32,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Implementations
+
+impl<K: Ord, V> PAVL<K, V> {
+    ////////////////////////////////////////////////////////////////////////////
+    //// Public API
+
+    pub fn new() -> Self {
+        Self {
+            cnt: 0,
+            root: Node::none(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cnt
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cnt == 0
+    }
+
+    pub fn get<Q>(&self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut x = &self.root;
+
+        while x.is_some() {
+            match k.cmp(key!(x).borrow()) {
+                Less => x = left!(x),
+                Equal => return Some(val!(x)),
+                Greater => x = right!(x),
+            }
+        }
+
+        None
+    }
+
+    /// Insert or update
+    pub fn insert(&self, k: K, v: V) -> Self
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let (root, is_new) = insert_at(&self.root, k, v);
+
+        Self {
+            cnt: if is_new { self.cnt + 1 } else { self.cnt },
+            root,
+        }
+    }
+
+    /// Return a shared copy if k doesn't exist
+    pub fn remove<Q>(&self, k: &Q) -> Self
+    where
+        K: Borrow<Q> + Clone,
+        Q: Ord + ?Sized,
+        V: Clone,
+    {
+        match remove_at(&self.root, k) {
+            Some(root) => Self {
+                cnt: self.cnt - 1,
+                root,
+            },
+            None => self.clone(),
+        }
+    }
+
+    /// In-order iterator
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        let mut stack = vec![];
+        let mut x = &self.root;
+
+        std::iter::from_fn(move || {
+            while x.is_some() {
+                stack.push(x);
+                x = left!(x);
+            }
+
+            let top = stack.pop()?;
+
+            x = right!(top);
+
+            Some((key!(top), val!(top)))
+        })
+    }
+
+    ////////////////////////////////////////////////////////////////////////////
+    //// Assistant Method
+
+    #[cfg(test)]
+    fn validate(&self) {
+        assert_eq!(self.root.validate(), self.cnt);
+    }
+}
+
+
+impl<K, V, Q> Index<&Q> for PAVL<K, V>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    fn index(&self, k: &Q) -> &Self::Output {
+        self.get(k).expect("no entry found for key")
+    }
+}
+
+
+impl<K, V> Node<K, V> {
+    fn height(&self) -> i32 {
+        if self.is_none() {
+            0
+        } else {
+            height!(self)
+        }
+    }
+
+    /// Return nodes count
+    #[cfg(test)]
+    fn validate(&self) -> usize
+    where
+        K: Ord,
+    {
+        if self.is_none() {
+            return 0;
+        }
+
+        let l = left!(self);
+        let r = right!(self);
+
+        if l.is_some() {
+            assert!(*key!(l) < *key!(self), "[validate] bad order");
+        }
+
+        if r.is_some() {
+            assert!(*key!(r) > *key!(self), "[validate] bad order");
+        }
+
+        assert!((l.height() - r.height()).abs() < 2, "[validate] bad bf");
+        assert_eq!(
+            height!(self),
+            1 + max(l.height(), r.height()),
+            "[validate] bad height"
+        );
+
+        1 + l.validate() + r.validate()
+    }
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//// Functions
+
+/// Build (l, k, v, r) and rebalance it, |h(l) - h(r)| <= 2
+fn balance<K: Clone, V: Clone>(
+    l: Node<K, V>,
+    k: K,
+    v: V,
+    r: Node<K, V>,
+) -> Node<K, V> {
+    let hl = l.height();
+    let hr = r.height();
+
+    if hl > hr + 1 {
+        let (ll, lr) = (left!(l), right!(l));
+
+        if ll.height() >= lr.height() {
+            node!(
+                ll.clone(),
+                key!(l).clone(),
+                val!(l).clone(),
+                node!(lr.clone(), k, v, r)
+            )
+        } else {
+            node!(
+                node!(
+                    ll.clone(),
+                    key!(l).clone(),
+                    val!(l).clone(),
+                    left!(lr).clone()
+                ),
+                key!(lr).clone(),
+                val!(lr).clone(),
+                node!(right!(lr).clone(), k, v, r)
+            )
+        }
+    } else if hr > hl + 1 {
+        let (rl, rr) = (left!(r), right!(r));
+
+        if rr.height() >= rl.height() {
+            node!(
+                node!(l, k, v, rl.clone()),
+                key!(r).clone(),
+                val!(r).clone(),
+                rr.clone()
+            )
+        } else {
+            node!(
+                node!(l, k, v, left!(rl).clone()),
+                key!(rl).clone(),
+                val!(rl).clone(),
+                node!(
+                    right!(rl).clone(),
+                    key!(r).clone(),
+                    val!(r).clone(),
+                    rr.clone()
+                )
+            )
+        }
+    } else {
+        node!(l, k, v, r)
+    }
+}
+
+
+/// Return (new root, is new key)
+fn insert_at<K: Ord + Clone, V: Clone>(
+    x: &Node<K, V>,
+    k: K,
+    v: V,
+) -> (Node<K, V>, bool) {
+    if x.is_none() {
+        return (node!(Node::none(), k, v, Node::none()), true);
+    }
+
+    match k.cmp(key!(x)) {
+        Less => {
+            let (l, is_new) = insert_at(left!(x), k, v);
+
+            (
+                balance(l, key!(x).clone(), val!(x).clone(), right!(x).clone()),
+                is_new,
+            )
+        }
+        Equal => (node!(left!(x).clone(), k, v, right!(x).clone()), false),
+        Greater => {
+            let (r, is_new) = insert_at(right!(x), k, v);
+
+            (
+                balance(left!(x).clone(), key!(x).clone(), val!(x).clone(), r),
+                is_new,
+            )
+        }
+    }
+}
+
+
+/// Return new root or None if k doesn't exist
+fn remove_at<K, V, Q>(x: &Node<K, V>, k: &Q) -> Option<Node<K, V>>
+where
+    K: Borrow<Q> + Clone,
+    V: Clone,
+    Q: Ord + ?Sized,
+{
+    if x.is_none() {
+        return None;
+    }
+
+    Some(match k.cmp(key!(x).borrow()) {
+        Less => balance(
+            remove_at(left!(x), k)?,
+            key!(x).clone(),
+            val!(x).clone(),
+            right!(x).clone(),
+        ),
+        Equal => {
+            if left!(x).is_none() {
+                right!(x).clone()
+            } else if right!(x).is_none() {
+                left!(x).clone()
+            } else {
+                let (sk, sv, r) = remove_min(right!(x));
+
+                balance(left!(x).clone(), sk, sv, r)
+            }
+        }
+        Greater => balance(
+            left!(x).clone(),
+            key!(x).clone(),
+            val!(x).clone(),
+            remove_at(right!(x), k)?,
+        ),
+    })
+}
+
+
+/// Return (min key, min val, rest root)
+fn remove_min<K: Clone, V: Clone>(x: &Node<K, V>) -> (K, V, Node<K, V>) {
+    debug_assert!(x.is_some());
+
+    if left!(x).is_none() {
+        return (key!(x).clone(), val!(x).clone(), right!(x).clone());
+    }
+
+    let (k, v, l) = remove_min(left!(x));
+
+    (
+        k,
+        v,
+        balance(l, key!(x).clone(), val!(x).clone(), right!(x).clone()),
+    )
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::{collections::BTreeMap, thread};
+
+    use common::random;
+
+    use super::*;
+
+    #[test]
+    fn test_pavl_case_1() {
+        let mut dict = PAVL::new();
+
+        for i in 0..30 {
+            dict = dict.insert(i, i);
+        }
+
+        let old = dict.clone();
+
+        for i in 0..10 {
+            dict = dict.remove(&i);
+        }
+
+        dict.validate();
+        old.validate();
+
+        assert_eq!(dict.len(), 20);
+        assert_eq!(old.len(), 30);
+        assert_eq!(dict.get(&5), None);
+        assert_eq!(old[&5], 5);
+    }
+
+    #[test]
+    fn test_pavl_random() {
+        let batch_num = 1_000;
+
+        let mut dict = PAVL::new();
+        let mut bt = BTreeMap::new();
+
+        let mut versions = vec![];
+
+        for _ in 0..batch_num {
+            let k = random::<u16>() % 500;
+            let v = random::<u64>();
+
+            if random::<u8>() % 3 == 0 {
+                dict = dict.remove(&k);
+                bt.remove(&k);
+            } else {
+                dict = dict.insert(k, v);
+                bt.insert(k, v);
+            }
+
+            dict.validate();
+
+            versions.push((dict.clone(), bt.clone()));
+        }
+
+        /* old versions keep unchanged */
+
+        for (dict, bt) in versions {
+            assert_eq!(dict.len(), bt.len());
+            assert!(dict.iter().eq(bt.iter()));
+        }
+    }
+
+    #[test]
+    fn test_pavl_sync() {
+        let mut dict = PAVL::new();
+
+        for i in 0..1000 {
+            dict = dict.insert(i, i);
+        }
+
+        thread::scope(|s| {
+            for t in 0..10 {
+                let dict = &dict;
+
+                s.spawn(move || {
+                    let mut new_dict = dict.clone();
+
+                    for i in (t..1000).step_by(10) {
+                        new_dict = new_dict.remove(&i).insert(i + 1000, t);
+                    }
+
+                    new_dict.validate();
+
+                    assert_eq!(new_dict.len(), 1000);
+                    assert_eq!(new_dict.get(&t), None);
+                    assert_eq!(new_dict.get(&(t + 1000)), Some(&t));
+                });
+            }
+        });
+
+        dict.validate();
+
+        assert!(dict.iter().map(|(k, _)| *k).eq(0..1000));
+    }
+}
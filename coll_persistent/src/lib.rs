@@ -7,5 +7,6 @@
 #[cfg(test)]
 mod vec;
 
+pub mod avl;
 pub mod trievec;
 pub mod trievec2;
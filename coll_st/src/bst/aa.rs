@@ -38,7 +38,7 @@ impl<K: Ord, V> AA<K, V> {
     /// Public API
 
     pub fn new() -> Self {
-        Self { root: Node::none() }
+        Self { root: Node::none(), cnt: 0 }
     }
 
     pub fn insert(&mut self, k: K, v: V) -> Option<V> {
@@ -46,6 +46,10 @@ impl<K: Ord, V> AA<K, V> {
 
         self.root = root;
 
+        if popped.is_none() {
+            self.cnt += 1;
+        }
+
         popped
     }
 
@@ -59,6 +63,10 @@ impl<K: Ord, V> AA<K, V> {
 
         self.root = root;
 
+        if popped.is_some() {
+            self.cnt -= 1;
+        }
+
         popped.map(|it| unwrap_into!(it).into_value())
     }
 
@@ -335,4 +343,11 @@ mod tests {
     fn test_bst_aa_random() {
         test_dict!(AA::new());
     }
+
+    impl_bpt_mapping!(AA, AA::new());
+
+    #[test]
+    fn test_bst_aa_bpt_mapping() {
+        test_bpt_mapping!(AA);
+    }
 }
@@ -0,0 +1,282 @@
+//! Range Aggregate Map
+//!
+//! AVL augmented by monoid sum of the values in subtree
+
+
+use std::{
+    borrow::Borrow,
+    fmt::Debug,
+    iter::Sum,
+    ops::{Add, Bound::{self, *}, RangeBounds},
+};
+
+use super::{*, avl::impl_aug_avl};
+
+
+def_attr_macro!(ref | (agg, V));
+
+
+impl_node!();
+impl_node_!({ height: i32, size: usize, agg: V });
+def_tree!(
+    /// Ordered map with range aggregate query, value is read-only
+    AggMap {}
+);
+impl_tree_debug!(AggMap);
+impl_tree_iter!(ro | AggMap where V: Monoid);
+impl_size!([K, V: Monoid]
+    fn augment(&self) {
+        agg!(self, self.calc_agg());
+    }
+);
+impl_order_stat!(AggMap);
+impl_aug_avl!(AggMap [K: Ord, V: Monoid] |k, v| {
+    let agg = v.clone();
+
+    node!({ k, v, height: 1, size: 1, agg: agg })
+});
+
+impl_rotate_cleanup!(AggMap ->
+    fn rotate_cleanup(&self, x: Node<K, V>, z: Node<K, V>)
+    where V: Monoid
+    {
+        x.update_height();
+        z.update_height();
+
+        x.update_size();
+        z.update_size();
+    }
+);
+impl_validate!(AggMap ->
+    #[cfg(test)]
+    fn validate(&self)
+    where V: Monoid + PartialEq + Debug
+    {
+        self.root.recalc_height();
+        self.root.validate_bf();
+        self.root.validate_size();
+        self.root.validate_agg();
+    }
+);
+
+
+/// Aggregation monoid: `Sum` of nothing is the identity and `&a + &b` is the
+/// (associative) combine, the same requirement as `coll::segment_tree`
+pub trait Monoid: Clone {
+    fn zero() -> Self;
+
+    fn combine(&self, rhs: &Self) -> Self;
+}
+
+
+impl<T> Monoid for T
+where
+    T: Sum + Clone,
+    for<'a> &'a T: Add<&'a T, Output = T>,
+{
+    fn zero() -> Self {
+        [].into_iter().sum()
+    }
+
+    fn combine(&self, rhs: &Self) -> Self {
+        self + rhs
+    }
+}
+
+
+impl<K: Ord, V> AggMap<K, V> {
+
+    ////////////////////////////////////////////////////////////////////////////
+    /// Public API
+
+    pub fn new() -> Self {
+        Self {
+            root: Node::none(),
+            cnt: 0
+        }
+    }
+
+
+    /// No get_mut, value changes should go through insert
+    pub fn get<Q>(&self, k: &Q) -> Option<&V>
+    where K: Borrow<Q>, Q: Ord + ?Sized
+    {
+        let x = bst_search!(self.root, k);
+
+        if x.is_some() {
+            Some(val!(x))
+        }
+        else {
+            None
+        }
+    }
+
+
+    /// Aggregate values of keys in the range (in key order), O(log n)
+    pub fn query<Q, R>(&self, range: R) -> V
+    where K: Borrow<Q>, Q: Ord + ?Sized, R: RangeBounds<Q>, V: Monoid
+    {
+        Self::query_at(&self.root, range.start_bound(), range.end_bound())
+    }
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //// Helper Method
+
+    /// Split on the first node in range, then each side has an open end and
+    /// only walks down one path
+    fn query_at<Q>(x: &Node<K, V>, start: Bound<&Q>, end: Bound<&Q>) -> V
+    where K: Borrow<Q>, Q: Ord + ?Sized, V: Monoid
+    {
+        if x.is_none() {
+            return V::zero();
+        }
+
+        if let (Unbounded, Unbounded) = (start, end) {
+            return agg!(x).clone();
+        }
+
+        let k: &Q = key!(x).borrow();
+
+        let after_start = match start {
+            Included(start) => k >= start,
+            Excluded(start) => k > start,
+            Unbounded => true,
+        };
+
+        if !after_start {
+            return Self::query_at(&right!(x), start, end);
+        }
+
+        let before_end = match end {
+            Included(end) => k <= end,
+            Excluded(end) => k < end,
+            Unbounded => true,
+        };
+
+        if !before_end {
+            return Self::query_at(&left!(x), start, end);
+        }
+
+        Self::query_at(&left!(x), start, Unbounded)
+            .combine(val!(x))
+            .combine(&Self::query_at(&right!(x), Unbounded, end))
+    }
+}
+
+
+impl<K, V: Monoid> Node<K, V> {
+    /// left.agg + val + right.agg (in key order)
+    fn calc_agg(&self) -> V {
+        let mut agg = if left!(self).is_some() {
+            agg!(left!(self)).combine(val!(self))
+        }
+        else {
+            val!(self).clone()
+        };
+
+        if right!(self).is_some() {
+            agg = agg.combine(agg!(right!(self)));
+        }
+
+        agg
+    }
+
+
+    #[cfg(test)]
+    fn validate_agg(&self)
+    where V: PartialEq + Debug
+    {
+        if self.is_some() {
+            left!(self).validate_agg();
+            right!(self).validate_agg();
+
+            assert_eq!(
+                agg!(self),
+                &self.calc_agg(),
+                "[validate] bad aggregate"
+            );
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use common::random;
+
+    use super::*;
+
+
+    /// Non-commutative monoid, check the combine order
+    #[derive(Default, Clone, PartialEq, Eq, Debug)]
+    struct Seq(Vec<i32>);
+
+    impl Sum for Seq {
+        fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+            Self(iter.flat_map(|x| x.0).collect())
+        }
+    }
+
+    impl<'a> Add for &'a Seq {
+        type Output = Seq;
+
+        fn add(self, rhs: Self) -> Self::Output {
+            Seq([&self.0[..], &rhs.0[..]].concat())
+        }
+    }
+
+
+    #[test]
+    fn test_bst_aggmap_query() {
+        let mut dict = AggMap::<i32, i64>::new();
+        let mut seq = AggMap::<i32, Seq>::new();
+        let mut bt = BTreeMap::new();
+
+        assert_eq!(dict.query(..), 0);
+
+        for _ in 0..2000 {
+            let k = random::<i32>() % 200;
+            let v = random::<i64>() % 1000;
+
+            if random::<u8>() % 3 == 0 {
+                assert_eq!(dict.remove(&k), bt.remove(&k));
+                seq.remove(&k);
+            }
+            else {
+                assert_eq!(dict.insert(k, v), bt.insert(k, v));
+                seq.insert(k, Seq(vec![k]));
+            }
+
+            dict.validate();
+            seq.validate();
+
+            assert_eq!(dict.len(), bt.len());
+            assert_eq!(dict.get(&k), bt.get(&k));
+
+            let mut lo = random::<i32>() % 220;
+            let mut hi = random::<i32>() % 220;
+
+            if lo > hi {
+                (lo, hi) = (hi, lo);
+            }
+
+            assert_eq!(dict.query(lo..hi), bt.range(lo..hi).map(|x| x.1).sum());
+            assert_eq!(
+                dict.query(lo..=hi),
+                bt.range(lo..=hi).map(|x| x.1).sum()
+            );
+            assert_eq!(dict.query(..hi), bt.range(..hi).map(|x| x.1).sum());
+            assert_eq!(dict.query(lo..), bt.range(lo..).map(|x| x.1).sum());
+            assert_eq!(dict.query(..), bt.values().sum());
+
+            assert_eq!(
+                seq.query(lo..=hi).0,
+                bt.range(lo..=hi).map(|x| *x.0).collect::<Vec<_>>()
+            );
+        }
+    }
+}
@@ -1,25 +1,257 @@
-use std::{cmp::max, borrow::Borrow, fmt::Debug};
+use std::cmp::min;
 
 use super::*;
 
 
+/// AVL insert/remove/retracing, for AVL itself and the augmented trees
+///
+/// `[generics] |k, v| leaf`: impl generics (with augment bounds) and how to
+/// make a new leaf node
+macro_rules! impl_aug_avl {
+    ($treename:ident [$($g:tt)*] |$k:ident, $v:ident| $leaf:expr) => {
+        impl<$($g)*> $treename<K, V> {
+            pub fn insert(&mut self, $k: K, $v: V) -> Option<V>
+            where V: Default
+            {
+                use std::borrow::Borrow;
+
+                let x = bst_search!(self.root, &$k);
+
+                if x.is_some() {
+                    let old = replace_val!(x, $v);
+
+                    /* value changed, re-evaluate augmentation */
+                    x.update_size_to_root();
+
+                    return Some(old);
+                }
+
+                let z = $leaf;
+
+                let popped: Option<V> = bst_insert!(self, z.clone());
+                debug_assert!(popped.is_none());
+
+                z.update_size_to_root();
+                self.retracing(z);
+
+                None
+            }
+
+
+            pub fn remove<Q>(&mut self, k: &Q) -> Option<V>
+            where
+                K: std::borrow::Borrow<Q> + std::fmt::Debug,
+                Q: Ord + ?Sized,
+                V: std::fmt::Debug
+            {
+                let z = bst_search!(self.root, k);
+
+                if z.is_none() {
+                    None
+                }
+                else {
+                    let retracing_entry;
+
+                    if left!(z).is_none() {
+                        retracing_entry = paren!(z).upgrade();
+                        subtree_shift!(self, z, right!(z));
+                    }
+                    else if right!(z).is_none() {
+                        retracing_entry = paren!(z).upgrade();
+                        subtree_shift!(self, z, left!(z));
+                    }
+                    else {
+                        /* case-1       case-2
+
+                             z            z
+                              \            \
+                               y            z.right
+                                           /
+                                          / (left-most)
+                                         y
+                                          \
+                                          y.right
+                        */
+
+                        let y = bst_successor!(z);
+
+                        if right!(z).rc_eq(&y) {
+                            // just ok
+                            retracing_entry = y.clone();
+                        } else {
+                            debug_assert!(y.is_some());
+                            retracing_entry = paren!(y).upgrade();
+
+                            // replace y with y.right
+                            subtree_shift!(self, y, right!(y));
+
+                            // connect z.right to y.right
+                            conn_right!(y, right!(z));
+                        }
+
+                        subtree_shift!(self, z, y);
+                        conn_left!(y, left!(z));
+                    }
+
+                    retracing_entry.update_size_to_root();
+                    self.retracing(retracing_entry);
+                    self.cnt -= 1;
+
+                    Some(unwrap_into!(z).into_value())
+                }
+            }
+
+
+            /// Bottom up fixing
+            fn retracing(&mut self, ent: Node<K, V>)
+            {
+                let mut p = ent;
+
+                while p.is_some() {
+                    p.update_height();
+
+                    if p.bf().abs() > 1 {
+                        let high =
+                        if right!(p).height() > left!(p).height() {
+                            Right
+                        }
+                        else {
+                            Left
+                        };
+
+                        let z = child!(p, high);
+
+                        if child!(z, high).height()
+                            >= child!(z, high.rev()).height()
+                        {
+                            p = rotate!(self, p, high.rev());
+                        }
+                        else {
+                            p = double_rotate!(self, p, high.rev());
+                        }
+                    }
+
+                    p = paren!(p).upgrade();
+                }
+
+            }
+        }
+
+
+        impl<K, V> Node<K, V> {
+
+            fn update_height(&self) {
+                if self.is_some() {
+                    height!(
+                        self,
+                        1 + std::cmp::max(
+                            left!(self).height(),
+                            right!(self).height()
+                        )
+                    );
+                }
+            }
+
+            ////////////////////////////////////////////////////////////////////
+            /// Static Stats
+
+            fn height(&self) -> i32 {
+                if self.is_none() {
+                    0
+                }
+                else {
+                    height!(self)
+                }
+            }
+
+            fn bf(&self) -> i32 {
+                if self.is_none() {
+                    0
+                }
+                else {
+                    right!(self).height() - left!(self).height()
+                }
+            }
+
+
+            ////////////////////////////////////////////////////////////////////
+            /// Validation Helper
+
+            /// Recursively validate BF:
+            ///
+            /// BF(X): H(right(X)) - H(left(X))
+            ///
+            /// BF(X) in {-1, 0, 1}
+            ///
+            #[cfg(test)]
+            fn validate_bf(&self) {
+                assert!(
+                    self.bf().abs() < 2
+                );
+
+                if self.is_some() {
+                    left!(self).validate_bf();
+                    right!(self).validate_bf();
+                }
+            }
+
+            /// Recursively calculate height stats in time instead of using
+            /// static height
+            #[cfg(test)]
+            fn recalc_height(&self) {
+                if self.is_some() {
+                    left!(self).recalc_height();
+                    right!(self).recalc_height();
+
+                    self.update_height();
+                }
+            }
+        }
+
+
+        impl<K: std::fmt::Debug, V> std::fmt::Debug for Node<K, V> {
+            fn fmt(
+                &self,
+                f: &mut std::fmt::Formatter<'_>,
+            ) -> std::fmt::Result {
+                if self.is_some() {
+                    write!(f, "{:?}(h: {})", key!(self), height!(self))
+                }
+                else {
+                    write!(f, "nil")
+                }
+            }
+        }
+    };
+}
+pub(crate) use impl_aug_avl;
+
+
 
 impl_node!();
-impl_node_!({ height: i32 });
+impl_node_!({ height: i32, size: usize });
 impl_tree!(AVL {});
+impl_size!();
+impl_aug_avl!(AVL [K: Ord, V] |k, v| node!({ k, v, height: 1, size: 1 }));
+impl_order_stat!(AVL);
+impl_split_join!(AVL {});
 
 impl_rotate_cleanup!(AVL ->
     fn rotate_cleanup(&self, x: Node<K, V>, z: Node<K, V>) {
         /* update height */
         x.update_height();
         z.update_height();
+
+        x.update_size();
+        z.update_size();
     }
 );
 impl_validate!(AVL ->
     #[cfg(test)]
-    fn validate(&mut self) {
+    fn validate(&self) {
         self.root.recalc_height();
         self.root.validate_bf();
+        self.root.validate_size();
     }
 );
 
@@ -31,81 +263,8 @@ impl<K: Ord, V> AVL<K, V> {
 
     pub fn new() -> Self {
         Self {
-            root: Node::none()
-        }
-    }
-
-
-    pub fn insert(&mut self, k: K, v: V) -> Option<V>
-    where V: Default
-    {
-        let z = node!({ k, v, height: 1 });
-
-        let popped = bst_insert!(self, z.clone());
-
-        // self.insert_retracing(z);
-        self.retracing(z);
-
-        popped
-    }
-
-
-    pub fn remove<Q>(&mut self, k: &Q) -> Option<V>
-    where K: Borrow<Q> + Debug, Q: Ord + ?Sized, V: Debug
-    {
-
-        let z = bst_search!(self.root, k);
-
-        if z.is_none() {
-            None
-        }
-        else {
-            let retracing_entry;
-
-            if left!(z).is_none() {
-                retracing_entry = paren!(z).upgrade();
-                subtree_shift!(self, z, right!(z));
-            }
-            else if right!(z).is_none() {
-                retracing_entry = paren!(z).upgrade();
-                subtree_shift!(self, z, left!(z));
-            }
-            else {
-                /* case-1       case-2
-
-                     z            z
-                      \            \
-                       y            z.right
-                                   /
-                                  / (left-most)
-                                 y
-                                  \
-                                  y.right
-                */
-
-                let y = bst_successor!(z);
-
-                if right!(z).rc_eq(&y) {
-                    // just ok
-                    retracing_entry = y.clone();
-                } else {
-                    debug_assert!(y.is_some());
-                    retracing_entry = paren!(y).upgrade();
-
-                    // replace y with y.right
-                    subtree_shift!(self, y, right!(y));
-
-                    // connect z.right to y.right
-                    conn_right!(y, right!(z));
-                }
-
-                subtree_shift!(self, z, y);
-                conn_left!(y, left!(z));
-            }
-
-            self.retracing(retracing_entry);
-
-            Some(unwrap_into!(z).into_value())
+            root: Node::none(),
+            cnt: 0
         }
     }
 
@@ -150,120 +309,71 @@ impl<K: Ord, V> AVL<K, V> {
     }
 
 
-    /// Bottom up fixing
-    fn retracing(&mut self, ent: Node<K, V>)
-    {
-        let mut p = ent;
-
-        while p.is_some() {
-            p.update_height();
-
-            if p.bf().abs() > 1 {
-                let high =
-                if right!(p).height() > left!(p).height() {
-                    Right
-                }
-                else {
-                    Left
-                };
-
-                let z = child!(p, high);
-
-                if child!(z, high).height() >= child!(z, high.rev()).height() {
-                    p = rotate!(self, p, high.rev());
-                }
-                else {
-                    p = double_rotate!(self, p, high.rev());
-                }
-            }
-
-            p = paren!(p).upgrade();
+    /// Join (l, m, r) where l < m < r, return new root
+    ///
+    /// O(|h(l) - h(r)|)
+    fn join3(
+        &mut self,
+        l: Node<K, V>,
+        m: Node<K, V>,
+        r: Node<K, V>,
+    ) -> Node<K, V> {
+        let hl = l.height();
+        let hr = r.height();
+
+        if (hl - hr).abs() <= 1 {
+            conn_left!(m, l);
+            conn_right!(m, r);
+
+            m.update_height();
+            m.update_size();
+
+            return m;
         }
 
-    }
-
-}
+        /* descend the spine of the higher one until height <= h(low) + 1 */
 
+        let (high, low, dir) =
+            if hl > hr { (l, r, Right) } else { (r, l, Left) };
+        let low_h = min(hl, hr);
 
-impl<K, V> Node<K, V> {
+        // keep high alive as the top before retracing
+        let mut p = high.clone();
+        let mut c = child!(p, dir);
 
-    fn update_height(&self) {
-        if self.is_some() {
-            height!(
-                self,
-                1 + max(
-                    left!(self).height(),
-                    right!(self).height()
-                )
-            );
+        while c.height() > low_h + 1 {
+            p = c;
+            c = child!(p, dir);
         }
-    }
 
-    ////////////////////////////////////////////////////////////////////////////
-    /// Static Stats
+        disconn!(p, c);
 
-    fn height(&self) -> i32 {
-        if self.is_none() {
-            0
+        if dir.is_left() {
+            conn_left!(m, low);
+            conn_right!(m, c);
         }
         else {
-            height!(self)
+            conn_left!(m, c);
+            conn_right!(m, low);
         }
-    }
 
-    fn bf(&self) -> i32 {
-        if self.is_none() {
-            0
-        }
-        else {
-            right!(self).height() - left!(self).height()
-        }
-    }
+        m.update_height();
+        m.update_size();
 
+        conn_child!(p, m, dir);
 
-    ////////////////////////////////////////////////////////////////////////////
-    /// Validation Helper
+        p.update_size_to_root();
+        self.retracing(p);
 
-    /// Recursively validate BF:
-    ///
-    /// BF(X): H(right(X)) - H(left(X))
-    ///
-    /// BF(X) in {-1, 0, 1}
-    ///
-    #[cfg(test)]
-    fn validate_bf(&self) {
-        assert!(
-            self.bf().abs() < 2
-        );
-
-        if self.is_some() {
-            left!(self).validate_bf();
-            right!(self).validate_bf();
-        }
-    }
-
-    /// Recursively calculate height stats in time instead of using static height
-    #[cfg(test)]
-    fn recalc_height(&self) {
-        if self.is_some() {
-            left!(self).recalc_height();
-            right!(self).recalc_height();
+        let mut root = m;
 
-            self.update_height();
+        while paren!(root).is_some() {
+            root = paren!(root).upgrade();
         }
-    }
-}
-
 
-impl<K: Debug, V> Debug for Node<K, V> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.is_some() {
-            write!(f, "{:?}(h: {})", key!(self), height!(self))
-        }
-        else {
-            write!(f, "nil")
-        }
+        root
     }
+
 }
 
 
@@ -299,5 +409,22 @@ mod tests {
     fn test_bst_avl_random() {
         test_dict!(AVL::new());
     }
+
+    impl_bpt_mapping!(AVL, AVL::new());
+
+    #[test]
+    fn test_bst_avl_bpt_mapping() {
+        test_bpt_mapping!(AVL);
+    }
+
+    #[test]
+    fn test_bst_avl_order_stat() {
+        test_order_stat!(AVL::new());
+    }
+
+    #[test]
+    fn test_bst_avl_split_join() {
+        test_split_join!(AVL::new());
+    }
 }
 
@@ -0,0 +1,268 @@
+//! Interval Tree
+//!
+//! AVL augmented by the node with max hi in subtree
+
+
+use std::fmt::Debug;
+
+use super::{*, avl::impl_aug_avl};
+
+
+def_attr_macro!(clone | max_hi);
+
+
+impl_node!();
+impl_node_!({ height: i32, size: usize, max_hi: WeakNode<K, V> });
+impl_tree!(
+    /// Interval map with overlap and stabbing query
+    IntervalTree {}
+    where K: Interval
+);
+impl_size!([K: Interval, V]
+    fn augment(&self) {
+        max_hi!(self, self.calc_max_hi().downgrade());
+    }
+);
+impl_order_stat!(IntervalTree);
+impl_aug_avl!(IntervalTree [K: Interval, V] |k, v| {
+    node!({ k, v, height: 1, size: 1, max_hi: WeakNode::none() })
+});
+
+impl_rotate_cleanup!(IntervalTree ->
+    fn rotate_cleanup(&self, x: Node<K, V>, z: Node<K, V>)
+    where K: Interval
+    {
+        x.update_height();
+        z.update_height();
+
+        x.update_size();
+        z.update_size();
+    }
+);
+impl_validate!(IntervalTree ->
+    #[cfg(test)]
+    fn validate(&self)
+    where K: Interval
+    {
+        self.root.recalc_height();
+        self.root.validate_bf();
+        self.root.validate_size();
+        self.root.validate_max_hi();
+    }
+);
+
+
+/// Closed interval [lo, hi], it should be ordered by lo first
+pub trait Interval: Ord {
+    type Endpoint: Ord;
+
+    fn lo(&self) -> &Self::Endpoint;
+
+    fn hi(&self) -> &Self::Endpoint;
+}
+
+
+impl<T: Ord> Interval for (T, T) {
+    type Endpoint = T;
+
+    fn lo(&self) -> &Self::Endpoint {
+        &self.0
+    }
+
+    fn hi(&self) -> &Self::Endpoint {
+        &self.1
+    }
+}
+
+
+impl<K: Ord, V> IntervalTree<K, V> {
+
+    ////////////////////////////////////////////////////////////////////////////
+    /// Public API
+
+    pub fn new() -> Self {
+        Self {
+            root: Node::none(),
+            cnt: 0
+        }
+    }
+}
+
+
+impl<K: Interval, V> IntervalTree<K, V> {
+
+    /// All intervals overlapping [lo, hi] (in key order)
+    ///
+    /// O(min(n, k log n)), k is the size of result
+    pub fn overlap(
+        &self,
+        lo: &K::Endpoint,
+        hi: &K::Endpoint,
+    ) -> Vec<(&K, &V)> {
+        let mut res = vec![];
+
+        Self::overlap_at(&self.root, lo, hi, &mut res);
+
+        res
+    }
+
+
+    /// Any one interval overlapping [lo, hi], O(log n)
+    pub fn overlap_any(
+        &self,
+        lo: &K::Endpoint,
+        hi: &K::Endpoint,
+    ) -> Option<(&K, &V)> {
+        let mut x = self.root.clone();
+
+        while x.is_some() {
+            if key!(x).lo() <= hi && lo <= key!(x).hi() {
+                return Some((key!(x), val!(x)));
+            }
+
+            /* if the left can't overlap, neither can the right */
+
+            if left!(x).is_some()
+                && key!(max_hi!(left!(x)).upgrade()).hi() >= lo
+            {
+                x = left!(x);
+            }
+            else {
+                x = right!(x);
+            }
+        }
+
+        None
+    }
+
+
+    /// All intervals containing p (in key order)
+    pub fn stab(&self, p: &K::Endpoint) -> Vec<(&K, &V)> {
+        self.overlap(p, p)
+    }
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //// Helper Method
+
+    fn overlap_at<'a>(
+        x: &Node<K, V>,
+        lo: &K::Endpoint,
+        hi: &K::Endpoint,
+        res: &mut Vec<(&'a K, &'a V)>,
+    ) {
+        if x.is_none() || key!(max_hi!(x).upgrade()).hi() < lo {
+            return;
+        }
+
+        Self::overlap_at(&left!(x), lo, hi, res);
+
+        // lo of right subtree is not less than lo of x
+        if key!(x).lo() > hi {
+            return;
+        }
+
+        if key!(x).hi() >= lo {
+            res.push((key!(x), val!(x)));
+        }
+
+        Self::overlap_at(&right!(x), lo, hi, res);
+    }
+}
+
+
+impl<K: Interval, V> Node<K, V> {
+    /// argmax hi of (self, left.max_hi, right.max_hi)
+    fn calc_max_hi(&self) -> Self {
+        let mut m = self.clone();
+
+        for c in [left!(self), right!(self)] {
+            if c.is_some() {
+                let cm = max_hi!(c).upgrade();
+
+                if key!(cm).hi() > key!(m).hi() {
+                    m = cm;
+                }
+            }
+        }
+
+        m
+    }
+
+
+    #[cfg(test)]
+    fn validate_max_hi(&self) {
+        if self.is_some() {
+            left!(self).validate_max_hi();
+            right!(self).validate_max_hi();
+
+            let m = max_hi!(self).upgrade();
+
+            // rotation may leave another node with the same hi
+            assert!(m.is_some(), "[validate] dangling max hi");
+            assert!(
+                key!(self.calc_max_hi()).hi() == key!(m).hi(),
+                "[validate] bad max hi"
+            );
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use common::random;
+
+    use super::*;
+
+
+    #[test]
+    fn test_bst_interval_overlap() {
+        let mut dict = IntervalTree::<(i32, i32), usize>::new();
+        let mut bt = BTreeMap::new();
+
+        assert!(dict.overlap_any(&0, &100).is_none());
+
+        for i in 0..2000 {
+            let lo = random::<u16>() as i32 % 500;
+            let hi = lo + random::<u16>() as i32 % 50;
+
+            if random::<u8>() % 3 == 0 {
+                if let Some(&k) = bt.keys().nth(i % (bt.len() + 1)) {
+                    assert_eq!(dict.remove(&k), bt.remove(&k));
+                }
+            }
+            else {
+                assert_eq!(dict.insert((lo, hi), i), bt.insert((lo, hi), i));
+            }
+
+            dict.validate();
+            assert_eq!(dict.len(), bt.len());
+
+            let lo = random::<u16>() as i32 % 550;
+            let hi = lo + random::<u16>() as i32 % 20;
+
+            let expect = bt
+                .iter()
+                .filter(|(k, _)| k.0 <= hi && lo <= k.1)
+                .collect::<Vec<_>>();
+
+            assert_eq!(dict.overlap(&lo, &hi), expect);
+
+            match dict.overlap_any(&lo, &hi) {
+                Some((k, _)) => assert!(k.0 <= hi && lo <= k.1),
+                None => assert!(expect.is_empty()),
+            }
+
+            assert_eq!(
+                dict.stab(&lo),
+                bt.iter()
+                    .filter(|(k, _)| k.0 <= lo && lo <= k.1)
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+}
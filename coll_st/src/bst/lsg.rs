@@ -11,16 +11,17 @@ def_tree!(
     /// Lazy Scapegoat Tree
     LSG
     {
-        cnt: usize,
         /// nodes count including marked
         max_cnt: usize,
         alpha: f32
     }
 );
 impl_tree_debug!(LSG);
+impl_tree_iter!(lazy | LSG);
 
 impl_node!();
 impl_node_!({ size: usize, deleted: bool });
+impl_size!();
 impl_flatten_cleanup!(
     fn flatten_cleanup(&self) {
         if self.is_some() {
@@ -172,24 +173,6 @@ impl<K: Ord, V> LSG <K, V> {
 
 
 impl<K, V> Node<K, V> {
-    fn update_size(&self) {
-        if self.is_some() {
-            size!(
-                self,
-                1 + left!(self).size() + right!(self).size()
-            );
-        }
-    }
-
-    fn size(&self) -> usize {
-        if self.is_some() {
-            size!(self)
-        }
-        else {
-            0
-        }
-    }
-
     /// Loosely alpha-height balanced
     #[cfg(test)]
     #[allow(unused)]
@@ -257,4 +240,11 @@ mod tests {
         test_dict!(LSG::new(0.6));
     }
 
+    impl_bpt_mapping!(LSG, LSG::new(0.7));
+
+    #[test]
+    fn test_bst_lsg_bpt_mapping() {
+        test_bpt_mapping!(LSG);
+    }
+
 }
@@ -1,5 +1,7 @@
 pub mod aa;
+pub mod aggmap;
 pub mod avl;
+pub mod interval;
 pub mod lsg;
 pub mod rb;
 pub mod sg;
@@ -381,6 +383,8 @@ macro_rules! bst_insert {
             }
         }
 
+        $tree.cnt += 1;
+
         None
     }};
 }
@@ -636,6 +640,76 @@ macro_rules! impl_rotate_cleanup {
 }
 
 
+/// Subtree size augmentation, Node_ requires `size: usize`
+///
+/// It's always on for AVL, RB, Treap and Splay rather than optional: besides
+/// rank/nth, split_off/append/set ops (`impl_split_join!` and Splay's
+/// split/merge) take the tree len from the size of subtree, and the augment
+/// hook below rides on it.
+///
+/// Augment hook: `[generics] fn augment(&self)`, recompute extra attrs of
+/// `impl_node_!` from children, it's re-evaluated with size (rotation,
+/// insert/remove path)
+macro_rules! impl_size {
+    () => {
+        impl_size!([K, V] fn augment(&self) {});
+    };
+    ([$($g:tt)*] $augment:item) => {
+        #[allow(unused)]
+        impl<K, V> Node<K, V> {
+            fn size(&self) -> usize {
+                if self.is_some() {
+                    size!(self)
+                }
+                else {
+                    0
+                }
+            }
+
+            #[cfg(test)]
+            fn validate_size(&self) {
+                if self.is_some() {
+                    left!(self).validate_size();
+                    right!(self).validate_size();
+
+                    assert_eq!(
+                        size!(self),
+                        1 + left!(self).size() + right!(self).size(),
+                        "[validate] bad subtree size"
+                    );
+                }
+            }
+        }
+
+        #[allow(unused)]
+        impl<$($g)*> Node<K, V> {
+            fn update_size(&self) {
+                if self.is_some() {
+                    size!(
+                        self,
+                        1 + left!(self).size() + right!(self).size()
+                    );
+
+                    self.augment();
+                }
+            }
+
+            /// Update size from self up to root
+            fn update_size_to_root(&self) {
+                let mut x = self.clone();
+
+                while x.is_some() {
+                    x.update_size();
+                    x = paren!(x).upgrade();
+                }
+            }
+
+            $augment
+        }
+    };
+}
+
+
 macro_rules! impl_validate {
     ($name:ident -> empty) => {
         impl<K, V> $name<K, V> {
@@ -678,6 +752,8 @@ macro_rules! def_tree {
         #[allow(unused)]
         pub struct $treename<K, V> {
             root: Node<K, V>,
+            /// nodes count (exclude marked)
+            cnt: usize,
 
             /* extra attr */
             $(
@@ -777,6 +853,641 @@ macro_rules! impl_tree_debug {
 }
 
 
+/// len, iter, range, first/last, pop_first/pop_last and IntoIterator
+///
+/// lazy: skip nodes marked deleted
+///
+/// where: extra bounds of `remove` (used by pop_first/pop_last)
+///
+/// ro: no mutable iterator (value is augmented)
+macro_rules! impl_tree_iter {
+    (lazy | $treename:ident) => {
+        impl_tree_iter!(@
+            $treename, |x| !deleted!(x), V: std::fmt::Debug + Default
+        );
+        impl_tree_iter!(@mut $treename);
+    };
+    (ro | $treename:ident where $($bound:tt)+) => {
+        impl_tree_iter!(@
+            $treename, |_x| true, V: std::fmt::Debug, $($bound)+
+        );
+    };
+    ($treename:ident where $($bound:tt)+) => {
+        impl_tree_iter!(@
+            $treename, |_x| true, V: std::fmt::Debug, $($bound)+
+        );
+        impl_tree_iter!(@mut $treename);
+    };
+    ($treename:ident) => {
+        impl_tree_iter!(@ $treename, |_x| true, V: std::fmt::Debug);
+        impl_tree_iter!(@mut $treename);
+    };
+    (@mut $treename:ident) => {
+        impl<K: Ord, V> $treename<K, V> {
+            pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+                self.range_mut::<K, _>(..)
+            }
+
+            pub fn range_mut<Q, R>(
+                &mut self,
+                range: R,
+            ) -> impl Iterator<Item = (&K, &mut V)>
+            where
+                K: std::borrow::Borrow<Q>,
+                Q: Ord + ?Sized,
+                R: std::ops::RangeBounds<Q>,
+            {
+                self.inner_range(range).map(|x| (key!(x), val_mut!(x)))
+            }
+        }
+    };
+    (@ $treename:ident, |$x:ident| $alive:expr, $($vbound:tt)+) => {
+        impl<K: Ord, V> $treename<K, V> {
+            pub fn len(&self) -> usize {
+                self.cnt
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.cnt == 0
+            }
+
+            pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+                self.range::<K, _>(..)
+            }
+
+            pub fn keys(&self) -> impl Iterator<Item = &K> {
+                self.iter().map(|(k, _v)| k)
+            }
+
+            pub fn values(&self) -> impl Iterator<Item = &V> {
+                self.iter().map(|(_k, v)| v)
+            }
+
+            pub fn range<Q, R>(
+                &self,
+                range: R,
+            ) -> impl Iterator<Item = (&K, &V)>
+            where
+                K: std::borrow::Borrow<Q>,
+                Q: Ord + ?Sized,
+                R: std::ops::RangeBounds<Q>,
+            {
+                self.inner_range(range).map(|x| (key!(x), val!(x)))
+            }
+
+            pub fn first(&self) -> Option<(&K, &V)> {
+                let x = self.first_node();
+
+                if x.is_some() {
+                    Some((key!(x), val!(x)))
+                }
+                else {
+                    None
+                }
+            }
+
+            pub fn last(&self) -> Option<(&K, &V)> {
+                let x = self.last_node();
+
+                if x.is_some() {
+                    Some((key!(x), val!(x)))
+                }
+                else {
+                    None
+                }
+            }
+
+            pub fn pop_first(&mut self) -> Option<(K, V)>
+            where
+                K: Clone + std::fmt::Debug,
+                $($vbound)+
+            {
+                let k = self.first().map(|(k, _v)| k.clone())?;
+                let v = self.remove(&k).unwrap();
+
+                Some((k, v))
+            }
+
+            pub fn pop_last(&mut self) -> Option<(K, V)>
+            where
+                K: Clone + std::fmt::Debug,
+                $($vbound)+
+            {
+                let k = self.last().map(|(k, _v)| k.clone())?;
+                let v = self.remove(&k).unwrap();
+
+                Some((k, v))
+            }
+
+            ////////////////////////////////////////////////////////////////////
+            //// Iterator Helper
+
+            fn first_node(&self) -> Node<K, V> {
+                if self.root.is_none() {
+                    return Node::none();
+                }
+
+                let mut x = bst_minimum!(self.root);
+
+                while x.is_some() && !{ let $x = &x; $alive } {
+                    x = bst_successor!(x);
+                }
+
+                x
+            }
+
+            fn last_node(&self) -> Node<K, V> {
+                if self.root.is_none() {
+                    return Node::none();
+                }
+
+                let mut x = bst_maximum!(self.root);
+
+                while x.is_some() && !{ let $x = &x; $alive } {
+                    x = bst_predecessor!(x);
+                }
+
+                x
+            }
+
+            /// Return left-most node in the range start or none-node
+            fn lower_bound<Q>(&self, start: std::ops::Bound<&Q>) -> Node<K, V>
+            where
+                K: std::borrow::Borrow<Q>,
+                Q: Ord + ?Sized,
+            {
+                use std::ops::Bound::*;
+
+                let mut x = self.root.clone();
+                let mut y = Node::none();
+
+                while x.is_some() {
+                    let k: &Q = key!(x).borrow();
+
+                    let hit = match start {
+                        Included(start) => k >= start,
+                        Excluded(start) => k > start,
+                        Unbounded => true,
+                    };
+
+                    if hit {
+                        y = x.clone();
+                        x = left!(x);
+                    }
+                    else {
+                        x = right!(x);
+                    }
+                }
+
+                while y.is_some() && !{ let $x = &y; $alive } {
+                    y = bst_successor!(y);
+                }
+
+                y
+            }
+
+            /// Alive nodes in the range
+            fn inner_range<Q, R>(
+                &self,
+                range: R,
+            ) -> impl Iterator<Item = Node<K, V>>
+            where
+                K: std::borrow::Borrow<Q>,
+                Q: Ord + ?Sized,
+                R: std::ops::RangeBounds<Q>,
+            {
+                use std::ops::Bound::*;
+
+                let mut x = self.lower_bound(range.start_bound());
+
+                std::iter::from_fn(move || {
+                    if x.is_none() {
+                        return None;
+                    }
+
+                    let k: &Q = key!(x).borrow();
+
+                    let in_range = match range.end_bound() {
+                        Included(end) => k <= end,
+                        Excluded(end) => k < end,
+                        Unbounded => true,
+                    };
+
+                    if !in_range {
+                        x = Node::none();
+                        return None;
+                    }
+
+                    let ent = x.clone();
+
+                    x = bst_successor!(x);
+
+                    while x.is_some() && !{ let $x = &x; $alive } {
+                        x = bst_successor!(x);
+                    }
+
+                    Some(ent)
+                })
+            }
+        }
+
+        impl<K: Ord, V> IntoIterator for $treename<K, V> {
+            type Item = (K, V);
+            type IntoIter = impl Iterator<Item = Self::Item>;
+
+            fn into_iter(mut self) -> Self::IntoIter {
+                let mut nodes = vec![];
+
+                if self.root.is_some() {
+                    let mut x = bst_minimum!(self.root);
+
+                    while x.is_some() {
+                        if { let $x = &x; $alive } {
+                            nodes.push(x.clone());
+                        }
+
+                        x = bst_successor!(x);
+                    }
+                }
+
+                /* drop all strong links (and marked nodes) */
+
+                let mut x = std::mem::replace(&mut self.root, Node::none());
+                let mut stack = vec![];
+
+                while x.is_some() || !stack.is_empty() {
+                    if x.is_none() {
+                        x = stack.pop().unwrap();
+                    }
+
+                    let left = left!(x);
+                    let right = right!(x);
+
+                    left!(x, Node::none());
+                    right!(x, Node::none());
+
+                    if right.is_some() {
+                        stack.push(right);
+                    }
+
+                    x = left;
+                }
+
+                nodes.into_iter().map(|x| {
+                    std::rc::Rc::try_unwrap(x.0.unwrap())
+                        .ok()
+                        .expect("node is still shared")
+                        .into_inner()
+                        .into_key_val()
+                })
+            }
+        }
+    };
+}
+
+
+/// rank, nth and range_count by subtree size (`impl_size!`), O(log n)
+macro_rules! impl_order_stat {
+    ($treename:ident) => {
+        impl<K: Ord, V> $treename<K, V> {
+            /// Start from 0, Ok if k exists, else Err(insert position)
+            pub fn rank<Q>(&self, k: &Q) -> Result<usize, usize>
+            where
+                K: std::borrow::Borrow<Q>,
+                Q: Ord + ?Sized,
+            {
+                use std::cmp::Ordering::*;
+
+                let mut x = self.root.clone();
+                let mut rk = 0;
+
+                while x.is_some() {
+                    match k.cmp(key!(x).borrow()) {
+                        Less => x = left!(x),
+                        Equal => return Ok(rk + left!(x).size()),
+                        Greater => {
+                            rk += left!(x).size() + 1;
+                            x = right!(x);
+                        }
+                    }
+                }
+
+                Err(rk)
+            }
+
+            /// Return Nth entry (start from 0)
+            pub fn nth(&self, mut idx: usize) -> Option<(&K, &V)> {
+                use std::cmp::Ordering::*;
+
+                let mut x = self.root.clone();
+
+                while x.is_some() {
+                    let left_size = left!(x).size();
+
+                    match idx.cmp(&left_size) {
+                        Less => x = left!(x),
+                        Equal => return Some((key!(x), val!(x))),
+                        Greater => {
+                            idx -= left_size + 1;
+                            x = right!(x);
+                        }
+                    }
+                }
+
+                None
+            }
+
+            /// Count keys in the range
+            pub fn range_count<Q, R>(&self, range: R) -> usize
+            where
+                K: std::borrow::Borrow<Q>,
+                Q: Ord + ?Sized,
+                R: std::ops::RangeBounds<Q>,
+            {
+                use std::ops::Bound::*;
+
+                let lo = match range.start_bound() {
+                    Included(start) => self.count_less(start, false),
+                    Excluded(start) => self.count_less(start, true),
+                    Unbounded => 0,
+                };
+
+                let hi = match range.end_bound() {
+                    Included(end) => self.count_less(end, true),
+                    Excluded(end) => self.count_less(end, false),
+                    Unbounded => self.root.size(),
+                };
+
+                hi.saturating_sub(lo)
+            }
+
+            /// Count keys less than (or equal to) k
+            fn count_less<Q>(&self, k: &Q, or_equal: bool) -> usize
+            where
+                K: std::borrow::Borrow<Q>,
+                Q: Ord + ?Sized,
+            {
+                let mut x = self.root.clone();
+                let mut cnt = 0;
+
+                while x.is_some() {
+                    let xk: &Q = key!(x).borrow();
+
+                    if xk < k || or_equal && xk == k {
+                        cnt += left!(x).size() + 1;
+                        x = right!(x);
+                    }
+                    else {
+                        x = left!(x);
+                    }
+                }
+
+                cnt
+            }
+        }
+    };
+}
+
+
+/// split_off, append and union / intersection / difference on split-join
+///
+/// Tree requires `fn join3(&mut self, l, m, r) -> Node` (l < m < r, all
+/// detached, return detached root), size from `impl_size!`
+macro_rules! impl_split_join {
+    ($treename:ident { $($field:ident),* }) => {
+        impl<K: Ord, V> $treename<K, V> {
+            /// Split into [.., k) and [k, ..), return the latter
+            pub fn split_off<Q>(&mut self, k: &Q) -> Self
+            where
+                K: std::borrow::Borrow<Q>,
+                Q: Ord + ?Sized,
+            {
+                let t = std::mem::replace(&mut self.root, Node::none());
+                let (l, m, mut r) = self.split3(t, k);
+
+                if m.is_some() {
+                    r = self.join3(Node::none(), m, r);
+                }
+
+                self.root = l;
+                self.cnt = self.root.size();
+
+                self.with_root(r)
+            }
+
+            /// Move all entries from other into self, other's value wins.
+            ///
+            /// O(log n) join if keys of self are all less than other,
+            /// else union
+            pub fn append(&mut self, other: &mut Self) {
+                let self_last = self.last_node();
+                let other_first = other.first_node();
+
+                let ordered = self_last.is_none()
+                    || other_first.is_none()
+                    || key!(self_last) < key!(other_first);
+
+                let l = std::mem::replace(&mut self.root, Node::none());
+                let r = std::mem::replace(&mut other.root, Node::none());
+
+                self.root = if ordered {
+                    self.join2(l, r)
+                }
+                else {
+                    self.union_at(r, l)
+                };
+
+                self.cnt = self.root.size();
+                other.cnt = 0;
+            }
+
+            /// Value of self wins
+            pub fn union(mut self, mut other: Self) -> Self {
+                let t1 = std::mem::replace(&mut self.root, Node::none());
+                let t2 = std::mem::replace(&mut other.root, Node::none());
+
+                self.root = self.union_at(t1, t2);
+                self.cnt = self.root.size();
+
+                self
+            }
+
+            /// Value of self wins
+            pub fn intersection(mut self, mut other: Self) -> Self {
+                let t1 = std::mem::replace(&mut self.root, Node::none());
+                let t2 = std::mem::replace(&mut other.root, Node::none());
+
+                self.root = self.intersection_at(t1, t2);
+                self.cnt = self.root.size();
+
+                self
+            }
+
+            pub fn difference(mut self, mut other: Self) -> Self {
+                let t1 = std::mem::replace(&mut self.root, Node::none());
+                let t2 = std::mem::replace(&mut other.root, Node::none());
+
+                self.root = self.difference_at(t1, t2);
+                self.cnt = self.root.size();
+
+                self
+            }
+
+            ////////////////////////////////////////////////////////////////////
+            //// Split Join Helper
+
+            fn with_root(&self, root: Node<K, V>) -> Self {
+                Self {
+                    cnt: root.size(),
+                    root,
+                    $($field: self.$field.clone()),*
+                }
+            }
+
+            /// Detach children of root t
+            fn expose(t: &Node<K, V>) -> (Node<K, V>, Node<K, V>) {
+                let l = left!(t);
+                let r = right!(t);
+
+                disconn!(t, l);
+                disconn!(t, r);
+
+                (l, r)
+            }
+
+            /// Split t into (< k, == k, > k)
+            fn split3<Q>(
+                &mut self,
+                t: Node<K, V>,
+                k: &Q,
+            ) -> (Node<K, V>, Node<K, V>, Node<K, V>)
+            where
+                K: std::borrow::Borrow<Q>,
+                Q: Ord + ?Sized,
+            {
+                use std::cmp::Ordering::*;
+
+                if t.is_none() {
+                    return (Node::none(), Node::none(), Node::none());
+                }
+
+                let (l, r) = Self::expose(&t);
+
+                match k.cmp(key!(t).borrow()) {
+                    Less => {
+                        let (ll, m, lr) = self.split3(l, k);
+
+                        (ll, m, self.join3(lr, t, r))
+                    }
+                    Equal => (l, t, r),
+                    Greater => {
+                        let (rl, m, rr) = self.split3(r, k);
+
+                        (self.join3(l, t, rl), m, rr)
+                    }
+                }
+            }
+
+            /// Return (t - max, max)
+            fn split_last(
+                &mut self,
+                t: Node<K, V>,
+            ) -> (Node<K, V>, Node<K, V>) {
+                let (l, r) = Self::expose(&t);
+
+                if r.is_none() {
+                    (l, t)
+                }
+                else {
+                    let (r, m) = self.split_last(r);
+
+                    (self.join3(l, t, r), m)
+                }
+            }
+
+            /// Join (l, r) where l < r
+            fn join2(&mut self, l: Node<K, V>, r: Node<K, V>) -> Node<K, V> {
+                if l.is_none() {
+                    r
+                }
+                else {
+                    let (l, m) = self.split_last(l);
+
+                    self.join3(l, m, r)
+                }
+            }
+
+            fn union_at(
+                &mut self,
+                t1: Node<K, V>,
+                t2: Node<K, V>,
+            ) -> Node<K, V> {
+                if t1.is_none() {
+                    return t2;
+                }
+                if t2.is_none() {
+                    return t1;
+                }
+
+                let (l2, _, r2) = self.split3(t2, key!(t1));
+                let (l1, r1) = Self::expose(&t1);
+
+                let l = self.union_at(l1, l2);
+                let r = self.union_at(r1, r2);
+
+                self.join3(l, t1, r)
+            }
+
+            fn intersection_at(
+                &mut self,
+                t1: Node<K, V>,
+                t2: Node<K, V>,
+            ) -> Node<K, V> {
+                if t1.is_none() || t2.is_none() {
+                    return Node::none();
+                }
+
+                let (l2, m2, r2) = self.split3(t2, key!(t1));
+                let (l1, r1) = Self::expose(&t1);
+
+                let l = self.intersection_at(l1, l2);
+                let r = self.intersection_at(r1, r2);
+
+                if m2.is_some() {
+                    self.join3(l, t1, r)
+                }
+                else {
+                    self.join2(l, r)
+                }
+            }
+
+            fn difference_at(
+                &mut self,
+                t1: Node<K, V>,
+                t2: Node<K, V>,
+            ) -> Node<K, V> {
+                if t1.is_none() || t2.is_none() {
+                    return t1;
+                }
+
+                let (l2, m2, r2) = self.split3(t2, key!(t1));
+                let (l1, r1) = Self::expose(&t1);
+
+                let l = self.difference_at(l1, l2);
+                let r = self.difference_at(r1, r2);
+
+                if m2.is_some() {
+                    self.join2(l, r)
+                }
+                else {
+                    self.join3(l, t1, r)
+                }
+            }
+        }
+    };
+}
+
+
 macro_rules! impl_tree {
     (
         $(#[$attr:meta])*
@@ -786,6 +1497,7 @@ macro_rules! impl_tree {
                 $name: ident : $ty: ty
             ),*
         }
+        $(where $($bound:tt)+)?
     ) =>
     {
         def_tree!(
@@ -798,6 +1510,7 @@ macro_rules! impl_tree {
             }
         );
         impl_tree_debug!($treename);
+        impl_tree_iter!($treename $(where $($bound)+)?);
 
         impl<K: Ord, V> $treename<K, V> {
             pub fn get<Q>(&self, k: &Q) -> Option<&V>
@@ -1002,6 +1715,316 @@ macro_rules! test_dict {
 }
 
 
+/// Verify rank, nth and range_count against BTreeMap
+#[cfg(test)]
+macro_rules! test_order_stat {
+    ($dict: expr) => {
+        use std::collections::BTreeMap;
+
+        use common::random_range;
+
+        for _ in 0..20 {
+            let mut dict = $dict;
+            let mut bt = BTreeMap::new();
+
+            for _ in 0..random_range!(0..400) {
+                let k = random_range!(0..500);
+
+                assert_eq!(dict.insert(k, k), bt.insert(k, k));
+            }
+
+            for _ in 0..random_range!(0..200) {
+                let k = random_range!(0..500);
+
+                assert_eq!(dict.remove(&k), bt.remove(&k));
+            }
+
+            dict.validate();
+
+            let keys = bt.keys().cloned().collect::<Vec<_>>();
+
+            for (i, (k, v)) in bt.iter().enumerate() {
+                assert_eq!(dict.nth(i), Some((k, v)), "[order stat] nth");
+            }
+            assert_eq!(dict.nth(keys.len()), None, "[order stat] nth");
+
+            for k in -1..501 {
+                assert_eq!(
+                    dict.rank(&k),
+                    keys.binary_search(&k),
+                    "[order stat] rank {k}"
+                );
+            }
+
+            for _ in 0..100 {
+                let lo = random_range!(-1..501);
+                let hi = random_range!(lo..501);
+
+                assert_eq!(dict.range_count(lo..hi), bt.range(lo..hi).count());
+                assert_eq!(
+                    dict.range_count(lo..=hi),
+                    bt.range(lo..=hi).count()
+                );
+                assert_eq!(dict.range_count(..hi), bt.range(..hi).count());
+                assert_eq!(dict.range_count(lo..), bt.range(lo..).count());
+            }
+
+            assert_eq!(dict.range_count::<i32, _>(..), bt.len());
+        }
+    };
+}
+#[cfg(test)]
+pub(crate) use test_order_stat;
+
+
+/// Verify split_off, append and set operations against BTreeMap
+#[cfg(test)]
+macro_rules! test_split_join {
+    ($dict: expr) => {
+        use std::collections::BTreeMap;
+
+        use common::random_range;
+
+        let gen_bt = |n: usize| {
+            (0..random_range!(0..n))
+                .map(|_| (random_range!(0..500), random_range!(0..1000)))
+                .collect::<BTreeMap<i32, i32>>()
+        };
+
+        let build = |bt: &BTreeMap<i32, i32>| {
+            let mut dict = $dict;
+
+            for (k, v) in bt.iter() {
+                dict.insert(*k, *v);
+            }
+
+            dict
+        };
+
+        macro_rules! check {
+            ($dict_:expr, $bt:expr) => {
+                let dict_ = &$dict_;
+
+                dict_.validate();
+
+                assert_eq!(dict_.len(), $bt.len());
+                assert!(dict_.iter().eq($bt.iter()));
+            };
+        }
+
+        for _ in 0..50 {
+            /* split_off and ordered append */
+
+            let mut bt = gen_bt(300);
+            let mut dict = build(&bt);
+            let k = random_range!(-1..501);
+
+            let mut dict2 = dict.split_off(&k);
+            let mut bt2 = bt.split_off(&k);
+
+            check!(dict, bt);
+            check!(dict2, bt2);
+
+            dict.append(&mut dict2);
+            bt.append(&mut bt2);
+
+            check!(dict, bt);
+            check!(dict2, bt2);
+
+            /* unordered append */
+
+            let mut bt2 = gen_bt(300);
+            let mut dict2 = build(&bt2);
+
+            dict.append(&mut dict2);
+            bt.append(&mut bt2);
+
+            check!(dict, bt);
+            check!(dict2, bt2);
+
+            /* set operations */
+
+            let bt1 = gen_bt(300);
+            let bt2 = gen_bt(300);
+
+            let mut union = bt2.clone();
+            union.extend(bt1.clone());
+
+            let (intersection, difference) = bt1
+                .iter()
+                .partition::<BTreeMap<i32, i32>, _>(|(k, _v)| {
+                    bt2.contains_key(*k)
+                });
+
+            check!(build(&bt1).union(build(&bt2)), union);
+            check!(build(&bt1).intersection(build(&bt2)), intersection);
+            check!(build(&bt1).difference(build(&bt2)), difference);
+        }
+    };
+}
+#[cfg(test)]
+pub(crate) use test_split_join;
+
+
+/// Impl `test_suites` mapping traits for `$treename<i32, i32>`
+#[cfg(test)]
+macro_rules! impl_bpt_mapping {
+    ($treename:ident, $new:expr) => {
+        impl std::fmt::Display for $treename<i32, i32> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.debug_write(f)
+            }
+        }
+
+        impl test_suites::Validate for $treename<i32, i32> {
+            fn validate(&self) {
+                self.validate()
+            }
+        }
+
+        impl test_suites::Collection for $treename<i32, i32> {
+            fn len(&self) -> usize {
+                self.len()
+            }
+
+            fn new() -> Self {
+                $new
+            }
+        }
+
+        impl test_suites::MappingIterable for $treename<i32, i32> {
+            type Key = i32;
+            type Value = i32;
+
+            fn iter<'a>(
+                &'a self,
+            ) -> impl Iterator<Item = (&'a Self::Key, &'a Self::Value)> + 'a
+            where
+                Self::Key: 'a,
+                Self::Value: 'a,
+            {
+                self.iter()
+            }
+        }
+
+        impl test_suites::Mapping<i32> for $treename<i32, i32> {
+            fn get(&self, k: &i32) -> Option<&Self::Value> {
+                self.get(k)
+            }
+        }
+
+        impl test_suites::MutableMapping<i32> for $treename<i32, i32> {
+            fn insert(
+                &mut self,
+                key: Self::Key,
+                value: Self::Value,
+            ) -> Option<Self::Value> {
+                self.insert(key, value)
+            }
+
+            fn remove(&mut self, key: &i32) -> Option<Self::Value> {
+                self.remove(key)
+            }
+        }
+
+        impl test_suites::BPTreeMap<i32> for $treename<i32, i32> {
+            fn range<R>(
+                &self,
+                range: R,
+            ) -> impl Iterator<Item = (&Self::Key, &Self::Value)>
+            where
+                R: std::ops::RangeBounds<i32>,
+            {
+                self.range(range)
+            }
+
+            fn range_mut<R>(
+                &mut self,
+                range: R,
+            ) -> impl Iterator<Item = (&Self::Key, &mut Self::Value)>
+            where
+                R: std::ops::RangeBounds<i32>,
+            {
+                self.range_mut(range)
+            }
+        }
+    };
+}
+#[cfg(test)]
+pub(crate) use impl_bpt_mapping;
+
+
+/// Run `test_suites` BPTreeMap suite (with range query) and check the
+/// iteration API against BTreeMap
+#[cfg(test)]
+macro_rules! test_bpt_mapping {
+    ($treename:ident) => {
+        use std::collections::BTreeMap;
+
+        use test_suites::{bpt_mapping::*, *};
+
+        let mut test_suit = BPTreeTestSuite::<
+            _,
+            GenerateI32Any,
+            _,
+            _,
+            $treename<_, _>,
+        >::new_with_loader(DefaultLoader::new());
+
+        test_suit.test_fixeddata();
+        test_suit.test_randomdata(100, 1000);
+
+        /* len, iter_mut, first/last, pop_first/pop_last, into_iter */
+
+        for _ in 0..20 {
+            let mut dict = <$treename<i32, i32> as Collection>::new();
+            let mut bt = BTreeMap::new();
+
+            for _ in 0..common::random_range!(0..300) {
+                let k = common::random_range!(0..500);
+
+                assert_eq!(dict.insert(k, k), bt.insert(k, k));
+            }
+
+            for _ in 0..common::random_range!(0..100) {
+                let k = common::random_range!(0..500);
+
+                assert_eq!(dict.remove(&k), bt.remove(&k));
+            }
+
+            assert_eq!(dict.len(), bt.len());
+            assert_eq!(dict.is_empty(), bt.is_empty());
+
+            for (_k, v) in dict.iter_mut() {
+                *v += 1;
+            }
+            for (_k, v) in bt.iter_mut() {
+                *v += 1;
+            }
+
+            assert!(dict.keys().eq(bt.keys()));
+            assert!(dict.values().eq(bt.values()));
+            assert_eq!(dict.first(), bt.first_key_value());
+            assert_eq!(dict.last(), bt.last_key_value());
+
+            for _ in 0..common::random_range!(0..50) {
+                if common::random_range!(0..2) == 0 {
+                    assert_eq!(dict.pop_first(), bt.pop_first());
+                }
+                else {
+                    assert_eq!(dict.pop_last(), bt.pop_last());
+                }
+            }
+
+            assert_eq!(dict.len(), bt.len());
+            assert!(dict.into_iter().eq(bt.into_iter()));
+        }
+    };
+}
+#[cfg(test)]
+pub(crate) use test_bpt_mapping;
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //// ReExport Declarative Macros
 
@@ -1011,10 +2034,8 @@ use bst_build;
 use bst_delete;
 use bst_flatten;
 use bst_insert;
-#[allow(unused)]
 use bst_maximum;
 use bst_minimum;
-#[allow(unused)]
 use bst_predecessor;
 use bst_search;
 use bst_successor;
@@ -1029,9 +2050,13 @@ use fake_swap;
 use impl_build_cleanup;
 use impl_flatten_cleanup;
 use impl_node_;
+use impl_order_stat;
 use impl_rotate_cleanup;
+use impl_size;
+use impl_split_join;
 use impl_tree;
 use impl_tree_debug;
+use impl_tree_iter;
 use impl_validate;
 use index_of_child;
 use rotate;
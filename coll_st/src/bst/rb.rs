@@ -6,10 +6,12 @@ use super::*;
 
 
 impl_node!();
-impl_node_!({ color: Color });
+impl_node_!({ color: Color, size: usize });
 
 
 impl_tree!(RB {});
+impl_size!();
+impl_order_stat!(RB);
 // impl_tree!(LLRB {});
 
 
@@ -22,17 +24,21 @@ impl_rotate_cleanup!(RB ->
         else {
             debug_assert!(x.is_black() && z.is_black());
         }
+
+        x.update_size();
+        z.update_size();
     }
 );
 impl_validate!(RB ->
     #[cfg(test)]
-    fn validate(&mut self)
+    fn validate(&self)
     where K: Debug
     {
         debug_assert!(self.root.is_black(), "[validate] root should be black");
 
         self.root.validate_rb_rule();
         self.root.validate_black_balance();
+        self.root.validate_size();
     }
 );
 
@@ -105,7 +111,8 @@ impl<K: Ord, V> RB <K, V> {
 
     pub fn new() -> Self {
         Self {
-            root: Node::none()
+            root: Node::none(),
+            cnt: 0
         }
     }
 
@@ -122,10 +129,14 @@ impl<K: Ord, V> RB <K, V> {
             color = Red;
         }
 
-        let z = node!({ k, v, color: color });
+        let z = node!({ k, v, color: color, size: 1 });
 
         let popped = bst_insert!(self, z.clone());
 
+        if popped.is_none() {
+            z.update_size_to_root();
+        }
+
         self.fix_red_violation(z);
 
         popped
@@ -153,6 +164,7 @@ impl<K: Ord, V> RB <K, V> {
 
             let child = child!(z, if left!(z).is_none() { Right } else { Left });
             subtree_shift!(self, z, child);
+            p.update_size_to_root();
 
             if z.is_black() {
                 if child.is_red() {
@@ -163,6 +175,8 @@ impl<K: Ord, V> RB <K, V> {
                 }
             }
 
+            self.cnt -= 1;
+
             Some(unwrap_into!(z).into_value())
         }
     }
@@ -460,4 +474,16 @@ mod tests {
     fn test_bst_rb_random() {
         test_dict!(RB::new());
     }
+
+    impl_bpt_mapping!(RB, RB::new());
+
+    #[test]
+    fn test_bst_rb_bpt_mapping() {
+        test_bpt_mapping!(RB);
+    }
+
+    #[test]
+    fn test_bst_rb_order_stat() {
+        test_order_stat!(RB::new());
+    }
 }
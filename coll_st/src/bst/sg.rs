@@ -11,7 +11,6 @@ impl_tree!(
     /// Scapegoat Tree
     SG
     {
-        cnt: usize,
         /// nodes count including marked
         max_cnt: usize,
         alpha: f32
@@ -52,7 +51,6 @@ impl<K: Ord, V> SG <K, V> {
         let popped = bst_insert!(self, z.clone());
 
         if popped.is_none() {
-            self.cnt += 1;
             self.max_cnt = max(self.cnt, self.max_cnt);
         }
 
@@ -201,4 +199,11 @@ mod tests {
         test_dict!(SG::new(0.6));
     }
 
+    impl_bpt_mapping!(SG, SG::new(0.7));
+
+    #[test]
+    fn test_bst_sg_bpt_mapping() {
+        test_bpt_mapping!(SG);
+    }
+
 }
@@ -6,12 +6,25 @@ use super::*;
 
 
 impl_node!();
-impl_node_!({});
+impl_node_!({ size: usize });
 def_tree!(Splay {});
 impl_tree_debug!(Splay);
-
-impl_rotate_cleanup!(Splay);
-impl_validate!(Splay -> empty);
+impl_tree_iter!(Splay);
+impl_size!();
+impl_order_stat!(Splay);
+
+impl_rotate_cleanup!(Splay ->
+    fn rotate_cleanup(&self, x: Node<K, V>, z: Node<K, V>) {
+        x.update_size();
+        z.update_size();
+    }
+);
+impl_validate!(Splay ->
+    #[cfg(test)]
+    fn validate(&self) {
+        self.root.validate_size();
+    }
+);
 
 
 
@@ -23,7 +36,8 @@ impl<K: Ord, V> Splay <K, V> {
 
     pub fn new() -> Self {
         Self {
-            root: Node::none()
+            root: Node::none(),
+            cnt: 0
         }
     }
 
@@ -84,25 +98,29 @@ impl<K: Ord, V> Splay <K, V> {
         let splay_at;
 
         if y.is_none() {
-            splay_at = node!({ k, v });
+            splay_at = node!({ k, v, size: 1 });
             self.root = splay_at.clone();
+            self.cnt += 1;
         } else {
             match k.cmp(key!(y)) {
                 Less => {
-                    splay_at = node!({ k, v });
+                    splay_at = node!({ k, v, size: 1 });
                     conn_left!(y, splay_at);
+                    self.cnt += 1;
                 }
                 Equal => {
                     popped = Some(replace_val!(y, v));
                     splay_at = y;
                 },
                 Greater => {
-                    splay_at = node!({ k, v });
+                    splay_at = node!({ k, v, size: 1 });
                     conn_right!(y, splay_at);
+                    self.cnt += 1;
                 }
             }
         }
 
+        splay_at.update_size_to_root();
         self.splay(&splay_at);
 
         popped
@@ -123,15 +141,53 @@ impl<K: Ord, V> Splay <K, V> {
             disconn!(s, s_left);
 
             self.join((s_left, l));
+            self.cnt -= 1;
 
             Some(unwrap_into!(s).into_value())
         }
     }
 
+    /// Split into [.., k) and [k, ..), return the latter
+    pub fn split_off<Q>(&mut self, k: &Q) -> Self
+    where K: Borrow<Q>, Q: Ord + ?Sized
+    {
+        let l = self.split_before(k);
+        let r = std::mem::replace(&mut self.root, l);
+
+        self.cnt = self.root.size();
+
+        Self { cnt: r.size(), root: r }
+    }
+
+    /// Move all entries from other into self, other's value wins.
+    ///
+    /// It's just a join if keys of self are all less than other
+    pub fn append(&mut self, other: &mut Self) {
+        let this = std::mem::replace(self, Self::new());
+        let other = std::mem::replace(other, Self::new());
+
+        *self = other.merge(this, |a, b| a || b);
+    }
+
+    /// Value of self wins
+    pub fn union(self, other: Self) -> Self {
+        self.merge(other, |a, b| a || b)
+    }
+
+    /// Value of self wins
+    pub fn intersection(self, other: Self) -> Self {
+        self.merge(other, |a, b| a && b)
+    }
+
+    pub fn difference(self, other: Self) -> Self {
+        self.merge(other, |a, b| a && !b)
+    }
+
     ////////////////////////////////////////////////////////////////////////////
     //// Helper Method
 
-    /// rotate x to root
+    /// rotate x to root by zig-zig/zig-zag, that halves the depth of the
+    /// access path (plain rotating up to root doesn't)
     fn splay(&mut self, x: &Node<K, V>)
     {
         debug_assert!(x.is_some());
@@ -139,7 +195,20 @@ impl<K: Ord, V> Splay <K, V> {
         let mut p = paren!(x).upgrade();
 
         while p.is_some() {
-            rotate!(self, p, index_of_child!(p, x).rev());
+            let g = paren!(p).upgrade();
+            let x_dir = index_of_child!(p, x);
+
+            if g.is_none() {
+                rotate!(self, p, x_dir.rev());
+            }
+            else if index_of_child!(g, p) == x_dir {
+                rotate!(self, g, x_dir.rev());
+                rotate!(self, p, x_dir.rev());
+            }
+            else {
+                double_rotate!(self, g, x_dir);
+            }
+
             p = paren!(x).upgrade();
         }
     }
@@ -152,10 +221,103 @@ impl<K: Ord, V> Splay <K, V> {
 
         let x_right = right!(x);
         disconn!(x, x_right);
+        x.update_size();
 
         (x.to_owned(), x_right)
     }
 
+    /// Split off [.., k) and return it, self keeps [k, ..)
+    fn split_before<Q>(&mut self, k: &Q) -> Node<K, V>
+    where K: Borrow<Q>, Q: Ord + ?Sized
+    {
+        /* find the max one < k */
+
+        let mut x = self.root.clone();
+        let mut p = Node::none();
+
+        while x.is_some() {
+            if key!(x).borrow() < k {
+                p = x.clone();
+                x = right!(x);
+            }
+            else {
+                x = left!(x);
+            }
+        }
+
+        if p.is_none() {
+            return Node::none();
+        }
+
+        let (l, r) = self.split(p);
+        self.root = r;
+
+        l
+    }
+
+    /// Join t after all nodes of self
+    fn join_right(&mut self, t: Node<K, V>) {
+        let s = std::mem::replace(&mut self.root, Node::none());
+
+        self.join((s, t));
+    }
+
+    /// Merge two trees run by run (split the longest prefix of one side that
+    /// less than the min of the other side), O(r log n) for r runs.
+    ///
+    /// `keep(in self, in other)`: if keep the key, value of self wins.
+    fn merge(mut self, mut other: Self, keep: fn(bool, bool) -> bool) -> Self
+    {
+        use std::cmp::Ordering::*;
+
+        let mut res = Self::new();
+
+        while self.root.is_some() && other.root.is_some() {
+            let a = bst_minimum!(self.root);
+            let b = bst_minimum!(other.root);
+
+            match key!(a).cmp(key!(b)) {
+                Less => {
+                    let run = self.split_before(key!(b));
+
+                    if keep(true, false) {
+                        res.join_right(run);
+                    }
+                }
+                Equal => {
+                    let (a, r) = self.split(a);
+                    self.root = r;
+
+                    let (_b, r) = other.split(b);
+                    other.root = r;
+
+                    if keep(true, true) {
+                        res.join_right(a);
+                    }
+                }
+                Greater => {
+                    let run = other.split_before(key!(a));
+
+                    if keep(false, true) {
+                        res.join_right(run);
+                    }
+                }
+            }
+        }
+
+        if keep(true, false) {
+            res.join_right(std::mem::replace(&mut self.root, Node::none()));
+        }
+
+        if keep(false, true) {
+            res.join_right(std::mem::replace(&mut other.root, Node::none()));
+        }
+
+        res.cnt = res.root.size();
+
+        res
+    }
+
     /// Join (S, L) tree
     fn join(&mut self, trees: (Node<K, V>, Node<K, V>))
     {
@@ -178,6 +340,7 @@ impl<K: Ord, V> Splay <K, V> {
             self.splay(&s_max);
             self.root = s_max.clone(); //  s maybe not root node
             conn_right!(s_max, l);
+            s_max.update_size();
         }
         else {
             self.root = l;
@@ -189,6 +352,25 @@ impl<K: Ord, V> Splay <K, V> {
 
 
 
+/// Splay tree can be a long chain (e.g. sorted inserts), drop it without
+/// recursion
+impl<K, V> Drop for Splay<K, V> {
+    fn drop(&mut self) {
+        let mut stack = vec![std::mem::replace(&mut self.root, Node::none())];
+
+        while let Some(x) = stack.pop() {
+            if x.is_some() {
+                stack.push(left!(x));
+                stack.push(right!(x));
+
+                left!(x, Node::none());
+                right!(x, Node::none());
+            }
+        }
+    }
+}
+
+
 impl<K: Debug, V> Debug for Node<K, V> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if self.is_some() {
@@ -231,4 +413,58 @@ mod tests {
     fn test_bst_splay_random() {
         test_dict!(Splay::new());
     }
+
+    impl_bpt_mapping!(Splay, Splay::new());
+
+    #[test]
+    fn test_bst_splay_bpt_mapping() {
+        test_bpt_mapping!(Splay);
+    }
+
+    #[test]
+    fn test_bst_splay_order_stat() {
+        test_order_stat!(Splay::new());
+    }
+
+    #[test]
+    fn test_bst_splay_split_join() {
+        test_split_join!(Splay::new());
+    }
+
+    /// Sorted inserts leave a chain, split and join shouldn't recurse on it
+    #[test]
+    fn test_bst_splay_split_join_chain() {
+        let n = 100_000;
+        let mut dict = Splay::new();
+
+        for i in 0..n {
+            dict.insert(i, i);
+        }
+
+        let mut r = dict.split_off(&(n / 2));
+
+        assert_eq!(dict.len(), n / 2);
+        assert_eq!(r.len(), n - n / 2);
+        assert_eq!(dict.last(), Some((&(n / 2 - 1), &(n / 2 - 1))));
+        assert_eq!(r.first(), Some((&(n / 2), &(n / 2))));
+
+        dict.append(&mut r);
+
+        assert!(r.is_empty());
+        assert!(dict.keys().cloned().eq(0..n));
+
+        /* interleaved keys */
+
+        let mut odd = Splay::new();
+
+        for i in (1..2 * n).step_by(2) {
+            odd.insert(i, 0);
+        }
+
+        dict.append(&mut odd);
+
+        assert_eq!(dict.len(), n + n / 2);
+        assert_eq!(dict.get(&(n + 1)), Some(&0));
+        assert_eq!(dict.get(&n), None);
+    }
 }
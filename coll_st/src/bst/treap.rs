@@ -11,12 +11,29 @@ def_attr_macro!(clone| w);
 
 
 impl_node!();
-impl_node_!({ w: usize });
+impl_node_!({ w: usize, size: usize });
 def_tree!(Treap { improve_search: bool });
 impl_tree_debug!(Treap);
-
-impl_rotate_cleanup!(Treap);
-impl_validate!(Treap);
+impl_tree_iter!(Treap);
+impl_size!();
+impl_order_stat!(Treap);
+impl_split_join!(Treap { improve_search });
+
+impl_rotate_cleanup!(Treap ->
+    fn rotate_cleanup(&self, x: Node<K, V>, z: Node<K, V>) {
+        x.update_size();
+        z.update_size();
+    }
+);
+impl_validate!(Treap ->
+    #[cfg(test)]
+    fn validate(&self) {
+        if self.root.is_some() {
+            self.root.validate();
+            self.root.validate_size();
+        }
+    }
+);
 
 
 
@@ -28,6 +45,7 @@ impl<K: Ord, V> Treap <K, V> {
     pub fn new() -> Self {
         Self {
             root: Node::none(),
+            cnt: 0,
             improve_search: false
         }
     }
@@ -74,11 +92,12 @@ impl<K: Ord, V> Treap <K, V> {
     pub fn insert(&mut self, k: K, v: V) -> Option<V>
     where V: Default
     {
-        let z = node!({ k, v, w: random() });
+        let z = node!({ k, v, w: random(), size: 1 });
 
         let popped = bst_insert!(self, z.clone());
 
         if popped.is_none() {
+            z.update_size_to_root();
             self.siftup(z);
         }
 
@@ -96,9 +115,15 @@ impl<K: Ord, V> Treap <K, V> {
         }
         else {
             if left!(z).is_none() {
+                let p = paren!(z).upgrade();
+
                 subtree_shift!(self, z, right!(z));
+                p.update_size_to_root();
             } else if right!(z).is_none() {
+                let p = paren!(z).upgrade();
+
                 subtree_shift!(self, z, left!(z));
+                p.update_size_to_root();
             } else {
                 /* case-1       case-2
 
@@ -113,18 +138,25 @@ impl<K: Ord, V> Treap <K, V> {
                 */
 
                 let y = bst_successor!(z);
+                let mut retracing_entry = y.clone();
 
                 if !right!(z).rc_eq(&y) {
+                    retracing_entry = paren!(y).upgrade();
+
                     subtree_shift!(self, y, right!(y));
                     conn_right!(y, right!(z));
                 }
                 subtree_shift!(self, z, y);
                 conn_left!(y, left!(z));
 
+                retracing_entry.update_size_to_root();
+
                 /* Only y and y.left and maybe y.right violate weight */
                 self.siftdown(y);
             }
 
+            self.cnt -= 1;
+
             Some(unwrap_into!(z).into_value())
         }
     }
@@ -173,6 +205,47 @@ impl<K: Ord, V> Treap <K, V> {
         }
     }
 
+    /// Join (l, m, r) where l < m < r, return new root
+    ///
+    /// the root with max weight goes top
+    fn join3(
+        &mut self,
+        l: Node<K, V>,
+        m: Node<K, V>,
+        r: Node<K, V>,
+    ) -> Node<K, V> {
+        let m_top = (l.is_none() || w!(m) >= w!(l))
+            && (r.is_none() || w!(m) >= w!(r));
+
+        if m_top {
+            conn_left!(m, l);
+            conn_right!(m, r);
+            m.update_size();
+
+            m
+        }
+        else if r.is_none() || l.is_some() && w!(l) >= w!(r) {
+            let l_right = right!(l);
+            disconn!(l, l_right);
+
+            let t = self.join3(l_right, m, r);
+            conn_right!(l, t);
+            l.update_size();
+
+            l
+        }
+        else {
+            let r_left = left!(r);
+            disconn!(r, r_left);
+
+            let t = self.join3(l, m, r_left);
+            conn_left!(r, t);
+            r.update_size();
+
+            r
+        }
+    }
+
     /// https://en.wikipedia.org/wiki/Treap
     fn aragon_seidel_search_suggestion(&self, x: Node<K, V>) {
         let neww = random();
@@ -251,5 +324,22 @@ mod tests {
         test_dict!(Treap::new().improve_search());
     }
 
+    impl_bpt_mapping!(Treap, Treap::new());
+
+    #[test]
+    fn test_bst_treap_bpt_mapping() {
+        test_bpt_mapping!(Treap);
+    }
+
+    #[test]
+    fn test_bst_treap_order_stat() {
+        test_order_stat!(Treap::new());
+    }
+
+    #[test]
+    fn test_bst_treap_split_join() {
+        test_split_join!(Treap::new());
+    }
+
 
 }
@@ -1,12 +1,13 @@
 use std::{fmt::Debug, fs::File, ops::Range, path::PathBuf, str::FromStr};
 
 use clap::Parser;
-use minghu6::{
-    collections::graph::{Graph, GraphGenOptions},
-    debug::graphviz::RenderOptions,
+use coll_graph::{
+    debug::graphviz::RenderOptions, test::GraphGenOptions, Graph,
 };
+use common::rand::{random, rngs::StdRng, SeedableRng};
 
 
+/// cargo run --example graphgen -- usp 30 4 1~15 abc
 #[derive(Parser)]
 #[clap()]
 struct Args {
@@ -15,12 +16,16 @@ struct Args {
     /// Candicates:
     ///
     /// + usp -- undirected graph used in shortest path
+    ///
+    /// + dsp -- (weak connected) directed graph used in shortest path,
+    ///   no negative cycle
     #[clap()]
     shape: GraphShape,
 
     #[clap()]
     vn: usize,
 
+    /// 0-10, 10 means complete graph
     #[clap()]
     sparsity: usize,
 
@@ -29,12 +34,20 @@ struct Args {
     /// + 2~4: [2, 4)
     /// + -10~5
     /// + -10~-5
-    #[clap()]
+    #[clap(allow_hyphen_values = true)]
     wrange: MyRange<isize>,
 
     #[clap(short = 'o', default_value = ".")]
     outdir: PathBuf,
 
+    /// generate n graphs: abc_0, abc_1, ...
+    #[clap(short = 'n')]
+    batch: Option<usize>,
+
+    /// random if absent, print it to stderr for reproducing
+    #[clap(long)]
+    seed: Option<u64>,
+
     /// abc -> abc.dot && abc.csv
     #[clap()]
     r#fn: PathBuf,
@@ -45,6 +58,8 @@ struct Args {
 enum GraphShape {
     /// Undirected SHortest Path
     USP,
+    /// Directed SHortest Path
+    DSP,
 }
 
 #[derive(Clone)]
@@ -61,6 +76,7 @@ impl FromStr for GraphShape {
 
         Ok(match s {
             "usp" => Self::USP,
+            "dsp" => Self::DSP,
             _ => return Err(format!("Unkonw ident {s}")),
         })
     }
@@ -76,21 +92,25 @@ impl GraphShape {
                     weight_edge: true,
                 },
             ),
+            GraphShape::DSP => (
+                GraphGenOptions::dir_conn().non_negative_cycle(),
+                RenderOptions {
+                    dir: true,
+                    weight_edge: true,
+                },
+            ),
         }
     }
 }
 
 impl<T, E> FromStr for MyRange<T>
 where
-    T: FromStr<Err = E>,
+    T: FromStr<Err = E> + PartialOrd,
     E: Debug,
 {
     type Err = String;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err>
-    where
-        <T as FromStr>::Err: Debug,
-    {
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         let parts: Vec<&str> = s.split("~").collect();
 
         let fail = || format!("failed {s}");
@@ -99,8 +119,12 @@ where
             return Err(fail());
         }
 
-        let start: T = parts[0].parse().unwrap();
-        let end: T = parts[1].parse().unwrap();
+        let start: T = parts[0].trim().parse().map_err(|_| fail())?;
+        let end: T = parts[1].trim().parse().map_err(|_| fail())?;
+
+        if start >= end {
+            return Err(format!("empty range {s}"));
+        }
 
         Ok(Self(start..end))
     }
@@ -117,18 +141,45 @@ fn main() {
     let wrange = cli.wrange;
     let shape = cli.shape;
 
+    assert!(vn >= 2, "vn should be at least 2");
+    assert!(sparsity <= 10, "sparsity should be in 0-10");
+
+    let seed = cli.seed.unwrap_or_else(random);
+    eprintln!("seed: {seed}");
+
+    let mut rng = StdRng::seed_from_u64(seed);
     let (gen_opt, render_opt) = shape.get_config();
-    let g = Graph::generate(&gen_opt, vn, sparsity, wrange.0);
-
-    let mut csv_fn = cli.r#fn.clone();
-    csv_fn.set_extension("csv");
-    let csv_path = cli.outdir.join(csv_fn);
-    g.write_to_csv(&mut File::create(csv_path).unwrap())
-        .unwrap();
-
-    let mut dot_fn = cli.r#fn.clone();
-    dot_fn.set_extension("dot");
-    let dot_path = cli.outdir.join(dot_fn);
-    g.render(&render_opt, &mut File::create(dot_path).unwrap())
-        .unwrap();
+
+    let fns = match cli.batch {
+        Some(n) => (0..n)
+            .map(|i| {
+                let mut name = cli.r#fn.clone().into_os_string();
+                name.push(format!("_{i}"));
+                PathBuf::from(name)
+            })
+            .collect(),
+        None => vec![cli.r#fn.clone()],
+    };
+
+    for r#fn in fns {
+        let g = Graph::generate_with_rng(
+            &gen_opt,
+            vn,
+            sparsity,
+            wrange.0.clone(),
+            &mut rng,
+        );
+
+        let mut csv_fn = r#fn.clone();
+        csv_fn.set_extension("csv");
+        let csv_path = cli.outdir.join(csv_fn);
+        g.write_to_csv(&mut File::create(csv_path).unwrap())
+            .unwrap();
+
+        let mut dot_fn = r#fn.clone();
+        dot_fn.set_extension("dot");
+        let dot_path = cli.outdir.join(dot_fn);
+        g.render(&render_opt, &mut File::create(dot_path).unwrap())
+            .unwrap();
+    }
 }